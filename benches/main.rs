@@ -0,0 +1,101 @@
+//! A minimal benchmark harness, to avoid pulling in any dependencies. Run with `cargo bench`.
+
+use std::{
+    future::{ready, Future},
+    hint::black_box,
+    pin::Pin,
+    sync::mpsc,
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up, and figure out roughly how many iterations fit into our time budget
+    let start = Instant::now();
+    let mut iters = 0u32;
+    while start.elapsed() < Duration::from_millis(100) {
+        f();
+        iters += 1;
+    }
+
+    let start = Instant::now();
+    for _ in 0..iters * 10 {
+        f();
+    }
+    let per_iter = start.elapsed() / (iters * 10);
+    println!("{:<28} {:>12?}/iter", name, per_iter);
+}
+
+// A future that hands its waker to a closure when first polled, then completes on the next poll.
+struct WithWaker<F>(Option<F>);
+
+impl<F: FnOnce(&Waker) + Unpin> Future for WithWaker<F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        match self.0.take() {
+            Some(f) => {
+                f(cx.waker());
+                Poll::Pending
+            }
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+// An opaque call to `block_on`, to measure what inlining it saves.
+#[inline(never)]
+fn block_on_no_inline<F: Future>(fut: F) -> F::Output {
+    pollster::block_on(fut)
+}
+
+fn main() {
+    bench("poll_ready", || {
+        black_box(pollster::block_on(ready(black_box(42))));
+    });
+
+    bench("poll_ready_noop_waker", || {
+        black_box(pollster::block_on_ready(ready(black_box(42))));
+    });
+
+    bench("poll_ready_no_inline", || {
+        black_box(block_on_no_inline(ready(black_box(42))));
+    });
+
+    bench("waker_clone", || {
+        pollster::block_on(WithWaker(Some(|waker: &Waker| {
+            let waker = black_box(waker.clone());
+            waker.wake();
+        })));
+    });
+
+    bench("wake_by_ref", || {
+        pollster::block_on(WithWaker(Some(|waker: &Waker| waker.wake_by_ref())));
+    });
+
+    // Threads that block exactly once, where the cached waker is never reused
+    bench("spawn_block_once", || {
+        thread::spawn(|| black_box(pollster::block_on(ready(black_box(42)))))
+            .join()
+            .unwrap();
+    });
+
+    bench("spawn_block_once_uncached", || {
+        thread::spawn(|| black_box(pollster::block_on_uncached(ready(black_box(42)))))
+            .join()
+            .unwrap();
+    });
+
+    let (tx, rx) = mpsc::channel::<Waker>();
+    let waker_thread = thread::spawn(move || {
+        for waker in rx {
+            waker.wake();
+        }
+    });
+    bench("wait_for_thread", || {
+        pollster::block_on(WithWaker(Some(|waker: &Waker| tx.send(waker.clone()).unwrap())));
+    });
+    drop(tx);
+    waker_thread.join().unwrap();
+}
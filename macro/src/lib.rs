@@ -1,12 +1,17 @@
 #![doc = include_str!("../README.md")]
 
 use std::iter::FromIterator;
-use std::str::FromStr;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Error, Expr, ExprLit, ExprPath, ItemFn, Lit, MetaNameValue, Result};
+use syn::{
+    Block, Error, Expr, ExprAwait, ExprGroup, ExprLit, ExprPath, ExprReturn, FnArg, Ident, ItemFn,
+    Lit, Meta, MetaNameValue, Pat, PatIdent, Path, Result, ReturnType, Stmt, Token, Type, TypePath,
+    TypeReference,
+};
 
 /// Uses [`pollster::block_on`] to enable `async fn main() {}`.
 ///
@@ -21,7 +26,41 @@ use syn::{Error, Expr, ExprLit, ExprPath, ItemFn, Lit, MetaNameValue, Result};
 /// }
 /// ```
 ///
+/// # Command-line arguments
+///
+/// With `args = "Trait"`, `main` can instead take a single argument, which is parsed by calling `Trait::parse()` on
+/// its type before blocking on the body. This fits [`clap::Parser`], but any trait with an associated
+/// `fn parse() -> Self` works:
+///
+/// ```ignore
+/// #[derive(clap::Parser)]
+/// struct Args {
+///     #[arg(long)]
+///     name: String,
+/// }
+///
+/// #[pollster::main(args = "clap::Parser")]
+/// async fn main(args: Args) {
+///     println!("Hello, {}!", args.name);
+/// }
+/// ```
+///
+/// # Blocking on the body directly
+///
+/// With `into_future`, the body must be a single expression, which is blocked on directly in place of the function's
+/// own `async` body. The expression can be anything that implements [`IntoFuture`] with the function's return type as
+/// its output, such as a request builder that isn't itself a future:
+///
+/// ```ignore
+/// #[pollster::main(into_future)]
+/// async fn main() -> Response {
+///     client.get("https://example.com")
+/// }
+/// ```
+///
 /// [`pollster::block_on`]: https://docs.rs/pollster/0.3.0/pollster/fn.block_on.html
+/// [`clap::Parser`]: https://docs.rs/clap/latest/clap/trait.Parser.html
+/// [`IntoFuture`]: std::future::IntoFuture
 #[proc_macro_attribute]
 pub fn main(
     attr: proc_macro::TokenStream,
@@ -30,7 +69,7 @@ pub fn main(
     let item = TokenStream::from(item);
     let backup = item.clone();
 
-    match common(attr.into(), item) {
+    match common(attr.into(), item, Attr::Main) {
         Ok(output) => output.into_token_stream().into(),
         Err(error) => TokenStream::from_iter([error.into_compile_error(), backup]).into(),
     }
@@ -38,6 +77,32 @@ pub fn main(
 
 /// Uses [`pollster::block_on`] to enable `async` on test functions.
 ///
+/// As with [`main`](macro@main), `into_future` blocks on a single-expression body directly.
+///
+/// # Methods
+///
+/// Rust only allows free functions to be tests, so when this is applied to a method (one that takes `self`), the method
+/// becomes an ordinary blocking method instead, without `#[test]`. This lets async checks live on a test fixture, to be
+/// called from tests:
+///
+/// ```ignore
+/// struct Fixture {
+///     db: Database,
+/// }
+///
+/// impl Fixture {
+///     #[pollster::test]
+///     async fn check_user(&self, id: u32) {
+///         assert!(self.db.user(id).await.is_some());
+///     }
+/// }
+///
+/// #[test]
+/// fn user_exists() {
+///     Fixture::new().check_user(1);
+/// }
+/// ```
+///
 /// # Example
 ///
 /// ```ignore
@@ -65,59 +130,503 @@ pub fn test(
 }
 
 fn test_internal(attr: TokenStream, item: TokenStream) -> Result<ItemFn> {
-    let mut item = common(attr, item)?;
-    item.attrs.push(syn::parse_quote! { #[test] });
+    let mut item = common(attr, item, Attr::Test)?;
+    // Methods can't be tests themselves, so a method (say, on a fixture) is left as a blocking method for tests to call
+    if item.sig.receiver().is_none() {
+        item.attrs.push(syn::parse_quote! { #[test] });
+    }
 
     Ok(item)
 }
 
-fn common(attr: TokenStream, item: TokenStream) -> Result<ItemFn> {
+/// Uses [`pollster::block_on`] to write async [Criterion](https://docs.rs/criterion) benchmarks.
+///
+/// The function must take a single `&mut Criterion` argument. Its body becomes the routine being benchmarked, blocked
+/// on once per iteration, under a benchmark with the same name as the function.
+///
+/// # Example
+///
+/// ```ignore
+/// use criterion::{criterion_group, criterion_main, Criterion};
+///
+/// #[pollster::bench]
+/// async fn my_bench(c: &mut Criterion) {
+///     let my_fut = async {};
+///
+///     my_fut.await;
+/// }
+///
+/// criterion_group!(benches, my_bench);
+/// criterion_main!(benches);
+/// ```
+///
+/// This expands to roughly:
+///
+/// ```ignore
+/// fn my_bench(c: &mut Criterion) {
+///     c.bench_function("my_bench", |b| b.iter(|| pollster::block_on(async { /* ... */ })));
+/// }
+/// ```
+///
+/// [`pollster::block_on`]: https://docs.rs/pollster/0.3.0/pollster/fn.block_on.html
+#[proc_macro_attribute]
+pub fn bench(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let item = TokenStream::from(item);
+    let backup = item.clone();
+
+    match bench_internal(attr.into(), item) {
+        Ok(output) => output.into_token_stream().into(),
+        Err(error) => TokenStream::from_iter([error.into_compile_error(), backup]).into(),
+    }
+}
+
+fn bench_internal(attr: TokenStream, item: TokenStream) -> Result<ItemFn> {
     let mut item: ItemFn = syn::parse2(item)?;
+    check_async(&mut item, Attr::Bench)?;
+    let path = Options::parse(attr, Attr::Bench)?.path;
 
-    if item.sig.asyncness.is_some() {
-        item.sig.asyncness = None;
-    } else {
-        return Err(Error::new_spanned(item, "expected function to be async"));
+    let criterion = match criterion_arg(&item) {
+        Some(criterion) => criterion.clone(),
+        None => {
+            return Err(Error::new(
+                item.sig.paren_token.span.join(),
+                "`#[pollster::bench]` functions must take a single `&mut Criterion` argument",
+            ))
+        }
+    };
+
+    // The body's value (if any) is returned from each iteration, so that Criterion can `black_box` it
+    item.sig.output = ReturnType::Default;
+
+    let name = item.sig.ident.to_string();
+    let span = item.span();
+    let block = item.block;
+    item.block = syn::parse_quote_spanned! {
+        span =>
+        {
+            #criterion.bench_function(#name, |b| b.iter(|| #path::block_on(async #block)));
+        }
+    };
+
+    Ok(item)
+}
+
+/// Get the name of a function's only argument, if its type is `&mut Criterion`.
+fn criterion_arg(item: &ItemFn) -> Option<&Ident> {
+    let mut inputs = item.sig.inputs.iter();
+    let arg = match (inputs.next(), inputs.next()) {
+        (Some(FnArg::Typed(arg)), None) => arg,
+        _ => return None,
+    };
+    let is_criterion = match &*arg.ty {
+        Type::Reference(TypeReference {
+            mutability: Some(_),
+            elem,
+            ..
+        }) => match &**elem {
+            Type::Path(TypePath { qself: None, path }) => path
+                .segments
+                .last()
+                .map_or(false, |segment| segment.ident == "Criterion"),
+            _ => false,
+        },
+        _ => false,
+    };
+    match &*arg.pat {
+        Pat::Ident(PatIdent { ident, .. }) if is_criterion => Some(ident),
+        _ => None,
     }
+}
 
-    let path = if attr.is_empty() {
-        quote::quote! { ::pollster }
+/// Returns a future that drives any number of futures concurrently, resolving to a tuple of their outputs once all of
+/// them are ready.
+///
+/// This is [`pollster::join`] for more than two futures, without the nested tuples that come from nesting calls to it.
+/// Every time the returned future is polled, each of the futures that hasn't completed yet is polled once, starting one
+/// further along each time so that none of them always goes first. Each future is dropped as soon as it completes.
+///
+/// # Example
+///
+/// ```
+/// let (a, b, c) = pollster::block_on(pollster::join!(async { 1 }, async { "two" }, std::future::ready(3.0)));
+///
+/// assert_eq!((a, b, c), (1, "two", 3.0));
+/// ```
+///
+/// [`pollster::join`]: https://docs.rs/pollster/0.3.0/pollster/fn.join.html
+#[proc_macro]
+pub fn join(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match join_internal(input.into(), false) {
+        Ok(output) => output.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Returns a future that drives any number of fallible futures concurrently, resolving to a tuple of their outputs
+/// once all of them have succeeded, or to the first error as soon as any of them fails.
+///
+/// This is the fallible version of [`join!`]: every future must resolve to a `Result` with the same error type. When
+/// one fails, the futures that are still pending are dropped straight away, without being polled again.
+///
+/// # Example
+///
+/// ```
+/// use std::future::ready;
+///
+/// let result = pollster::block_on(pollster::try_join!(ready(Ok::<_, &str>(1)), async { Ok("two") }));
+/// assert_eq!(result, Ok((1, "two")));
+///
+/// let result = pollster::block_on(pollster::try_join!(ready(Ok(1)), ready(Err::<(), _>("oh no"))));
+/// assert_eq!(result, Err("oh no"));
+/// ```
+#[proc_macro]
+pub fn try_join(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match join_internal(input.into(), true) {
+        Ok(output) => output.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Expand `join!`, or `try_join!` if `fallible` is set.
+fn join_internal(input: TokenStream, fallible: bool) -> Result<TokenStream> {
+    let futs = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(input)?;
+    let n = futs.len();
+    if n == 0 {
+        return Ok(if fallible {
+            quote::quote! { ::core::future::ready(::core::result::Result::Ok(())) }
+        } else {
+            quote::quote! { ::core::future::ready(()) }
+        });
+    }
+
+    // Mixed-site hygiene, so that the futures passed in can't see (or be shadowed by) the names used here
+    let span = Span::mixed_site();
+    let ident = |name: &str, i: usize| Ident::new(&format!("{}{}", name, i), span);
+    let fut = (0..n).map(|i| ident("fut", i)).collect::<Vec<_>>();
+    let out = (0..n).map(|i| ident("out", i)).collect::<Vec<_>>();
+    let index = 0..n;
+    let (first, cx, i) = (
+        Ident::new("first", span),
+        Ident::new("cx", span),
+        Ident::new("i", span),
+    );
+    let futs = futs.into_iter();
+    // With a single future there's nothing to rotate
+    let (rotation, next, rotate) = if n > 1 {
+        (
+            Some(quote::quote_spanned! { span => let mut #first = 0usize; }),
+            quote::quote_spanned! { span => (#first + #i) % #n },
+            Some(quote::quote_spanned! { span => #first = (#first + 1) % #n; }),
+        )
     } else {
-        let attr: MetaNameValue = syn::parse2(attr)?;
-
-        if attr.path.is_ident("crate") {
-            match attr.value {
-                Expr::Lit(ExprLit {
-                    attrs,
-                    lit: Lit::Str(str),
-                }) if attrs.is_empty() => TokenStream::from_str(&str.value())?,
-                Expr::Path(ExprPath {
-                    attrs,
-                    qself: None,
-                    path,
-                }) if attrs.is_empty() => path.to_token_stream(),
-                _ => {
-                    return Err(Error::new_spanned(
-                        attr.value,
-                        "expected valid path, e.g. `::package_name`",
-                    ))
+        (None, i.to_token_stream(), None)
+    };
+    // What to do with each future's output once it's ready: store it, or (for `try_join!`) fail early on an error
+    let (store, finish) = if fallible {
+        (
+            quote::quote_spanned! {
+                span =>
+                match poll {
+                    ::core::task::Poll::Ready(::core::result::Result::Ok(out)) => {
+                        *out_slot = ::core::option::Option::Some(out);
+                        fut_slot.set(::core::option::Option::None);
+                    }
+                    ::core::task::Poll::Ready(::core::result::Result::Err(err)) => {
+                        // Drop every future that's still pending now, rather than when this one is dropped
+                        #(#fut.set(::core::option::Option::None);)*
+                        return ::core::task::Poll::Ready(::core::result::Result::Err(err));
+                    }
+                    ::core::task::Poll::Pending => {}
                 }
+            },
+            quote::quote_spanned! {
+                span =>
+                ::core::result::Result::Ok((#(#out.take().expect("`try_join!` polled after completion"),)*))
+            },
+        )
+    } else {
+        (
+            quote::quote_spanned! {
+                span =>
+                if let ::core::task::Poll::Ready(out) = poll {
+                    *out_slot = ::core::option::Option::Some(out);
+                    fut_slot.set(::core::option::Option::None);
+                }
+            },
+            quote::quote_spanned! {
+                span =>
+                (#(#out.take().expect("`join!` polled after completion"),)*)
+            },
+        )
+    };
+
+    // The futures are converted straight away, as `join(a, b)` would, and then moved into an `async` block that pins
+    // each of them in place (in an `Option`, so that it can be dropped as soon as it completes) and polls them all
+    // from a single `poll_fn`.
+    Ok(quote::quote_spanned! {
+        span =>
+        {
+            #(let #fut = ::core::future::IntoFuture::into_future(#futs);)*
+            async move {
+                #(
+                    let mut #fut = ::core::pin::pin!(::core::option::Option::Some(#fut));
+                    let mut #out = ::core::option::Option::None;
+                )*
+                #rotation
+                ::core::future::poll_fn(|#cx: &mut ::core::task::Context<'_>| {
+                    for #i in 0..#n {
+                        match #next {
+                            #(
+                                #index => if let ::core::option::Option::Some(fut) = #fut.as_mut().as_pin_mut() {
+                                    let poll = ::core::future::Future::poll(fut, #cx);
+                                    let (fut_slot, out_slot) = (&mut #fut, &mut #out);
+                                    #store
+                                },
+                            )*
+                            _ => ::core::unreachable!(),
+                        }
+                    }
+                    #rotate
+
+                    if #(#fut.is_none())&&* {
+                        ::core::task::Poll::Ready(#finish)
+                    } else {
+                        ::core::task::Poll::Pending
+                    }
+                })
+                .await
+            }
+        }
+    })
+}
+
+/// The attribute being expanded, used to tailor diagnostics.
+#[derive(Clone, Copy)]
+enum Attr {
+    Main,
+    Test,
+    Bench,
+}
+
+impl Attr {
+    fn name(self) -> &'static str {
+        match self {
+            Attr::Main => "#[pollster::main]",
+            Attr::Test => "#[pollster::test]",
+            Attr::Bench => "#[pollster::bench]",
+        }
+    }
+
+    fn sync_note(self) -> &'static str {
+        match self {
+            Attr::Main => "if you don't need `async`, remove `#[pollster::main]`",
+            Attr::Test => "if you don't need `async`, use `#[test]` instead",
+            Attr::Bench => "if you don't need `async`, call `bench_function` directly",
+        }
+    }
+}
+
+/// The arguments passed to an attribute.
+struct Options {
+    /// The path to the `pollster` crate, from `crate = ...`, defaulting to `::pollster`.
+    path: TokenStream,
+    /// The trait used to parse `main`'s argument, from `args = ...`.
+    args: Option<TokenStream>,
+    /// Whether the body is an expression to block on directly, from `into_future`.
+    into_future: bool,
+}
+
+impl Options {
+    fn parse(attr: TokenStream, kind: Attr) -> Result<Self> {
+        let mut options = Options {
+            path: quote::quote! { ::pollster },
+            args: None,
+            into_future: false,
+        };
+
+        let expected = match kind {
+            Attr::Main => "expected `crate`, `args` or `into_future`",
+            Attr::Test => "expected `crate` or `into_future`",
+            Attr::Bench => "expected `crate`",
+        };
+        let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+        for arg in parser.parse2(attr)? {
+            match arg {
+                Meta::NameValue(MetaNameValue { path, value, .. }) if path.is_ident("crate") => {
+                    options.path = path_value(value, "::package_name")?;
+                }
+                Meta::NameValue(MetaNameValue { path, value, .. })
+                    if path.is_ident("args") && matches!(kind, Attr::Main) =>
+                {
+                    options.args = Some(path_value(value, "\"clap::Parser\"")?);
+                }
+                Meta::Path(path)
+                    if path.is_ident("into_future") && !matches!(kind, Attr::Bench) =>
+                {
+                    options.into_future = true;
+                }
+                arg => return Err(Error::new_spanned(arg.path(), expected)),
             }
-        } else {
-            return Err(Error::new_spanned(attr.path, "expected `crate`"));
         }
+
+        Ok(options)
+    }
+}
+
+fn common(attr: TokenStream, item: TokenStream, kind: Attr) -> Result<ItemFn> {
+    let mut item: ItemFn = syn::parse2(item)?;
+    check_async(&mut item, kind)?;
+    let Options {
+        path,
+        args,
+        into_future,
+    } = Options::parse(attr, kind)?;
+    let preamble = match args {
+        Some(parser) => Some(parse_args(&mut item, &parser)?),
+        None => None,
     };
 
     let span = item.span();
     let block = item.block;
-    item.block = syn::parse_quote_spanned! {
-        span =>
+    if into_future {
+        // The body evaluates to something to block on, rather than being the code to run
+        let expr = body_expr(&block).ok_or_else(|| {
+            Error::new_spanned(
+                &block,
+                format!(
+                    "`{}` with `into_future` needs a body that's a single expression",
+                    kind.name()
+                ),
+            )
+        })?;
+        item.block = syn::parse_quote_spanned! {
+            span =>
+            {
+                #preamble
+                #path::block_on(#expr)
+            }
+        };
+        return Ok(item);
+    }
+    item.block = match body_expr(&block) {
+        // The body already blocks on its own async block, so there is nothing for us to wrap. Any other receiver might
+        // `.await` (or be some other type's `block_on`), so it still needs the async context.
+        Some(Expr::MethodCall(call))
+            if call.method == "block_on" && call.args.is_empty() && matches!(*call.receiver, Expr::Async(_)) =>
         {
-            #path::block_on(async {
-                #block
-            })
+            syn::parse_quote_spanned! {
+                span =>
+                {
+                    #preamble
+                    #block
+                }
+            }
+        }
+        // The body just awaits an async block, so block on that directly rather than wrapping it in another one.
+        Some(Expr::Await(ExprAwait { base, .. })) if matches!(**base, Expr::Async(_)) => {
+            syn::parse_quote_spanned! {
+                span =>
+                {
+                    #preamble
+                    #path::block_on(#base)
+                }
+            }
+        }
+        _ => {
+            // A method's body is moved into the async block along with `self`, so that it can consume `self`
+            let capture = item.sig.receiver().map(|_| <Token![move]>::default());
+            syn::parse_quote_spanned! {
+                span =>
+                {
+                    #preamble
+                    #path::block_on(async #capture {
+                        #block
+                    })
+                }
+            }
         }
     };
 
     Ok(item)
 }
+
+/// Check that the function is `async`, and remove the `async` so that it can be given a blocking body.
+fn check_async(item: &mut ItemFn, kind: Attr) -> Result<()> {
+    if item.sig.asyncness.is_some() {
+        item.sig.asyncness = None;
+        Ok(())
+    } else {
+        // Point at the `fn` keyword rather than the whole item, since that's where `async` needs to go
+        Err(Error::new(
+            item.sig.fn_token.span,
+            format!(
+                "add `async` to this function to use `{}`\nnote: {}",
+                kind.name(),
+                kind.sync_note(),
+            ),
+        ))
+    }
+}
+
+/// Remove `main`'s only argument, returning a statement that binds it by parsing it with `parser`.
+fn parse_args(item: &mut ItemFn, parser: &TokenStream) -> Result<Stmt> {
+    let mut inputs = std::mem::take(&mut item.sig.inputs).into_iter();
+    let arg =
+        match (inputs.next(), inputs.next()) {
+            (Some(FnArg::Typed(arg)), None) => arg,
+            _ => return Err(Error::new(
+                item.sig.paren_token.span.join(),
+                "`#[pollster::main(args = ...)]` functions must take a single argument to parse",
+            )),
+        };
+
+    let (pat, ty) = (arg.pat, arg.ty);
+    Ok(syn::parse_quote_spanned! {
+        ty.span() =>
+        let #pat: #ty = <#ty as #parser>::parse();
+    })
+}
+
+/// Get a path from the value of an argument such as `crate = ...`.
+///
+/// Both bare paths (`crate = ::my_crate::pollster`) and string literals (`crate = "::my_crate::pollster"`) are
+/// accepted.
+fn path_value(value: Expr, example: &str) -> Result<TokenStream> {
+    match value {
+        Expr::Lit(ExprLit {
+            attrs,
+            lit: Lit::Str(str),
+        }) if attrs.is_empty() => Ok(str.parse::<Path>()?.to_token_stream()),
+        Expr::Path(ExprPath {
+            attrs,
+            qself: None,
+            path,
+        }) if attrs.is_empty() => Ok(path.to_token_stream()),
+        // Paths passed through a `macro_rules!` macro as a `$path:path` arrive wrapped in an invisible group
+        Expr::Group(ExprGroup { attrs, expr, .. }) if attrs.is_empty() => {
+            path_value(*expr, example)
+        }
+        value => Err(Error::new_spanned(
+            value,
+            format!("expected valid path, e.g. `{}`", example),
+        )),
+    }
+}
+
+/// If the block consists of a single tail expression (or a single `return`), get the expression it evaluates to.
+fn body_expr(block: &Block) -> Option<&Expr> {
+    match block.stmts.as_slice() {
+        [Stmt::Expr(
+            Expr::Return(ExprReturn {
+                expr: Some(expr), ..
+            }),
+            _,
+        )] => Some(expr),
+        [Stmt::Expr(Expr::Return(_), _)] => None,
+        [Stmt::Expr(expr, None)] => Some(expr),
+        _ => None,
+    }
+}
@@ -0,0 +1,69 @@
+extern crate pollster as reexported_pollster;
+
+use std::{
+    future::ready,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+// A stand-in for `criterion::Criterion`, which records the benchmarks that were registered and runs a few iterations
+#[derive(Default)]
+struct Criterion {
+    benches: Vec<(String, Vec<String>)>,
+}
+
+struct Bencher {
+    outputs: Vec<String>,
+}
+
+impl Criterion {
+    fn bench_function(&mut self, id: &str, f: impl FnOnce(&mut Bencher)) -> &mut Self {
+        let mut bencher = Bencher {
+            outputs: Vec::new(),
+        };
+        f(&mut bencher);
+        self.benches.push((id.to_string(), bencher.outputs));
+        self
+    }
+}
+
+impl Bencher {
+    fn iter<O: std::fmt::Debug>(&mut self, mut routine: impl FnMut() -> O) {
+        for _ in 0..3 {
+            self.outputs.push(format!("{:?}", routine()));
+        }
+    }
+}
+
+static ITERS: AtomicU32 = AtomicU32::new(0);
+
+#[pollster::bench]
+async fn count(c: &mut Criterion) {
+    ready(()).await;
+    ITERS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[pollster::bench(crate = reexported_pollster)]
+async fn output(criterion: &mut self::Criterion) -> u32 {
+    ready(42).await
+}
+
+#[test]
+fn bench() {
+    let mut c = Criterion::default();
+    count(&mut c);
+    output(&mut c);
+
+    assert_eq!(ITERS.load(Ordering::Relaxed), 3);
+    let benches = c
+        .benches
+        .iter()
+        .map(|(id, outputs)| (id.as_str(), outputs.join(" ")))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        benches,
+        [
+            ("count", "() () ()".to_string()),
+            ("output", "42 42 42".to_string())
+        ]
+    );
+}
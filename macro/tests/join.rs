@@ -0,0 +1,96 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::{ready, Future},
+    task::{Context, Poll},
+};
+
+#[test]
+fn outputs() {
+    let (a, b, c, d) = pollster::block_on(pollster::join!(
+        ready(1),
+        async { "two" },
+        async { ready(3.0).await },
+        ready(()),
+    ));
+    assert_eq!((a, b, c, d), (1, "two", 3.0, ()));
+
+    let (a,) = pollster::block_on(pollster::join!(ready(1)));
+    assert_eq!(a, 1);
+
+    pollster::block_on(pollster::join!());
+}
+
+#[test]
+fn concurrent() {
+    let log = RefCell::new(Vec::new());
+    let task = |name: char| {
+        let log = &log;
+        async move {
+            for i in 0..3 {
+                log.borrow_mut().push((name, i));
+                pollster::yield_now().await;
+            }
+            name
+        }
+    };
+
+    let outputs = pollster::block_on(pollster::join!(task('a'), task('b'), task('c')));
+    assert_eq!(outputs, ('a', 'b', 'c'));
+
+    // Every future makes progress on every poll, with a different one going first each time
+    let log = log.into_inner();
+    let firsts = log.chunks(3).map(|sweep| sweep[0].0).collect::<Vec<_>>();
+    assert_eq!(firsts, ['a', 'b', 'c']);
+    for (i, sweep) in log.chunks(3).enumerate() {
+        assert!(sweep.iter().all(|&(_, j)| j == i));
+    }
+}
+
+#[test]
+fn hygiene() {
+    // The macro's own bindings don't clash with names used by the futures passed in
+    let (fut0, out0, first) = (ready(1), 2, 3);
+    let outputs = pollster::block_on(pollster::join!(fut0, async move { out0 + first }));
+    assert_eq!(outputs, (1, 5));
+}
+
+#[test]
+fn try_join_ok() {
+    let outputs = pollster::block_on(pollster::try_join!(ready(Ok::<_, &str>(1)), async {
+        pollster::yield_now().await;
+        Ok("two")
+    },));
+    assert_eq!(outputs, Ok((1, "two")));
+}
+
+#[test]
+fn try_join_err() {
+    // Records when it's dropped
+    struct Guard<'a>(&'a Cell<bool>);
+    impl Drop for Guard<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = Cell::new(false);
+    let mut fut = Box::pin(pollster::try_join!(
+        async {
+            let _guard = Guard(&dropped);
+            std::future::pending::<Result<(), &str>>().await
+        },
+        async {
+            pollster::yield_now().await;
+            Err::<(), _>("oh no")
+        },
+        ready(Ok(3)),
+    ));
+
+    let waker = pollster::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert!(!dropped.get());
+    // The pending future is dropped as soon as the error arrives, before the `try_join!` future itself is
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err("oh no")));
+    assert!(dropped.get());
+}
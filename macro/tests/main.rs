@@ -33,8 +33,138 @@ async fn main_crate_str() {
     ready(42).await;
 }
 
+mod reexports {
+    pub use ::pollster;
+}
+
+#[pollster::main(crate = ::reexported_pollster)]
+async fn main_crate_global_path() {
+    ready(42).await;
+}
+
+#[pollster::main(crate = self::reexports::pollster)]
+async fn main_crate_nested_path() {
+    ready(42).await;
+}
+
+#[pollster::main(crate = "crate::reexports::pollster")]
+async fn main_crate_nested_str() {
+    ready(42).await;
+}
+
+// Paths forwarded through another macro, as a crate re-exporting pollster might do
+macro_rules! forward_crate {
+    ($name:ident, $path:path) => {
+        #[pollster::main(crate = $path)]
+        async fn $name() {
+            ready(42).await;
+        }
+    };
+}
+
+forward_crate!(main_crate_forwarded, crate::reexports::pollster);
+
 #[test]
 fn crate_() {
     main_crate_path();
     main_crate_str();
+    main_crate_global_path();
+    main_crate_nested_path();
+    main_crate_nested_str();
+    main_crate_forwarded();
+}
+
+#[pollster::main]
+async fn main_await_async_block() -> i32 {
+    async move { ready(42).await }.await
+}
+
+#[pollster::main]
+#[allow(clippy::needless_return)]
+async fn main_return_await_async_block() -> i32 {
+    return async { ready(42).await }.await;
+}
+
+#[pollster::main]
+async fn main_block_on() -> i32 {
+    use pollster::FutureExt as _;
+
+    async { ready(42).await }.block_on()
+}
+
+#[pollster::main]
+async fn main_await_then_block_on() -> i32 {
+    use pollster::FutureExt as _;
+
+    // Only the receiver is awaited, so the body still needs to be in an async context
+    ready(async { 42 }).await.block_on()
+}
+
+#[test]
+fn single_expr() {
+    assert_eq!(main_await_async_block(), 42);
+    assert_eq!(main_return_await_async_block(), 42);
+    assert_eq!(main_block_on(), 42);
+    assert_eq!(main_await_then_block_on(), 42);
+}
+
+mod cli {
+    // A stand-in for `clap::Parser`
+    pub trait Parser: Sized {
+        fn parse() -> Self;
+    }
+}
+
+struct Args {
+    name: &'static str,
+}
+
+impl cli::Parser for Args {
+    fn parse() -> Self {
+        Args { name: "world" }
+    }
+}
+
+#[pollster::main(args = "cli::Parser")]
+async fn main_args(args: Args) -> String {
+    format!("hello, {}", ready(args.name).await)
+}
+
+#[pollster::main(crate = reexported_pollster, args = cli::Parser)]
+async fn main_args_destructured(Args { name }: Args) -> &'static str {
+    async move { ready(name).await }.await
+}
+
+#[test]
+fn args() {
+    assert_eq!(main_args(), "hello, world");
+    assert_eq!(main_args_destructured(), "world");
+}
+
+// Something that isn't a future itself, but can be turned into one
+struct Request(u32);
+
+impl std::future::IntoFuture for Request {
+    type Output = u32;
+    type IntoFuture = std::future::Ready<u32>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        ready(self.0 * 2)
+    }
+}
+
+#[pollster::main(into_future)]
+async fn main_into_future() -> u32 {
+    Request(21)
+}
+
+#[pollster::main(crate = reexported_pollster, into_future, args = cli::Parser)]
+async fn main_into_future_args(args: Args) -> &'static str {
+    ready(args.name)
+}
+
+#[test]
+fn into_future() {
+    assert_eq!(main_into_future(), 42);
+    assert_eq!(main_into_future_args(), "world");
 }
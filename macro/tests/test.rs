@@ -25,3 +25,42 @@ async fn crate_path() {
 async fn crate_str() {
     ready(42).await;
 }
+
+#[pollster::test(crate = ::reexported_pollster)]
+async fn crate_global_path() {
+    ready(42).await;
+}
+
+#[pollster::test(into_future)]
+async fn into_future() -> Result<(), std::io::Error> {
+    ready(Ok(()))
+}
+
+struct Fixture {
+    value: u32,
+}
+
+impl Fixture {
+    #[pollster::test]
+    async fn check(&self, expected: u32) {
+        assert_eq!(ready(self.value).await, expected);
+    }
+
+    #[pollster::test]
+    async fn consume(self) -> u32 {
+        ready(self.value).await
+    }
+
+    #[pollster::test(into_future)]
+    async fn value(&mut self) -> u32 {
+        ready(self.value)
+    }
+}
+
+#[test]
+fn fixture_methods() {
+    let mut fixture = Fixture { value: 42 };
+    fixture.check(42);
+    assert_eq!(fixture.value(), 42);
+    assert_eq!(fixture.consume(), 42);
+}
@@ -0,0 +1,67 @@
+//! Tagging panics with the pollster context they happened in.
+
+use std::{cell::Cell, future::IntoFuture, panic, sync::Once, thread};
+
+use crate::block_on;
+
+thread_local! {
+    // How many calls to `block_on_annotated` are currently blocking this thread
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Block the thread until the future is ready, as with [`block_on`], annotating any panic that happens while the
+/// future is being polled with the name of the thread.
+///
+/// When many worker threads are each blocking on a different future, this makes it easier to tell where a panic in
+/// the logs came from. After the usual panic message, a note like this is printed to stderr:
+///
+/// ```text
+/// note: thread 'worker-3' panicked while blocked in pollster
+/// ```
+///
+/// The panic itself is left untouched, and still unwinds out of this function.
+///
+/// The first call installs a process-wide [panic hook](std::panic::set_hook) that runs the previously installed hook
+/// before adding the note, and only adds it on threads that are currently inside `block_on_annotated`. Other threads,
+/// and this thread once the call returns, are unaffected. Replacing the panic hook afterwards removes the annotation.
+///
+/// # Example
+///
+/// ```
+/// let worker = std::thread::Builder::new()
+///     .name("worker-3".into())
+///     .spawn(|| pollster::block_on_annotated(async { panic!("oh no") }))
+///     .unwrap();
+///
+/// assert!(worker.join().is_err());
+/// ```
+pub fn block_on_annotated<F: IntoFuture>(fut: F) -> F::Output {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            previous(info);
+            // The thread might be panicking while its thread-locals are being destroyed
+            if DEPTH.try_with(Cell::get).unwrap_or(0) > 0 {
+                eprintln!(
+                    "note: thread '{}' panicked while blocked in pollster",
+                    thread::current().name().unwrap_or("<unnamed>"),
+                );
+            }
+        }));
+    });
+
+    // Decrement on the way out, including when unwinding
+    struct Entered;
+
+    impl Drop for Entered {
+        fn drop(&mut self) {
+            DEPTH.with(|depth| depth.set(depth.get() - 1));
+        }
+    }
+
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _entered = Entered;
+    block_on(fut)
+}
@@ -0,0 +1,261 @@
+//! A builder for composing `block_on` options.
+
+use std::{
+    fmt,
+    future::{Future, IntoFuture},
+    hint, mem,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{park, park_timeout, AbortOnPanic, Elapsed, FlagSignal};
+
+/// A builder for blocking on a future with a combination of options.
+///
+/// Each option has a standalone function equivalent (such as [`block_on_timeout`](crate::block_on_timeout) or
+/// [`block_on_budget`](crate::block_on_budget)), but those can't be combined. The builder lets you pick only the ones
+/// you need and then call [`BlockOnBuilder::run`] to block on a future.
+///
+/// Setting a [`timeout`](BlockOnBuilder::timeout) changes the type of the builder, so that `run` returns a
+/// `Result<_, Elapsed>` only when a timeout was actually set.
+///
+/// # Example
+///
+/// ```
+/// use pollster::BlockOnBuilder;
+/// use std::time::Duration;
+///
+/// let mut polls = 0;
+/// let result = BlockOnBuilder::new()
+///     .timeout(Duration::from_secs(1))
+///     .on_poll(|n| polls = n)
+///     .spin_limit(100)
+///     .run(async { 42 });
+///
+/// assert_eq!(result, Ok(42));
+/// assert_eq!(polls, 0);
+/// ```
+#[must_use = "a builder does nothing until `run` is called"]
+#[derive(Clone)]
+pub struct BlockOnBuilder<T = (), G = fn(u64)> {
+    timeout: T,
+    on_poll: G,
+    spin_limit: usize,
+    polls_per_yield: usize,
+    poll_interval: Option<Duration>,
+    abort_on_panic: bool,
+}
+
+impl BlockOnBuilder {
+    /// Create a builder with no options set, equivalent to [`block_on`](crate::block_on).
+    pub fn new() -> Self {
+        Self {
+            timeout: (),
+            on_poll: |_| {},
+            spin_limit: 0,
+            polls_per_yield: 0,
+            poll_interval: None,
+            abort_on_panic: false,
+        }
+    }
+}
+
+impl Default for BlockOnBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, G> BlockOnBuilder<T, G> {
+    /// Call `on_poll` each time the future returns [`Poll::Pending`], as with
+    /// [`block_on_with_progress`](crate::block_on_with_progress).
+    pub fn on_poll<H: FnMut(u64)>(self, on_poll: H) -> BlockOnBuilder<T, H> {
+        BlockOnBuilder {
+            timeout: self.timeout,
+            on_poll,
+            spin_limit: self.spin_limit,
+            polls_per_yield: self.polls_per_yield,
+            poll_interval: self.poll_interval,
+            abort_on_panic: self.abort_on_panic,
+        }
+    }
+
+    /// Spin for up to `spin_limit` iterations waiting for a wakeup before parking the thread.
+    ///
+    /// Futures that are usually woken very soon after returning [`Poll::Pending`] (for example, by another thread
+    /// that's actively working on them) can be resumed without the latency of parking and unparking. Defaults to `0`.
+    pub fn spin_limit(self, spin_limit: usize) -> Self {
+        Self { spin_limit, ..self }
+    }
+
+    /// Yield to the OS scheduler after every `polls_per_yield` consecutive polls in which the future woke itself, as
+    /// with [`block_on_budget`](crate::block_on_budget).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `polls_per_yield` is zero.
+    pub fn budget(self, polls_per_yield: usize) -> Self {
+        assert!(polls_per_yield > 0, "`polls_per_yield` must be non-zero");
+        Self {
+            polls_per_yield,
+            ..self
+        }
+    }
+
+    /// Re-poll the future at least once every `interval` even if it isn't woken, as with
+    /// [`block_on_resilient`](crate::block_on_resilient).
+    pub fn poll_interval(self, interval: Duration) -> Self {
+        Self {
+            poll_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Abort the process if the future panics, instead of letting the panic unwind out of `run`, as with
+    /// [`block_on_abort_on_panic`](crate::block_on_abort_on_panic).
+    pub fn abort_on_panic(self) -> Self {
+        Self {
+            abort_on_panic: true,
+            ..self
+        }
+    }
+}
+
+impl<G> BlockOnBuilder<(), G> {
+    /// Give up on the future if it isn't ready within `timeout`, as with
+    /// [`block_on_timeout`](crate::block_on_timeout).
+    pub fn timeout(self, timeout: Duration) -> BlockOnBuilder<Duration, G> {
+        BlockOnBuilder {
+            timeout,
+            on_poll: self.on_poll,
+            spin_limit: self.spin_limit,
+            polls_per_yield: self.polls_per_yield,
+            poll_interval: self.poll_interval,
+            abort_on_panic: self.abort_on_panic,
+        }
+    }
+}
+
+impl<G: FnMut(u64)> BlockOnBuilder<(), G> {
+    /// Block the thread until the future is ready.
+    pub fn run<F: IntoFuture>(self, fut: F) -> F::Output {
+        match self.run_inner(fut, None) {
+            Ok(item) => item,
+            Err(_) => unreachable!("no deadline was set"),
+        }
+    }
+}
+
+impl<G: FnMut(u64)> BlockOnBuilder<Duration, G> {
+    /// Block the thread until the future is ready, or until the timeout has elapsed.
+    ///
+    /// The future is always polled at least once, even if the timeout is zero. If the timeout elapses first, the
+    /// future is dropped and [`Elapsed`] is returned.
+    pub fn run<F: IntoFuture>(self, fut: F) -> Result<F::Output, Elapsed> {
+        // A deadline too far in the future to represent will never be reached
+        let start = Instant::now();
+        let deadline = start.checked_add(self.timeout);
+        self.run_inner(fut, deadline.map(|deadline| (start, deadline)))
+    }
+}
+
+impl<T, G: FnMut(u64)> BlockOnBuilder<T, G> {
+    // `timeout` holds when the timeout started and its deadline, if there is one.
+    fn run_inner<F: IntoFuture>(
+        self,
+        fut: F,
+        timeout: Option<(Instant, Instant)>,
+    ) -> Result<F::Output, Elapsed> {
+        if !self.abort_on_panic {
+            return self.poll_loop(fut, timeout);
+        }
+        let guard = AbortOnPanic;
+        let output = self.poll_loop(fut, timeout);
+        mem::forget(guard);
+        output
+    }
+
+    fn poll_loop<F: IntoFuture>(
+        mut self,
+        fut: F,
+        timeout: Option<(Instant, Instant)>,
+    ) -> Result<F::Output, Elapsed> {
+        let mut fut = core::pin::pin!(fut.into_future());
+
+        let signal = Arc::new(FlagSignal::new());
+        let waker = Waker::from(Arc::clone(&signal));
+        let mut context = Context::from_waker(&waker);
+
+        let mut polls = 0;
+        let mut busy_polls = 0;
+        loop {
+            signal.take_woken();
+            if let Poll::Ready(item) = fut.as_mut().poll(&mut context) {
+                break Ok(item);
+            }
+            polls += 1;
+            (self.on_poll)(polls);
+
+            // Checked on every iteration, so that a future that keeps waking itself still times out
+            let remaining = match timeout {
+                Some((start, deadline)) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break Err(Elapsed::new(now - start));
+                    }
+                    Some(deadline - now)
+                }
+                None => None,
+            };
+
+            let mut spins = 0;
+            let woken = loop {
+                if signal.take_woken() {
+                    break true;
+                } else if spins >= self.spin_limit {
+                    break false;
+                }
+                spins += 1;
+                hint::spin_loop();
+            };
+
+            if woken {
+                busy_polls += 1;
+                if self.polls_per_yield > 0 && busy_polls >= self.polls_per_yield {
+                    busy_polls = 0;
+                    thread::yield_now();
+                }
+                // Consume the unpark token the wake left behind
+                park();
+                continue;
+            }
+            busy_polls = 0;
+
+            let timeout = match remaining {
+                Some(remaining) => Some(
+                    self.poll_interval
+                        .map_or(remaining, |interval| interval.min(remaining)),
+                ),
+                None => self.poll_interval,
+            };
+            match timeout {
+                Some(timeout) => park_timeout(timeout),
+                None => park(),
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug, G> fmt::Debug for BlockOnBuilder<T, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockOnBuilder")
+            .field("timeout", &self.timeout)
+            .field("spin_limit", &self.spin_limit)
+            .field("polls_per_yield", &self.polls_per_yield)
+            .field("poll_interval", &self.poll_interval)
+            .field("abort_on_panic", &self.abort_on_panic)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,349 @@
+//! Blocking wrappers for asynchronous channels.
+//!
+//! [`BlockingSender`] and [`BlockingReceiver`] let synchronous code talk to async code over a channel, without
+//! writing `block_on(tx.send(item))` for every item and handling the other side hanging up by hand. They work with
+//! any channel that implements [`AsyncSender`] and [`AsyncReceiver`]. These are implemented for
+//! [`futures::channel::mpsc`](https://docs.rs/futures/0.3/futures/channel/mpsc/index.html) with the
+//! `futures-channel` feature, and for [`tokio::sync::mpsc`](https://docs.rs/tokio/1/tokio/sync/mpsc/index.html)
+//! with the `tokio-channel` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use futures::channel::mpsc;
+//! use pollster::channel::{BlockingReceiver, BlockingSender};
+//!
+//! let (tx, rx) = mpsc::channel(4);
+//! let (mut tx, rx) = (BlockingSender::new(tx), BlockingReceiver::new(rx));
+//!
+//! let producer = std::thread::spawn(move || {
+//!     for i in 0..100 {
+//!         tx.send(i).unwrap();
+//!     }
+//! });
+//!
+//! // The receiver stops once the sender has been dropped
+//! assert_eq!(rx.sum::<i32>(), 4950);
+//! producer.join().unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{block_on, block_on_timeout, noop_waker};
+
+/// The future returned by [`AsyncSender::send`].
+pub type SendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<(), T>> + 'a>>;
+
+/// The sending half of an asynchronous channel.
+pub trait AsyncSender {
+    /// The type of item sent on the channel.
+    type Item;
+
+    /// Returns a future that sends `item`, waiting for room in the channel if it's full.
+    ///
+    /// If the receiver has been dropped, the future fails and gives `item` back.
+    fn send(&mut self, item: Self::Item) -> SendFuture<'_, Self::Item>;
+}
+
+/// The receiving half of an asynchronous channel.
+pub trait AsyncReceiver {
+    /// The type of item received from the channel.
+    type Item;
+
+    /// Attempt to receive the next item, registering the waker in `cx` to be woken when one arrives if the channel is
+    /// empty.
+    ///
+    /// Returns `Poll::Ready(None)` once every sender has been dropped and the channel is empty.
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+}
+
+/// A channel sender that blocks the thread until each item has been sent.
+///
+/// Created by wrapping an [`AsyncSender`] with [`BlockingSender::new`].
+#[derive(Clone, Debug, Default)]
+pub struct BlockingSender<S> {
+    sender: S,
+}
+
+impl<S> BlockingSender<S> {
+    /// Wrap an asynchronous sender.
+    pub fn new(sender: S) -> Self {
+        Self { sender }
+    }
+
+    /// Get a reference to the underlying sender.
+    pub fn get_ref(&self) -> &S {
+        &self.sender
+    }
+
+    /// Get a mutable reference to the underlying sender.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sender
+    }
+
+    /// Consume this wrapper, returning the underlying sender.
+    pub fn into_inner(self) -> S {
+        self.sender
+    }
+}
+
+impl<S: AsyncSender> BlockingSender<S> {
+    /// Block the thread until `item` has been sent, waiting for room in the channel if it's full.
+    ///
+    /// If the receiver has been dropped (including while the thread is waiting), a [`SendError`] is returned with
+    /// `item` inside it.
+    pub fn send(&mut self, item: S::Item) -> Result<(), SendError<S::Item>> {
+        block_on(self.sender.send(item)).map_err(SendError)
+    }
+}
+
+/// A channel receiver that blocks the thread until an item arrives.
+///
+/// Created by wrapping an [`AsyncReceiver`] with [`BlockingReceiver::new`]. As an [`Iterator`], it yields items as they
+/// are received and ends once every sender has been dropped.
+#[derive(Debug, Default)]
+pub struct BlockingReceiver<R> {
+    receiver: R,
+}
+
+impl<R> BlockingReceiver<R> {
+    /// Wrap an asynchronous receiver.
+    pub fn new(receiver: R) -> Self {
+        Self { receiver }
+    }
+
+    /// Get a reference to the underlying receiver.
+    pub fn get_ref(&self) -> &R {
+        &self.receiver
+    }
+
+    /// Get a mutable reference to the underlying receiver.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.receiver
+    }
+
+    /// Consume this wrapper, returning the underlying receiver.
+    pub fn into_inner(self) -> R {
+        self.receiver
+    }
+}
+
+impl<R: AsyncReceiver> BlockingReceiver<R> {
+    /// Block the thread until an item arrives.
+    ///
+    /// Returns `None` once every sender has been dropped (including while the thread is waiting) and the channel is
+    /// empty.
+    pub fn recv(&mut self) -> Option<R::Item> {
+        block_on(poll_fn(|cx| self.receiver.poll_recv(cx)))
+    }
+
+    /// Block the thread until an item arrives, or until `timeout` has elapsed.
+    ///
+    /// The channel is always checked at least once, even if `timeout` is zero.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<R::Item, RecvTimeoutError> {
+        match block_on_timeout(poll_fn(|cx| self.receiver.poll_recv(cx)), timeout) {
+            Ok(Some(item)) => Ok(item),
+            Ok(None) => Err(RecvTimeoutError::Disconnected),
+            Err(_) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Receive an item if one is waiting in the channel, without blocking the thread.
+    pub fn try_recv(&mut self) -> Result<R::Item, TryRecvError> {
+        match self
+            .receiver
+            .poll_recv(&mut Context::from_waker(&noop_waker()))
+        {
+            Poll::Ready(Some(item)) => Ok(item),
+            Poll::Ready(None) => Err(TryRecvError::Disconnected),
+            Poll::Pending => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<R: AsyncReceiver> Iterator for BlockingReceiver<R> {
+    type Item = R::Item;
+
+    /// Block the thread until an item arrives, as [`BlockingReceiver::recv`] does.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// The error returned by [`BlockingSender::send`] when the receiver has been dropped.
+///
+/// This holds the item that couldn't be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> SendError<T> {
+    /// Get back the item that couldn't be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// Manual, so that the error can be used with `?` even when the item doesn't implement `Debug`.
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a closed channel")
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+/// The error returned by [`BlockingReceiver::try_recv`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The channel is empty, but the senders are still connected.
+    Empty,
+    /// The channel is empty and every sender has been dropped.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TryRecvError::Empty => "receiving on an empty channel",
+            TryRecvError::Disconnected => "receiving on a closed channel",
+        })
+    }
+}
+
+impl Error for TryRecvError {}
+
+/// The error returned by [`BlockingReceiver::recv_timeout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// Nothing arrived before the timeout elapsed.
+    Timeout,
+    /// The channel is empty and every sender has been dropped.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RecvTimeoutError::Timeout => "timed out waiting on channel",
+            RecvTimeoutError::Disconnected => "channel is empty and sending half is closed",
+        })
+    }
+}
+
+impl Error for RecvTimeoutError {}
+
+#[cfg(feature = "futures-channel")]
+mod futures_impls {
+    use std::{
+        future::{poll_fn, ready},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures_channel::mpsc::{
+        Receiver, Sender, TrySendError, UnboundedReceiver, UnboundedSender,
+    };
+    use futures_core::Stream;
+
+    use super::{AsyncReceiver, AsyncSender, SendFuture};
+
+    impl<T> AsyncSender for Sender<T> {
+        type Item = T;
+
+        fn send(&mut self, item: T) -> SendFuture<'_, T> {
+            Box::pin(async move {
+                if poll_fn(|cx| self.poll_ready(cx)).await.is_err() {
+                    return Err(item);
+                }
+                self.try_send(item).map_err(TrySendError::into_inner)
+            })
+        }
+    }
+
+    impl<T> AsyncSender for UnboundedSender<T> {
+        type Item = T;
+
+        fn send(&mut self, item: T) -> SendFuture<'_, T> {
+            // Unbounded channels are never full, so this never needs to wait
+            Box::pin(ready(
+                self.unbounded_send(item).map_err(TrySendError::into_inner),
+            ))
+        }
+    }
+
+    impl<T> AsyncReceiver for Receiver<T> {
+        type Item = T;
+
+        fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            Pin::new(self).poll_next(cx)
+        }
+    }
+
+    impl<T> AsyncReceiver for UnboundedReceiver<T> {
+        type Item = T;
+
+        fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            Pin::new(self).poll_next(cx)
+        }
+    }
+}
+
+#[cfg(feature = "tokio-channel")]
+mod tokio_impls {
+    use std::{
+        future::ready,
+        task::{Context, Poll},
+    };
+
+    use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+
+    use super::{AsyncReceiver, AsyncSender, SendFuture};
+
+    impl<T> AsyncSender for Sender<T> {
+        type Item = T;
+
+        fn send(&mut self, item: T) -> SendFuture<'_, T> {
+            Box::pin(async move { Sender::send(self, item).await.map_err(|err| err.0) })
+        }
+    }
+
+    impl<T> AsyncSender for UnboundedSender<T> {
+        type Item = T;
+
+        fn send(&mut self, item: T) -> SendFuture<'_, T> {
+            // Unbounded channels are never full, so this never needs to wait
+            Box::pin(ready(
+                UnboundedSender::send(self, item).map_err(|err| err.0),
+            ))
+        }
+    }
+
+    impl<T> AsyncReceiver for Receiver<T> {
+        type Item = T;
+
+        fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            Receiver::poll_recv(self, cx)
+        }
+    }
+
+    impl<T> AsyncReceiver for UnboundedReceiver<T> {
+        type Item = T;
+
+        fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            UnboundedReceiver::poll_recv(self, cx)
+        }
+    }
+}
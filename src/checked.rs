@@ -0,0 +1,70 @@
+//! Detection of futures that return `Pending` without holding on to their waker.
+
+use std::{
+    fmt,
+    future::{Future, IntoFuture},
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{park, FlagSignal};
+
+/// The error returned by [`block_on_checked`] when a future returns [`Poll::Pending`] but nothing could ever wake it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoWakerHeld(());
+
+impl fmt::Display for NoWakerHeld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("future returned `Pending` without holding on to its waker, so it can never be woken")
+    }
+}
+
+impl std::error::Error for NoWakerHeld {}
+
+/// Block the thread until the future is ready, returning an error instead of blocking forever if the future returns
+/// [`Poll::Pending`] without keeping any way to wake it.
+///
+/// This catches the classic "forgot to store the waker" bug, which would otherwise cause [`block_on`](crate::block_on)
+/// to hang.
+///
+/// Unlike `block_on`, a fresh waker is created for each call so that its clones can be counted. After each poll that
+/// returns `Pending`, [`NoWakerHeld`] is returned (and the future dropped) if, at that moment, both:
+///
+/// - no clone of the waker exists outside of this function (it was never cloned, or every clone has been dropped)
+/// - the waker has not been woken (by [`Waker::wake`] or [`Waker::wake_by_ref`]) since the poll began
+///
+/// A future that wakes itself with `wake_by_ref` before returning `Pending` is therefore accepted, even though it
+/// holds no waker. The check is a heuristic: a future can still hang if it holds a clone of its waker that it will
+/// never wake.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = std::future::poll_fn(|_| std::task::Poll::<()>::Pending);
+/// let result = pollster::block_on_checked(my_fut);
+/// assert!(result.is_err());
+/// ```
+pub fn block_on_checked<F: IntoFuture>(fut: F) -> Result<F::Output, NoWakerHeld> {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    // A fresh signal, rather than the per-thread one, so that the references to it are ours alone to count
+    let signal = Arc::new(FlagSignal::new());
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut context = Context::from_waker(&waker);
+
+    // The references held by this function: `signal` and `waker`
+    const OWN_REFS: usize = 2;
+
+    loop {
+        signal.take_woken();
+        match fut.as_mut().poll(&mut context) {
+            Poll::Pending => {
+                if Arc::strong_count(&signal) <= OWN_REFS && !signal.take_woken() {
+                    break Err(NoWakerHeld(()));
+                }
+                park();
+            }
+            Poll::Ready(item) => break Ok(item),
+        }
+    }
+}
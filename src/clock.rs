@@ -0,0 +1,130 @@
+//! The time seen by sleeps, which can be paused for testing.
+
+use std::time::Instant;
+#[cfg(feature = "testing")]
+use std::{cell::Cell, time::Duration};
+
+#[cfg(feature = "testing")]
+thread_local! {
+    // While time on this thread is virtual, the virtual time as of the last change to the clock, along with the real
+    // time at which it resumed (or `None` while it's paused)
+    static VIRTUAL: Cell<Option<(Instant, Option<Instant>)>> = const { Cell::new(None) };
+}
+
+// Returns the current time, as seen by sleeps on this thread.
+#[inline(always)]
+pub(crate) fn now() -> Instant {
+    #[cfg(feature = "testing")]
+    if let Some((at, resumed)) = VIRTUAL.with(Cell::get) {
+        return resumed.map_or(at, |resumed| at + resumed.elapsed());
+    }
+    Instant::now()
+}
+
+/// Pause time on the current thread.
+///
+/// While time is paused, [`sleep`](crate::sleep), [`timeout`](crate::timeout), [`FutureExt::timeout`] and
+/// [`Interval`](crate::Interval) see a virtual clock that only moves when it's told to. [`advance`] moves it by hand.
+/// It also moves by itself: whenever [`block_on`](crate::block_on) finds its future waiting for a deadline, rather
+/// than parking the thread until then, it jumps the clock straight to the deadline and polls again. That way, a test
+/// of a ten minute timeout finishes in (real) milliseconds.
+///
+/// Wakes from other threads are still processed as usual. When a future isn't waiting for any deadline, the thread
+/// parks until it's woken, and a wake that arrives while the clock is jumping ahead is seen on the next poll. Since
+/// the clock doesn't wait for other threads, though, a race between a timer and some real work will usually be won by
+/// the timer.
+///
+/// Only sleeps polled by `block_on` jump the clock. Under another executor, a sleep falls back to a timer thread that
+/// uses real time, so it should be woken with [`advance`] and a poll instead. Other blocking functions with timeouts,
+/// such as [`block_on_timeout`](crate::block_on_timeout), always use real time.
+///
+/// This is available behind the `testing` feature, which is intended to be enabled for dev-dependencies only.
+///
+/// [`FutureExt::timeout`]: crate::FutureExt::timeout
+///
+/// # Panics
+///
+/// Panics if time is already paused on this thread.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// pollster::time::pause();
+///
+/// let start = Instant::now();
+/// let result = pollster::block_on(pollster::timeout(
+///     Duration::from_secs(600),
+///     std::future::pending::<()>(),
+/// ));
+/// assert!(result.is_err());
+/// assert!(start.elapsed() < Duration::from_secs(1));
+/// ```
+#[cfg(feature = "testing")]
+pub fn pause() {
+    let now = now();
+    VIRTUAL.with(|clock| {
+        assert!(
+            !matches!(clock.get(), Some((_, None))),
+            "time is already paused"
+        );
+        clock.set(Some((now, None)));
+    });
+}
+
+/// Resume time on the current thread, after it was paused with [`pause`].
+///
+/// Time carries on at the usual rate from wherever the virtual clock had got to. It doesn't jump back to real time,
+/// so sleeps that have already finished stay finished.
+///
+/// # Panics
+///
+/// Panics if time isn't paused on this thread.
+#[cfg(feature = "testing")]
+pub fn resume() {
+    VIRTUAL.with(|clock| match clock.get() {
+        Some((at, None)) => clock.set(Some((at, Some(Instant::now())))),
+        _ => panic!("time isn't paused"),
+    });
+}
+
+/// Move paused time on the current thread forward by `duration`.
+///
+/// Sleeps whose deadline has now passed are ready the next time they're polled.
+///
+/// # Panics
+///
+/// Panics if time isn't paused on this thread.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// pollster::time::pause();
+///
+/// let mut sleep = core::pin::pin!(pollster::sleep(Duration::from_secs(60)));
+/// assert!(pollster::try_poll_once(sleep.as_mut()).is_pending());
+/// pollster::time::advance(Duration::from_secs(60));
+/// assert!(pollster::try_poll_once(sleep.as_mut()).is_ready());
+/// ```
+#[cfg(feature = "testing")]
+pub fn advance(duration: Duration) {
+    VIRTUAL.with(|clock| match clock.get() {
+        Some((at, None)) => clock.set(Some((at + duration, None))),
+        _ => panic!("time isn't paused"),
+    });
+}
+
+// If time is paused on this thread, jump it forward to `deadline` (if it's later) and return `true`.
+#[cfg(feature = "testing")]
+pub(crate) fn skip_to(deadline: Instant) -> bool {
+    VIRTUAL.with(|clock| match clock.get() {
+        Some((at, None)) => {
+            clock.set(Some((at.max(deadline), None)));
+            true
+        }
+        _ => false,
+    })
+}
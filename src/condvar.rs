@@ -0,0 +1,76 @@
+//! Waiting for wakeups with a condition variable, rather than `thread::park`.
+//!
+//! Behind the `condvar` feature, which swaps this in for the wait in `block_on`. Some platforms have been seen to
+//! misbehave with `thread::park` and `Thread::unpark` under heavy wakeup traffic, whereas a `Mutex<bool>` and
+//! `Condvar` is about as well-trodden as synchronisation gets.
+
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    task::{Wake, Waker},
+    time::Instant,
+};
+
+use crate::clock;
+
+pub(crate) struct CondvarSignal {
+    woken: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl CondvarSignal {
+    // Block the thread until the signal is woken. As with `thread::park`, a wake that arrived since the last call
+    // makes this return immediately.
+    pub(crate) fn wait(&self) {
+        let mut woken = self.woken.lock().unwrap();
+        while !*woken {
+            woken = self.condvar.wait(woken).unwrap();
+        }
+        *woken = false;
+    }
+
+    // Block the thread until the signal is woken, or until `deadline` (if any) has passed, as `thread::park_timeout`
+    // would.
+    pub(crate) fn wait_until(&self, deadline: Option<Instant>) {
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => return self.wait(),
+        };
+        let timeout = deadline.saturating_duration_since(clock::now());
+        #[cfg(all(windows, feature = "hi-res-timer"))]
+        let _period = crate::hi_res::Period::begin(timeout);
+
+        let woken = self.woken.lock().unwrap();
+        let (mut woken, _) = self
+            .condvar
+            .wait_timeout_while(woken, timeout, |woken| !*woken)
+            .unwrap();
+        *woken = false;
+    }
+}
+
+impl Wake for CondvarSignal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("wakeup");
+        *self.woken.lock().unwrap() = true;
+        self.condvar.notify_one();
+    }
+}
+
+// Creates a signal along with a waker for it.
+pub(crate) fn signal() -> (Arc<CondvarSignal>, Waker) {
+    let signal = Arc::new(CondvarSignal {
+        woken: Mutex::new(false),
+        condvar: Condvar::new(),
+    });
+    (Arc::clone(&signal), Waker::from(signal))
+}
+
+thread_local! {
+    // The condition variable equivalent of `LOCAL_WAKER`, used by `block_on` in its place.
+    pub(crate) static LOCAL_SIGNAL: (Arc<CondvarSignal>, Waker) = signal();
+}
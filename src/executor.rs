@@ -0,0 +1,308 @@
+//! Driving several futures at once on a single thread.
+
+use std::{
+    fmt,
+    future::{poll_fn, Future, IntoFuture},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{block_on, park, FlagSignal};
+
+/// An executor that drives several futures concurrently on the current thread.
+///
+/// Futures are spawned onto the executor with [`LocalExecutor::spawn`], and [`LocalExecutor::run`] then blocks the
+/// thread until all of them have completed. Futures don't need to be [`Send`] or `'static`, since they never leave
+/// the thread that's running them.
+///
+/// Each future is only polled when it has been woken. To stop one future from starving the others by repeatedly
+/// waking itself, a future is polled at most [`budget`](LocalExecutor::budget) times in a row before the executor
+/// moves on to the next one. Each sweep over the futures also starts one future further along than the last, so when
+/// several are woken at once, every one of them takes its turn at being polled first.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// let total = Cell::new(0);
+///
+/// let mut executor = pollster::LocalExecutor::new();
+/// for i in 1..=3 {
+///     let total = &total;
+///     executor.spawn(async move { total.set(total.get() + i) });
+/// }
+/// executor.run();
+///
+/// assert_eq!(total.get(), 6);
+/// ```
+#[must_use = "an executor does nothing until `run` is called"]
+pub struct LocalExecutor<'a> {
+    tasks: Vec<Pin<Box<dyn Future<Output = ()> + 'a>>>,
+    budget: usize,
+}
+
+impl<'a> LocalExecutor<'a> {
+    /// Create an executor with no futures, and the default budget of 16 polls.
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            budget: 16,
+        }
+    }
+
+    /// Set the maximum number of times in a row that a future can be polled before the executor moves on to the
+    /// others, if it keeps waking itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `budget` is zero.
+    pub fn budget(self, budget: usize) -> Self {
+        assert!(budget > 0, "`budget` must be non-zero");
+        Self { budget, ..self }
+    }
+
+    /// Add a future to be driven by the executor.
+    pub fn spawn<F: IntoFuture<Output = ()>>(&mut self, fut: F)
+    where
+        F::IntoFuture: 'a,
+    {
+        self.tasks.push(Box::pin(fut.into_future()));
+    }
+
+    /// Block the thread until every future that has been spawned onto the executor has completed.
+    pub fn run(self) {
+        let mut tasks = self
+            .tasks
+            .into_iter()
+            .map(|fut| {
+                let signal = Arc::new(FlagSignal::new());
+                // Every future needs to be polled at least once
+                signal.wake_by_ref();
+                (Some(fut), Waker::from(Arc::clone(&signal)), signal)
+            })
+            .collect::<Vec<_>>();
+
+        let mut remaining = tasks.len();
+        while remaining > 0 {
+            let mut polled = false;
+            for (task, waker, signal) in &mut tasks {
+                let mut context = Context::from_waker(waker);
+
+                // Once the budget is used up, any remaining wake is left for the next sweep
+                for _ in 0..self.budget {
+                    let fut = match task {
+                        Some(fut) if signal.take_woken() => fut,
+                        _ => break,
+                    };
+                    polled = true;
+                    if fut.as_mut().poll(&mut context).is_ready() {
+                        *task = None;
+                        remaining -= 1;
+                    }
+                }
+            }
+
+            // Rotate which future goes first, so that those early in the list don't always get ahead
+            tasks.rotate_left(1);
+
+            // Nothing was woken, so wait for something to be
+            if !polled {
+                park();
+            }
+        }
+    }
+}
+
+impl Default for LocalExecutor<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LocalExecutor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalExecutor")
+            .field("tasks", &self.tasks.len())
+            .field("budget", &self.budget)
+            .finish()
+    }
+}
+
+/// Returns a future that drives two futures concurrently, resolving to both of their outputs once both are ready.
+///
+/// Every time the returned future is polled, each of the inner futures that hasn't completed yet is polled once, so
+/// neither can starve the other. Which of them is polled first alternates from one poll to the next.
+///
+/// # Example
+///
+/// ```
+/// let (a, b) = pollster::block_on(pollster::join(async { 1 }, async { "two" }));
+///
+/// assert_eq!((a, b), (1, "two"));
+/// ```
+pub fn join<A: IntoFuture, B: IntoFuture>(a: A, b: B) -> Join<A::IntoFuture, B::IntoFuture> {
+    Join {
+        a: MaybeDone::Pending(a.into_future()),
+        b: MaybeDone::Pending(b.into_future()),
+        b_first: false,
+    }
+}
+
+/// A future that drives two futures concurrently.
+///
+/// Created by [`join`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+    // Whether `b` is polled before `a` next time
+    b_first: bool,
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `a` and `b` are structurally pinned: they're only ever accessed through these `Pin`s, and `Join` has
+        // no `Drop` impl. `b_first` isn't pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let (mut a, mut b) = unsafe { (Pin::new_unchecked(&mut this.a), Pin::new_unchecked(&mut this.b)) };
+        // Poll both (even if the first is still pending), so that both make progress
+        let (a_done, b_done) = if this.b_first {
+            let b_done = b.as_mut().poll(cx);
+            (a.as_mut().poll(cx), b_done)
+        } else {
+            let a_done = a.as_mut().poll(cx);
+            (a_done, b.as_mut().poll(cx))
+        };
+        this.b_first = !this.b_first;
+        if a_done && b_done {
+            Poll::Ready((a.take(), b.take()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<A: Future, B: Future> fmt::Debug for Join<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Join").finish_non_exhaustive()
+    }
+}
+
+// A future that holds on to its output once it has completed.
+enum MaybeDone<F: Future> {
+    Pending(F),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    // Polls the future if it hasn't completed yet, returning whether it has completed.
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // SAFETY: The future is only polled in place, and is dropped in place when `Pin::set` replaces it with its
+        // output.
+        if let MaybeDone::Pending(fut) = unsafe { self.as_mut().get_unchecked_mut() } {
+            match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                Poll::Ready(output) => self.set(MaybeDone::Done(output)),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    fn take(self: Pin<&mut Self>) -> F::Output {
+        // SAFETY: Only a completed output is moved out, and it isn't pinned. A future that's still pending is left
+        // where it is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let MaybeDone::Done(_) = this {
+            if let MaybeDone::Done(output) = std::mem::replace(this, MaybeDone::Taken) {
+                return output;
+            }
+        }
+        panic!("`Join` polled after completion")
+    }
+}
+
+/// Block the thread until either of two futures is ready, returning its output along with the other future.
+///
+/// The future that completed first is dropped, and the other is returned untouched as a [`Remaining`], which can be
+/// blocked on (or raced against another future) later on. If both are ready at once, `a` wins.
+///
+/// # Example
+///
+/// ```
+/// use pollster::Either;
+/// use std::future::ready;
+///
+/// let (output, remaining) = pollster::block_on_any(ready(1), async { "two" });
+/// assert_eq!(output, Either::Left(1));
+///
+/// // The remaining future resolves to the other side's output
+/// assert_eq!(pollster::block_on(remaining), Either::Right("two"));
+/// ```
+pub fn block_on_any<A: IntoFuture, B: IntoFuture>(
+    a: A,
+    b: B,
+) -> (Either<A::Output, B::Output>, Remaining<A, B>) {
+    let mut a = Box::pin(a.into_future());
+    let mut b = Box::pin(b.into_future());
+
+    let output = block_on(poll_fn(|cx| {
+        if let Poll::Ready(output) = a.as_mut().poll(cx) {
+            return Poll::Ready(Either::Left(output));
+        }
+        b.as_mut().poll(cx).map(Either::Right)
+    }));
+
+    match output {
+        Either::Left(_) => (output, Remaining::Right(b)),
+        Either::Right(_) => (output, Remaining::Left(a)),
+    }
+}
+
+/// A value that is one of two types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+    /// A value of the left type.
+    Left(L),
+    /// A value of the right type.
+    Right(R),
+}
+
+/// The future that didn't complete first in a call to [`block_on_any`].
+///
+/// This resolves to the future's output, on the same side of an [`Either`] as the future was passed to
+/// [`block_on_any`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub enum Remaining<A: IntoFuture, B: IntoFuture> {
+    /// The left future, `a`, is still pending.
+    Left(Pin<Box<A::IntoFuture>>),
+    /// The right future, `b`, is still pending.
+    Right(Pin<Box<B::IntoFuture>>),
+}
+
+// The futures are boxed, so moving a `Remaining` is always fine.
+impl<A: IntoFuture, B: IntoFuture> Unpin for Remaining<A, B> {}
+
+impl<A: IntoFuture, B: IntoFuture> Future for Remaining<A, B> {
+    type Output = Either<A::Output, B::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match &mut *self {
+            Remaining::Left(fut) => fut.as_mut().poll(cx).map(Either::Left),
+            Remaining::Right(fut) => fut.as_mut().poll(cx).map(Either::Right),
+        }
+    }
+}
+
+impl<A: IntoFuture, B: IntoFuture> fmt::Debug for Remaining<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Remaining::Left(_) => f.write_str("Left(..)"),
+            Remaining::Right(_) => f.write_str("Right(..)"),
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Future combinators.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    future::{Future, IntoFuture},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+/// A future that calls a closure with the result of each poll of an inner future.
+///
+/// Created by [`FutureExt::inspect_poll`](crate::FutureExt::inspect_poll).
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct InspectPoll<F, G> {
+    fut: F,
+    f: G,
+}
+
+impl<F, G> InspectPoll<F, G> {
+    pub(crate) fn new(fut: F, f: G) -> Self {
+        Self { fut, f }
+    }
+}
+
+impl<F: Future, G: FnMut(&Poll<F::Output>)> Future for InspectPoll<F, G> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut` is structurally pinned: it's only ever accessed through this `Pin`, and `InspectPoll` has no
+        // `Drop` impl. The closure isn't pinned, so handing it out as `&mut` is fine.
+        let (fut, f) = unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.fut), &mut this.f)
+        };
+        let poll = fut.poll(cx);
+        f(&poll);
+        poll
+    }
+}
+
+impl<F, G> fmt::Debug for InspectPoll<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InspectPoll").finish_non_exhaustive()
+    }
+}
+
+/// Wrap a future so that it can be woken from anywhere with the returned [`WakeHandle`].
+///
+/// This is useful for futures that depend on some external condition that you control: when the condition changes,
+/// call [`WakeHandle::wake`] to have the future polled again.
+///
+/// # Example
+///
+/// ```
+/// use std::{sync::atomic::{AtomicBool, Ordering}, task::Poll, thread};
+///
+/// static READY: AtomicBool = AtomicBool::new(false);
+///
+/// let (handle, fut) = pollster::wrap_wakeable(std::future::poll_fn(|_| {
+///     if READY.load(Ordering::Acquire) {
+///         Poll::Ready(42)
+///     } else {
+///         Poll::Pending
+///     }
+/// }));
+///
+/// thread::spawn(move || {
+///     READY.store(true, Ordering::Release);
+///     handle.wake();
+/// });
+///
+/// assert_eq!(pollster::block_on(fut), 42);
+/// ```
+pub fn wrap_wakeable<F: IntoFuture>(fut: F) -> (WakeHandle, Wakeable<F::IntoFuture>) {
+    let waker = Arc::new(Mutex::new(None));
+    let handle = WakeHandle {
+        waker: Arc::clone(&waker),
+    };
+    let fut = Wakeable {
+        fut: fut.into_future(),
+        waker,
+    };
+    (handle, fut)
+}
+
+/// A handle that wakes the task polling a [`Wakeable`] future.
+///
+/// Created by [`wrap_wakeable`]. Handles are cheap to clone and can be used from any thread.
+#[derive(Clone)]
+pub struct WakeHandle {
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl WakeHandle {
+    /// Wake the task that most recently polled the future, so that it will be polled again.
+    ///
+    /// If the future hasn't been polled yet, this does nothing, since it will be polled anyway.
+    pub fn wake(&self) {
+        let waker = self.waker.lock().unwrap();
+        if let Some(waker) = &*waker {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+impl fmt::Debug for WakeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakeHandle").finish_non_exhaustive()
+    }
+}
+
+/// A future that can be woken by a [`WakeHandle`].
+///
+/// Created by [`wrap_wakeable`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Wakeable<F> {
+    fut: F,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<F: Future> Future for Wakeable<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        {
+            let mut waker = self.waker.lock().unwrap();
+            match &mut *waker {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                waker => *waker = Some(cx.waker().clone()),
+            }
+        }
+        // SAFETY: `fut` is structurally pinned: this is the only place it's accessed, and `Wakeable` has no `Drop`
+        // impl.
+        unsafe { self.map_unchecked_mut(|this| &mut this.fut) }.poll(cx)
+    }
+}
+
+impl<F> fmt::Debug for Wakeable<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wakeable").finish_non_exhaustive()
+    }
+}
+
+/// Returns a future that is pending the first time it's polled, and ready the second.
+///
+/// The future wakes itself before returning [`Poll::Pending`], so it doesn't need anything else to wake it: awaiting it
+/// just gives up control for one poll, allowing other futures being driven alongside it (for example, by a `join`) to
+/// make progress. Under [`block_on`](crate::block_on), this causes exactly one extra poll (without blocking).
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async {
+///     for i in 0..3 {
+///         // Do some work, then let other futures run
+///         pollster::yield_now().await;
+///     }
+/// };
+///
+/// pollster::block_on(my_fut);
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// A future that yields once before completing.
+///
+/// Created by [`yield_now`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// A future that can be cloned, with every clone resolving to a clone of the inner future's output.
+///
+/// Created by [`FutureExt::shared`](crate::FutureExt::shared). Whichever clone is polled drives the inner future, and
+/// the rest wait to be woken once it has made progress, so the inner future is only ever polled from one place at a
+/// time. Clones can be sent to other threads (as long as the future and its output are [`Send`]), and blocked on or
+/// awaited there.
+///
+/// If the inner future panics, the panic is passed to the clone that polled it, and every other clone panics when
+/// it's next polled.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Shared<F: Future> {
+    state: Arc<Mutex<SharedState<F>>>,
+    wakers: Arc<SharedWakers>,
+    // The key this clone's waker is registered under in `wakers`
+    id: usize,
+}
+
+enum SharedState<F: Future> {
+    // Boxed, since it's moved out of the state while a clone polls it
+    Pending(Pin<Box<F>>),
+    // Taken out of the state by the clone that's currently polling it
+    Polling,
+    Done(F::Output),
+    Panicked,
+}
+
+// The wakers of every clone that's waiting, woken all at once by the waker the inner future is polled with.
+#[derive(Default)]
+struct SharedWakers {
+    wakers: Mutex<HashMap<usize, Waker>>,
+    next_id: AtomicUsize,
+}
+
+impl SharedWakers {
+    fn wake_all(&self) {
+        let wakers = std::mem::take(&mut *self.wakers.lock().unwrap());
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for SharedWakers {
+    fn wake(self: Arc<Self>) {
+        self.wake_all();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_all();
+    }
+}
+
+impl<F: Future> Shared<F> {
+    pub(crate) fn new(fut: F) -> Self {
+        let wakers = Arc::new(SharedWakers::default());
+        Self {
+            state: Arc::new(Mutex::new(SharedState::Pending(Box::pin(fut)))),
+            id: wakers.next_id.fetch_add(1, Ordering::Relaxed),
+            wakers,
+        }
+    }
+}
+
+impl<F: Future> Clone for Shared<F> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            wakers: Arc::clone(&self.wakers),
+            id: self.wakers.next_id.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl<F: Future> Future for Shared<F>
+where
+    F::Output: Clone,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match &*state {
+            SharedState::Done(output) => return Poll::Ready(output.clone()),
+            SharedState::Panicked => panic!("`Shared` future's inner future panicked"),
+            SharedState::Pending(_) | SharedState::Polling => {}
+        }
+
+        // Registered before polling, so that if another clone is polling the inner future right now, this one is still
+        // woken when it makes progress
+        {
+            let mut wakers = self.wakers.wakers.lock().unwrap();
+            match wakers.get(&self.id) {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                _ => {
+                    wakers.insert(self.id, cx.waker().clone());
+                }
+            }
+        }
+
+        let mut fut = match std::mem::replace(&mut *state, SharedState::Polling) {
+            SharedState::Pending(fut) => fut,
+            // Another clone is polling it, and will wake this one
+            _ => return Poll::Pending,
+        };
+        // Not held while polling, so that other clones can register their wakers in the meantime
+        drop(state);
+
+        let waker = Waker::from(Arc::clone(&self.wakers));
+        let poll = panic::catch_unwind(AssertUnwindSafe(|| {
+            fut.as_mut().poll(&mut Context::from_waker(&waker))
+        }));
+
+        let mut state = self.state.lock().unwrap();
+        match poll {
+            Ok(Poll::Ready(output)) => {
+                *state = SharedState::Done(output.clone());
+                drop(state);
+                self.wakers.wake_all();
+                Poll::Ready(output)
+            }
+            Ok(Poll::Pending) => {
+                *state = SharedState::Pending(fut);
+                Poll::Pending
+            }
+            Err(panic) => {
+                *state = SharedState::Panicked;
+                drop(state);
+                self.wakers.wake_all();
+                panic::resume_unwind(panic)
+            }
+        }
+    }
+}
+
+impl<F: Future> Drop for Shared<F> {
+    fn drop(&mut self) {
+        self.wakers.wakers.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl<F: Future> fmt::Debug for Shared<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared").finish_non_exhaustive()
+    }
+}
+
+/// Block the thread until `first` is ready, then until the future built from its output by `then` is ready.
+///
+/// This is [`FutureExt::and_then`](crate::FutureExt::and_then) followed by [`block_on`](crate::block_on), for
+/// sequencing futures without writing an `async` block.
+///
+/// # Example
+///
+/// ```
+/// let result = pollster::block_on_chain(async { 20 }, |n| async move { n * 2 + 2 });
+/// assert_eq!(result, 42);
+/// ```
+pub fn block_on_chain<F: IntoFuture, G: FnOnce(F::Output) -> H, H: Future>(
+    first: F,
+    then: G,
+) -> H::Output {
+    crate::block_on(AndThen::new(first.into_future(), then))
+}
+
+/// A future that waits for one future, and then for a second future built from its output.
+///
+/// Created by [`FutureExt::and_then`](crate::FutureExt::and_then).
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AndThen<F, G, H> {
+    state: AndThenState<F, G, H>,
+}
+
+enum AndThenState<F, G, H> {
+    First(F, Option<G>),
+    Second(H),
+    Done,
+}
+
+impl<F, G, H> AndThen<F, G, H> {
+    pub(crate) fn new(fut: F, then: G) -> Self {
+        Self {
+            state: AndThenState::First(fut, Some(then)),
+        }
+    }
+}
+
+impl<F: Future, G: FnOnce(F::Output) -> H, H: Future> Future for AndThen<F, G, H> {
+    type Output = H::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `state` is structurally pinned, and `AndThen` has no `Drop` impl.
+        let mut state = unsafe { self.map_unchecked_mut(|this| &mut this.state) };
+        loop {
+            // SAFETY: Each future is only polled in place, and is dropped in place when `Pin::set` moves on to the next
+            // state. The closure isn't pinned, so it's fine to move it out.
+            match unsafe { state.as_mut().get_unchecked_mut() } {
+                AndThenState::First(fut, then) => {
+                    let output = match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                        Poll::Ready(output) => output,
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    let then = then.take().expect("`AndThen` closure already called");
+                    // The second future is polled straight away, in the same poll as the first completed
+                    state.set(AndThenState::Second(then(output)));
+                }
+                AndThenState::Second(fut) => {
+                    let output = match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                        Poll::Ready(output) => output,
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    state.set(AndThenState::Done);
+                    return Poll::Ready(output);
+                }
+                AndThenState::Done => panic!("`AndThen` polled after completion"),
+            }
+        }
+    }
+}
+
+impl<F, G, H> fmt::Debug for AndThen<F, G, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            AndThenState::First(..) => "First",
+            AndThenState::Second(_) => "Second",
+            AndThenState::Done => "Done",
+        };
+        f.debug_struct("AndThen")
+            .field("state", &state)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,40 @@
+//! Raising the Windows timer resolution around short waits.
+
+use std::time::Duration;
+
+#[link(name = "winmm")]
+extern "system" {
+    fn timeBeginPeriod(period: u32) -> u32;
+    fn timeEndPeriod(period: u32) -> u32;
+}
+
+// The resolution to ask for, in milliseconds
+const PERIOD: u32 = 1;
+const TIMERR_NOERROR: u32 = 0;
+
+// Longer waits keep the default resolution (about 15.6ms), since an error of a tick matters little to them, and a
+// raised resolution costs power across the whole system for as long as it's held.
+const SHORT_WAIT: Duration = Duration::from_millis(50);
+
+// Raises the system timer resolution for the duration of a short wait, restoring it when dropped.
+pub(crate) struct Period {
+    raised: bool,
+}
+
+impl Period {
+    pub(crate) fn begin(timeout: Duration) -> Self {
+        // SAFETY: `timeBeginPeriod` has no preconditions, and each successful call is matched by `timeEndPeriod` with
+        // the same period when this is dropped.
+        let raised = timeout <= SHORT_WAIT && unsafe { timeBeginPeriod(PERIOD) } == TIMERR_NOERROR;
+        Self { raised }
+    }
+}
+
+impl Drop for Period {
+    fn drop(&mut self) {
+        if self.raised {
+            // SAFETY: This matches the successful `timeBeginPeriod` call made when this was created.
+            unsafe { timeEndPeriod(PERIOD) };
+        }
+    }
+}
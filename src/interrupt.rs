@@ -0,0 +1,136 @@
+//! Returning early from `block_on` when the process receives `SIGINT` (usually from Ctrl+C).
+
+use std::{
+    error::Error,
+    fmt,
+    future::{Future, IntoFuture},
+    mem, ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    task::{Context, Poll},
+    thread::{self, Thread},
+};
+
+use crate::{park, LOCAL_WAKER};
+
+// Whether `SIGINT` has been received since the handler was installed
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+// The thread to unpark when `SIGINT` is received, owned by the installed `Handler`
+static WAITING: AtomicPtr<Thread> = AtomicPtr::new(ptr::null_mut());
+// How many signal handlers are running right now, so that the waiting thread isn't freed out from under them
+static HANDLING: AtomicUsize = AtomicUsize::new(0);
+// Whether a handler is installed, since there's only one `SIGINT` disposition per process
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Block the thread until the future is ready, or until the process receives `SIGINT` (usually from Ctrl+C).
+///
+/// A `SIGINT` handler is installed for the duration of the call, and the previous handler is restored when it returns
+/// (or unwinds). If the signal arrives while the future is pending, the thread is unparked and [`Interrupted`] is
+/// returned, dropping the future without polling it again. Without this, Ctrl+C terminates the process as usual, or
+/// is swallowed by whatever handler the program has installed.
+///
+/// This is a separate function rather than a change to [`block_on`](crate::block_on)'s signature, so that enabling the
+/// `ctrlc` feature never breaks other crates that use `block_on`. It's only available on Linux and Android, where the
+/// standard library parks threads with a futex, so that the thread can be woken from inside the signal handler.
+///
+/// # Panics
+///
+/// Panics if another thread is already in `block_on_interruptible`, since a process only has one `SIGINT` handler.
+///
+/// # Example
+///
+/// ```no_run
+/// # let long_running_task = std::future::pending::<()>();
+/// match pollster::block_on_interruptible(long_running_task) {
+///     Ok(output) => println!("finished: {:?}", output),
+///     Err(pollster::Interrupted) => eprintln!("cancelled"),
+/// }
+/// ```
+pub fn block_on_interruptible<F: IntoFuture>(fut: F) -> Result<F::Output, Interrupted> {
+    let mut fut = core::pin::pin!(fut.into_future());
+    let waker = LOCAL_WAKER.with(Clone::clone);
+    let mut context = Context::from_waker(&waker);
+
+    let _handler = Handler::install();
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut context) {
+            return Ok(output);
+        }
+        park();
+    }
+    Err(Interrupted)
+}
+
+extern "C" fn on_sigint(_: libc::c_int) {
+    HANDLING.fetch_add(1, Ordering::SeqCst);
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    let thread = WAITING.load(Ordering::SeqCst);
+    if !thread.is_null() {
+        // SAFETY: `Handler` only frees the thread once it has cleared `WAITING` and `HANDLING` has dropped back to
+        // zero, and this handler incremented `HANDLING` before loading the pointer. On Linux and Android (the only
+        // targets this module is built for), the standard library's parker is futex-based, so unparking is a single
+        // atomic swap and (at most) a `futex` wake system call. Neither takes a lock, so it's safe to do from a
+        // signal handler. Other platforms' parkers use a mutex and condition variable, which are not.
+        unsafe { (*thread).unpark() };
+    }
+    HANDLING.fetch_sub(1, Ordering::SeqCst);
+}
+
+// The installed `SIGINT` handler, which restores the previous one when dropped.
+struct Handler {
+    previous: libc::sigaction,
+    thread: *mut Thread,
+}
+
+impl Handler {
+    fn install() -> Self {
+        assert!(
+            !INSTALLED.swap(true, Ordering::SeqCst),
+            "`block_on_interruptible` called while another thread is already in it"
+        );
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        let thread = Box::into_raw(Box::new(thread::current()));
+        WAITING.store(thread, Ordering::SeqCst);
+
+        // SAFETY: `sigaction` is a plain C struct, for which all zeroes is a valid (empty) value. The handler only
+        // touches atomics and unparks a thread, both of which are async-signal-safe.
+        let previous = unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            let mut previous = mem::zeroed();
+            let result = libc::sigaction(libc::SIGINT, &action, &mut previous);
+            assert_eq!(result, 0, "failed to install `SIGINT` handler");
+            previous
+        };
+        Self { previous, thread }
+    }
+}
+
+impl Drop for Handler {
+    fn drop(&mut self) {
+        // SAFETY: `previous` was filled in by `sigaction` when the handler was installed.
+        unsafe { libc::sigaction(libc::SIGINT, &self.previous, ptr::null_mut()) };
+
+        // A handler that started before the previous one was restored may still be about to unpark the thread
+        WAITING.store(ptr::null_mut(), Ordering::SeqCst);
+        while HANDLING.load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        // SAFETY: The pointer came from `Box::into_raw`, and no handler can be using it any more.
+        drop(unsafe { Box::from_raw(self.thread) });
+        INSTALLED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// The error returned by [`block_on_interruptible`] when the process receives `SIGINT` before the future is ready.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("interrupted by SIGINT")
+    }
+}
+
+impl Error for Interrupted {}
@@ -0,0 +1,231 @@
+//! A ticker that fires at a fixed period.
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use crate::{block_on, clock, sleep_until, Sleep};
+
+/// Returns an [`Interval`] that ticks every `period`, starting immediately.
+///
+/// Missed ticks are [burst](MissedTicks::Burst). Use [`IntervalBuilder`] to choose otherwise.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let mut interval = pollster::interval(Duration::from_millis(10));
+/// for _ in 0..3 {
+///     interval.blocking_tick();
+///     // Check the device...
+/// }
+/// ```
+pub fn interval(period: Duration) -> Interval {
+    IntervalBuilder::new(period).build()
+}
+
+/// What an [`Interval`] does when ticks are missed, because the consumer fell behind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedTicks {
+    /// Fire every missed tick straight away, one after another, until the interval has caught up.
+    ///
+    /// The number of ticks over any stretch of time stays the same, as if none were missed.
+    #[default]
+    Burst,
+    /// Fire a single tick straight away, then carry on from the next tick that's still to come.
+    ///
+    /// Ticks stay aligned to the original schedule, but the missed ones are lost.
+    Skip,
+}
+
+/// A builder for an [`Interval`].
+///
+/// # Example
+///
+/// ```
+/// use pollster::{IntervalBuilder, MissedTicks};
+/// use std::time::Duration;
+///
+/// let mut interval = IntervalBuilder::new(Duration::from_millis(10))
+///     .immediate(false)
+///     .missed_ticks(MissedTicks::Skip)
+///     .build();
+///
+/// // The first tick is one period from now
+/// interval.blocking_tick();
+/// ```
+#[must_use = "a builder does nothing until `build` is called"]
+#[derive(Clone, Debug)]
+pub struct IntervalBuilder {
+    period: Duration,
+    immediate: bool,
+    missed_ticks: MissedTicks,
+}
+
+impl IntervalBuilder {
+    /// Create a builder for an interval that ticks every `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Self {
+        assert!(period > Duration::ZERO, "`period` must be non-zero");
+        Self {
+            period,
+            immediate: true,
+            missed_ticks: MissedTicks::default(),
+        }
+    }
+
+    /// Set whether the first tick fires immediately (the default), or one period after the interval is built.
+    pub fn immediate(self, immediate: bool) -> Self {
+        Self { immediate, ..self }
+    }
+
+    /// Set what happens when ticks are missed. Defaults to [`MissedTicks::Burst`].
+    pub fn missed_ticks(self, missed_ticks: MissedTicks) -> Self {
+        Self {
+            missed_ticks,
+            ..self
+        }
+    }
+
+    /// Build the interval, starting its schedule now.
+    pub fn build(self) -> Interval {
+        let now = clock::now();
+        Interval {
+            period: self.period,
+            // A tick too far in the future to represent will never come
+            next: if self.immediate {
+                Some(now)
+            } else {
+                now.checked_add(self.period)
+            },
+            missed_ticks: self.missed_ticks,
+            sleep: None,
+        }
+    }
+}
+
+/// A ticker that fires every period.
+///
+/// Created by [`interval`] or [`IntervalBuilder`]. Each tick can be waited for by blocking the thread with
+/// [`Interval::blocking_tick`], or from async code with [`Interval::tick`] (so that it can be raced or joined with
+/// other futures). Either way, it waits in the same way as [`sleep`](crate::sleep). With the `stream` feature, an
+/// interval is also a `Stream` of ticks.
+///
+/// Ticks fire on a fixed schedule, rather than a period after the last one was consumed. When the consumer falls
+/// behind, the interval's [`MissedTicks`] policy decides how it catches up.
+#[must_use = "an interval does nothing unless its ticks are waited for"]
+pub struct Interval {
+    period: Duration,
+    next: Option<Instant>,
+    missed_ticks: MissedTicks,
+    // The sleep until `next`, once it has been polled
+    sleep: Option<Sleep>,
+}
+
+impl Interval {
+    /// Block the thread until the next tick, returning the time it was scheduled for.
+    pub fn blocking_tick(&mut self) -> Instant {
+        block_on(self.tick())
+    }
+
+    /// Returns a future that resolves to the time the next tick was scheduled for, once it has come.
+    pub fn tick(&mut self) -> Tick<'_> {
+        Tick { interval: self }
+    }
+
+    /// Poll for the next tick, returning the time it was scheduled for once it has come.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        let next = match self.next {
+            Some(next) => next,
+            None => return Poll::Pending,
+        };
+        let mut now = clock::now();
+        if now < next {
+            let sleep = self.sleep.get_or_insert_with(|| sleep_until(next));
+            if Pin::new(sleep).poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            now = clock::now();
+        }
+
+        self.sleep = None;
+        self.next = match self.missed_ticks {
+            MissedTicks::Burst => next.checked_add(self.period),
+            MissedTicks::Skip => {
+                // The first tick of the schedule that's still to come
+                let missed = (now - next).as_nanos() / self.period.as_nanos();
+                u32::try_from(missed + 1)
+                    .ok()
+                    .and_then(|ticks| self.period.checked_mul(ticks))
+                    .and_then(|ahead| next.checked_add(ahead))
+            }
+        };
+        Poll::Ready(next)
+    }
+
+    /// Returns the period between ticks.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the policy for missed ticks.
+    pub fn missed_ticks(&self) -> MissedTicks {
+        self.missed_ticks
+    }
+}
+
+impl fmt::Debug for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interval")
+            .field("period", &self.period)
+            .field("next", &self.next)
+            .field("missed_ticks", &self.missed_ticks)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl futures_core::Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_tick(cx).map(Some)
+    }
+}
+
+/// A future that resolves once the next tick of an [`Interval`] has come.
+///
+/// Created by [`Interval::tick`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Tick<'a> {
+    interval: &'a mut Interval,
+}
+
+impl Future for Tick<'_> {
+    type Output = Instant;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.interval.poll_tick(cx)
+    }
+}
+
+impl fmt::Debug for Tick<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tick")
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
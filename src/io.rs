@@ -0,0 +1,183 @@
+//! Blocking adapters for asynchronous IO types.
+//!
+//! The adapters at the root of this module are built on the `futures-io` traits (behind the `futures-io` feature).
+//! Adapters with the same names and behaviour for Tokio's IO traits are in [`tokio`](self::tokio) (behind the
+//! `tokio-io` feature). [`SinkWriter`] (behind the `sink` feature) writes to a [`Sink`](futures_sink::Sink) of
+//! frames instead, and [`StreamReader`] (behind the `stream` feature) reads from a stream of byte chunks. Adapters for
+//! the `embedded-io-async` traits are in [`embedded`](self::embedded) (behind the `embedded-io` feature).
+
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+use std::{
+    fmt,
+    sync::{atomic::Ordering, Arc},
+    task::Waker,
+};
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+use std::{
+    future::poll_fn,
+    io,
+    task::{Context, Poll},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+use crate::{block_on, park, park_timeout, FlagSignal, LOCAL_WAKER};
+
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+#[cfg(feature = "futures-io")]
+mod futures;
+#[cfg(feature = "sink")]
+mod sink;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "tokio-io")]
+pub mod tokio;
+
+#[cfg(feature = "futures-io")]
+pub use self::futures::{
+    copy, copy_async, split, AsyncReadExt, BlockingBufReader, BlockingReadHalf, BlockingReader,
+    BlockingWriteHalf, BlockingWriter, CopyBuilder, CopyError, ReuniteError,
+};
+#[cfg(feature = "sink")]
+pub use self::sink::SinkWriter;
+#[cfg(feature = "stream")]
+pub use self::stream::StreamReader;
+
+// Block the thread until `poll` is ready, failing with `TimedOut` if it isn't ready within `timeout`.
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+fn block_on_io<T>(
+    timeout: Option<Duration>,
+    mut poll: impl FnMut(&mut Context<'_>) -> Poll<io::Result<T>>,
+) -> io::Result<T> {
+    if timeout.is_none() {
+        return block_on(poll_fn(poll));
+    }
+
+    let deadline = Deadline::after(timeout);
+    LOCAL_WAKER.with(|waker| {
+        let mut context = Context::from_waker(waker);
+        loop {
+            match poll(&mut context) {
+                Poll::Ready(result) => break result,
+                Poll::Pending => deadline.park()?,
+            }
+        }
+    })
+}
+
+// The deadline for a single IO operation, however many times it is woken before completing.
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+struct Deadline(Option<Instant>);
+
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+impl Deadline {
+    fn after(timeout: Option<Duration>) -> Self {
+        // A deadline too far in the future to represent will never be reached
+        Self(timeout.and_then(|timeout| Instant::now().checked_add(timeout)))
+    }
+
+    // Park the thread until it is unparked or the deadline passes, or fail with `TimedOut` if it already has.
+    fn park(&self) -> io::Result<()> {
+        match self.0 {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                park_timeout(deadline - now);
+            }
+            None => park(),
+        }
+        Ok(())
+    }
+}
+
+// The state of an adapter in non-blocking mode, where each operation polls the inner object once.
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+#[derive(Default)]
+struct Nonblocking {
+    // Created on the first poll, and again whenever the adapter is polled from a different thread
+    signal: Option<(Arc<FlagSignal>, Waker)>,
+    // Whether the last operation returned `WouldBlock`
+    pending: bool,
+    // A Tokio seek that has been started but hasn't finished yet, so that retrying it doesn't start it again
+    #[cfg(feature = "tokio-io")]
+    seeking: Option<std::io::SeekFrom>,
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+impl Nonblocking {
+    // Poll once, failing with `WouldBlock` if `poll` is pending.
+    fn poll<T>(
+        &mut self,
+        poll: impl FnOnce(&mut Context<'_>) -> Poll<io::Result<T>>,
+    ) -> io::Result<T> {
+        let current = thread::current();
+        let (signal, waker) = match &mut self.signal {
+            Some((signal, waker)) if signal.owning_thread.id() == current.id() => (signal, waker),
+            slot => {
+                let signal = Arc::new(FlagSignal::new());
+                let waker = Waker::from(Arc::clone(&signal));
+                let (signal, waker) = slot.insert((signal, waker));
+                (&mut *signal, &mut *waker)
+            }
+        };
+
+        // Only wakes from this poll onwards are relevant to whether the next one can make progress
+        signal.take_woken();
+        match poll(&mut Context::from_waker(waker)) {
+            Poll::Ready(result) => {
+                self.pending = false;
+                result
+            }
+            Poll::Pending => {
+                self.pending = true;
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+    }
+
+    // Whether the next operation might make progress.
+    fn ready(&self) -> bool {
+        !self.pending
+            || self
+                .signal
+                .as_ref()
+                .map_or(true, |(signal, _)| signal.woken.load(Ordering::Acquire))
+    }
+
+    // Park the thread until the next operation might make progress, or until `timeout` passes.
+    fn wait(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+        while !self.ready() {
+            if deadline.park().is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+impl fmt::Debug for Nonblocking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nonblocking")
+            .field("ready", &self.ready())
+            .finish_non_exhaustive()
+    }
+}
+
+// Block the thread until `poll` is ready, as with `block_on_io`, or only poll it once in non-blocking mode.
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+fn poll_io<T>(
+    nonblocking: &mut Option<Nonblocking>,
+    timeout: Option<Duration>,
+    poll: impl FnMut(&mut Context<'_>) -> Poll<io::Result<T>>,
+) -> io::Result<T> {
+    match nonblocking {
+        Some(nonblocking) => nonblocking.poll(poll),
+        None => block_on_io(timeout, poll),
+    }
+}
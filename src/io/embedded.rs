@@ -0,0 +1,160 @@
+//! Blocking adapters for the `embedded-io-async` traits, implementing their `embedded-io` equivalents.
+//!
+//! These let drivers that only expose [`embedded_io_async`] traits be used by code written against the blocking
+//! [`embedded_io`] traits. Errors are passed through unchanged, as the adapters share the [`ErrorType`] of the type
+//! they wrap.
+//!
+//! Like the rest of pollster, the adapters park the thread while they wait, so they need `std`. `embedded-io-async`
+//! uses `async fn` in traits, so the `embedded-io` feature also needs Rust 1.75 or later.
+
+use embedded_io::{BufRead, ErrorType, Read, Seek, SeekFrom, Write};
+
+use crate::block_on;
+
+/// An [`embedded_io::Read`] implementation that blocks the thread on an [`embedded_io_async::Read`].
+///
+/// [`BufRead`] and [`Seek`] are also implemented, for readers that implement their async equivalents.
+///
+/// # Example
+///
+/// ```
+/// use embedded_io::Read;
+/// use pollster::io::embedded::BlockingReader;
+///
+/// let mut reader = BlockingReader::new(&b"hello"[..]);
+///
+/// let mut buf = [0; 5];
+/// reader.read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, b"hello");
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingReader<R> {
+    reader: R,
+}
+
+impl<R> BlockingReader<R> {
+    /// Wrap an asynchronous reader.
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: ErrorType> ErrorType for BlockingReader<R> {
+    type Error = R::Error;
+}
+
+impl<R: embedded_io_async::Read> Read for BlockingReader<R> {
+    /// Block the thread until the reader has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the reader.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        block_on(self.reader.read(buf))
+    }
+}
+
+impl<R: embedded_io_async::BufRead> BufRead for BlockingReader<R> {
+    /// Block the thread until the reader has filled its buffer.
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        block_on(self.reader.fill_buf())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.reader.consume(amt);
+    }
+}
+
+impl<R: embedded_io_async::Seek> Seek for BlockingReader<R> {
+    /// Block the thread until the reader has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        block_on(self.reader.seek(pos))
+    }
+}
+
+/// An [`embedded_io::Write`] implementation that blocks the thread on an [`embedded_io_async::Write`].
+///
+/// [`Seek`] is also implemented, for writers that implement its async equivalent.
+///
+/// # Example
+///
+/// ```
+/// use embedded_io::Write;
+/// use pollster::io::embedded::BlockingWriter;
+///
+/// let mut buf = [0; 5];
+/// let mut writer = BlockingWriter::new(&mut buf[..]);
+///
+/// writer.write_all(b"hello").unwrap();
+/// assert_eq!(&buf, b"hello");
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingWriter<W> {
+    writer: W,
+}
+
+impl<W> BlockingWriter<W> {
+    /// Wrap an asynchronous writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume this adapter, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: ErrorType> ErrorType for BlockingWriter<W> {
+    type Error = W::Error;
+}
+
+impl<W: embedded_io_async::Write> Write for BlockingWriter<W> {
+    /// Block the thread until the writer has accepted some bytes from `buf`.
+    ///
+    /// Writing an empty `buf` returns `Ok(0)` immediately, without polling the writer.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        block_on(self.writer.write(buf))
+    }
+
+    /// Block the thread until the writer has been flushed.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        block_on(self.writer.flush())
+    }
+}
+
+impl<W: embedded_io_async::Seek> Seek for BlockingWriter<W> {
+    /// Block the thread until the writer has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        block_on(self.writer.seek(pos))
+    }
+}
@@ -0,0 +1,1118 @@
+//! Blocking adapters for the `futures-io` traits.
+
+use std::{
+    error::Error,
+    fmt,
+    future::poll_fn,
+    io::{self, BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+
+use super::{block_on_io, poll_io, Deadline, Nonblocking};
+use crate::{block_on, LOCAL_WAKER};
+
+/// A [`Read`] implementation that blocks the thread on an [`AsyncRead`].
+///
+/// Each call to [`Read::read`] blocks until the reader makes progress. Errors from the reader are passed through
+/// unchanged.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::BlockingReader;
+/// use std::io::Read;
+///
+/// let mut reader = BlockingReader::new(futures::io::Cursor::new(b"hello"));
+///
+/// let mut s = String::new();
+/// reader.read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "hello");
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingReader<R> {
+    reader: R,
+    timeout: Option<Duration>,
+    nonblocking: Option<Nonblocking>,
+}
+
+impl<R> BlockingReader<R> {
+    /// Wrap an asynchronous reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            timeout: None,
+            nonblocking: None,
+        }
+    }
+
+    /// Make each call to [`Read::read`] fail with [`io::ErrorKind::TimedOut`] if the reader doesn't make progress
+    /// within `timeout`.
+    ///
+    /// The timeout applies to each call separately, however many times the reader is woken during it. After a timeout
+    /// the adapter can still be used: the next call polls the reader again, with a new timeout.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set or clear the timeout for each call to [`Read::read`], as with [`with_timeout`](Self::with_timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The timeout for each call to [`Read::read`], if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Switch the adapter into or out of non-blocking mode, as with [`TcpStream::set_nonblocking`].
+    ///
+    /// In non-blocking mode, each read or seek polls the reader exactly once, failing with
+    /// [`io::ErrorKind::WouldBlock`] if it isn't ready rather than blocking the thread. The reader is polled with a
+    /// waker for the current thread, so [`ready`](Self::ready) and [`wait_readable`](Self::wait_readable) can tell when
+    /// it's worth trying again. The [timeout](Self::with_timeout) doesn't apply in non-blocking mode.
+    ///
+    /// [`TcpStream::set_nonblocking`]: std::net::TcpStream::set_nonblocking
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        if nonblocking != self.nonblocking.is_some() {
+            self.nonblocking = nonblocking.then(Nonblocking::default);
+        }
+    }
+
+    /// Whether the adapter is in [non-blocking mode](Self::set_nonblocking).
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.is_some()
+    }
+
+    /// Whether the next read might make progress: that is, whether the last one didn't fail with
+    /// [`io::ErrorKind::WouldBlock`], or the reader has been woken since.
+    ///
+    /// This is always `true` in blocking mode.
+    pub fn ready(&self) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.ready())
+    }
+
+    /// Park the thread until [`ready`](Self::ready) returns `true`, or until `timeout` passes, returning whether the
+    /// adapter is ready.
+    ///
+    /// The reader wakes the thread that last read from it, so this should be called from that thread.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.wait(timeout))
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Read for BlockingReader<R> {
+    /// Block the thread until the reader has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the reader.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut reader = Pin::new(&mut self.reader);
+        poll_io(&mut self.nonblocking, self.timeout, |cx| {
+            reader.as_mut().poll_read(cx, buf)
+        })
+    }
+
+    /// Block the thread until the reader has read some bytes into `bufs`, using [`AsyncRead::poll_read_vectored`].
+    ///
+    /// Reading into only empty buffers returns `Ok(0)` immediately, without polling the reader.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        let mut reader = Pin::new(&mut self.reader);
+        poll_io(&mut self.nonblocking, self.timeout, |cx| {
+            reader.as_mut().poll_read_vectored(cx, bufs)
+        })
+    }
+}
+
+impl<R: AsyncSeek + Unpin> Seek for BlockingReader<R> {
+    /// Block the thread until the reader has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let mut reader = Pin::new(&mut self.reader);
+        poll_io(&mut self.nonblocking, None, |cx| {
+            reader.as_mut().poll_seek(cx, pos)
+        })
+    }
+}
+
+/// A [`BufRead`] implementation that blocks the thread on an [`AsyncBufRead`].
+///
+/// This allows text protocols to be read line by line with [`BufRead::lines`] or [`BufRead::read_line`]. Errors from
+/// the reader are passed through unchanged.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::BlockingBufReader;
+/// use std::io::BufRead;
+///
+/// let reader = BlockingBufReader::new(futures::io::Cursor::new("hello\nworld\n"));
+///
+/// let lines = reader.lines().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(lines, ["hello", "world"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingBufReader<R> {
+    reader: R,
+    timeout: Option<Duration>,
+}
+
+impl<R> BlockingBufReader<R> {
+    /// Wrap an asynchronous buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            timeout: None,
+        }
+    }
+
+    /// Make each call to [`Read::read`] or [`BufRead::fill_buf`] fail with [`io::ErrorKind::TimedOut`] if the reader
+    /// doesn't make progress within `timeout`.
+    ///
+    /// The timeout applies to each call separately, however many times the reader is woken during it. After a timeout
+    /// the adapter can still be used: the next call polls the reader again, with a new timeout.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set or clear the timeout for each call to [`Read::read`] or [`BufRead::fill_buf`], as with
+    /// [`with_timeout`](Self::with_timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The timeout for each call to [`Read::read`] or [`BufRead::fill_buf`], if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Read for BlockingBufReader<R> {
+    /// Block the thread until the reader has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the reader.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut reader = Pin::new(&mut self.reader);
+        block_on_io(self.timeout, |cx| reader.as_mut().poll_read(cx, buf))
+    }
+
+    /// Block the thread until the reader has read some bytes into `bufs`, using [`AsyncRead::poll_read_vectored`].
+    ///
+    /// Reading into only empty buffers returns `Ok(0)` immediately, without polling the reader.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        let mut reader = Pin::new(&mut self.reader);
+        block_on_io(self.timeout, |cx| {
+            reader.as_mut().poll_read_vectored(cx, bufs)
+        })
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> BufRead for BlockingBufReader<R> {
+    /// Block the thread until the reader has filled its buffer, returning the buffered bytes.
+    ///
+    /// # Errors
+    ///
+    /// As well as passing through errors from the reader, this returns [`io::ErrorKind::WouldBlock`] if the reader is
+    /// no longer ready when polled again after filling its buffer, and [`io::ErrorKind::TimedOut`] if a
+    /// [timeout](BlockingBufReader::with_timeout) is set and the reader doesn't fill its buffer in time.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let mut reader = Pin::new(&mut self.reader);
+        let deadline = Deadline::after(self.timeout);
+
+        LOCAL_WAKER.with(|waker| {
+            let mut context = Context::from_waker(waker);
+
+            // The buffer borrowed from a poll can't be returned from within the loop, so once the reader is ready we
+            // poll it one more time (which it answers immediately, since its buffer is now full) and return that.
+            loop {
+                match reader.as_mut().poll_fill_buf(&mut context) {
+                    Poll::Pending => deadline.park()?,
+                    Poll::Ready(Err(err)) => return Err(err),
+                    Poll::Ready(Ok(_)) => break,
+                }
+            }
+            match reader.poll_fill_buf(&mut context) {
+                Poll::Ready(result) => result,
+                Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+            }
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        Pin::new(&mut self.reader).consume(amt)
+    }
+}
+
+impl<R: AsyncSeek + Unpin> Seek for BlockingBufReader<R> {
+    /// Block the thread until the reader has seeked to `pos`.
+    ///
+    /// This adapter doesn't buffer anything itself, so it relies on the reader discarding any data it has buffered, as
+    /// `futures::io::BufReader` does.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let mut reader = Pin::new(&mut self.reader);
+        block_on(poll_fn(|cx| reader.as_mut().poll_seek(cx, pos)))
+    }
+}
+
+/// A [`Write`] implementation that blocks the thread on an [`AsyncWrite`].
+///
+/// Each call to [`Write::write`] blocks until the writer accepts some bytes. Like [`Write::write`] itself, only part of
+/// the buffer may be written: use [`Write::write_all`] to write all of it. Errors from the writer are passed through
+/// unchanged.
+///
+/// Dropping a `BlockingWriter` flushes and closes the underlying writer, waiting for up to a second (see
+/// [`with_drop_timeout`](BlockingWriter::with_drop_timeout)) so that a stalled writer can't hang the thread. Errors
+/// can't be reported from `drop`, so they are ignored, and a panic while closing is caught rather than unwinding out
+/// of `drop`. Call [`BlockingWriter::close`] first to see errors, or [`BlockingWriter::forget_close`] to leave the
+/// writer open.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::BlockingWriter;
+/// use std::io::Write;
+///
+/// let mut writer = BlockingWriter::new(futures::io::Cursor::new(Vec::new()));
+///
+/// writer.write_all(b"hello").unwrap();
+/// writer.close().unwrap();
+/// assert_eq!(writer.into_inner().into_inner(), b"hello");
+/// ```
+pub struct BlockingWriter<W> {
+    // Only `None` once `into_inner` has taken it
+    writer: Option<W>,
+    nonblocking: Option<Nonblocking>,
+    drop_close: Option<CloseFn<W>>,
+    drop_timeout: Duration,
+}
+
+impl<W> BlockingWriter<W> {
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.as_ref().expect(TAKEN)
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.as_mut().expect(TAKEN)
+    }
+
+    /// Consume this adapter, returning the underlying writer without closing it.
+    pub fn into_inner(mut self) -> W {
+        self.writer.take().expect(TAKEN)
+    }
+
+    /// Set how long dropping the adapter waits for the writer to be flushed and closed, instead of one second.
+    pub fn with_drop_timeout(mut self, timeout: Duration) -> Self {
+        self.drop_timeout = timeout;
+        self
+    }
+
+    /// Stop dropping the adapter from flushing and closing the writer, for callers that manage that themselves.
+    pub fn forget_close(&mut self) {
+        self.drop_close = None;
+    }
+
+    /// Switch the adapter into or out of non-blocking mode, as with [`TcpStream::set_nonblocking`].
+    ///
+    /// In non-blocking mode, each write, flush, close or seek polls the writer exactly once, failing with
+    /// [`io::ErrorKind::WouldBlock`] if it isn't ready rather than blocking the thread. The writer is polled with a
+    /// waker for the current thread, so [`ready`](Self::ready) and [`wait_writable`](Self::wait_writable) can tell when
+    /// it's worth trying again.
+    ///
+    /// [`TcpStream::set_nonblocking`]: std::net::TcpStream::set_nonblocking
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        if nonblocking != self.nonblocking.is_some() {
+            self.nonblocking = nonblocking.then(Nonblocking::default);
+        }
+    }
+
+    /// Whether the adapter is in [non-blocking mode](Self::set_nonblocking).
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.is_some()
+    }
+
+    /// Whether the next operation might make progress: that is, whether the last one didn't fail with
+    /// [`io::ErrorKind::WouldBlock`], or the writer has been woken since.
+    ///
+    /// This is always `true` in blocking mode.
+    pub fn ready(&self) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.ready())
+    }
+
+    /// Park the thread until [`ready`](Self::ready) returns `true`, or until `timeout` passes, returning whether the
+    /// adapter is ready.
+    ///
+    /// The writer wakes the thread that last used it, so this should be called from that thread.
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.wait(timeout))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> BlockingWriter<W> {
+    /// Wrap an asynchronous writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            nonblocking: None,
+            drop_close: Some(close_within::<W>),
+            drop_timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Block the thread until the writer has been flushed and closed.
+    ///
+    /// Once this succeeds, dropping the adapter won't try to close the writer again.
+    pub fn close(&mut self) -> io::Result<()> {
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_close(cx)
+        })?;
+        self.drop_close = None;
+        Ok(())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Write for BlockingWriter<W> {
+    /// Block the thread until the writer has accepted some bytes from `buf`.
+    ///
+    /// Writing an empty `buf` returns `Ok(0)` immediately, without polling the writer.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_write(cx, buf)
+        })
+    }
+
+    /// Block the thread until the writer has accepted some bytes from `bufs`, using
+    /// [`AsyncWrite::poll_write_vectored`].
+    ///
+    /// Writing only empty buffers returns `Ok(0)` immediately, without polling the writer.
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_write_vectored(cx, bufs)
+        })
+    }
+
+    /// Block the thread until the writer has been flushed.
+    fn flush(&mut self) -> io::Result<()> {
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_flush(cx)
+        })
+    }
+}
+
+impl<W: AsyncSeek + Unpin> Seek for BlockingWriter<W> {
+    /// Block the thread until the writer has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_seek(cx, pos)
+        })
+    }
+}
+
+impl<W: AsyncWrite + Unpin + Default> Default for BlockingWriter<W> {
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for BlockingWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingWriter")
+            .field("writer", self.get_ref())
+            .field("nonblocking", &self.nonblocking)
+            .field("drop_timeout", &self.drop_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> Drop for BlockingWriter<W> {
+    fn drop(&mut self) {
+        if let (Some(close), Some(writer)) = (self.drop_close, self.writer.as_mut()) {
+            // There's nowhere to report an error to, and a panic mustn't unwind out of `drop`
+            let timeout = self.drop_timeout;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| close(writer, timeout)));
+        }
+    }
+}
+
+const TAKEN: &str = "the writer has been taken by `into_inner`";
+
+// `Drop` can't require `W: AsyncWrite`, so `new` stores the function that closes the writer instead.
+type CloseFn<W> = fn(&mut W, Duration) -> io::Result<()>;
+
+// Flushes and then closes `writer`, giving up once `timeout` has elapsed.
+fn close_within<W: AsyncWrite + Unpin>(writer: &mut W, timeout: Duration) -> io::Result<()> {
+    let mut writer = Pin::new(writer);
+    let mut flushed = false;
+    block_on_io(Some(timeout), |cx| {
+        if !flushed {
+            match writer.as_mut().poll_flush(cx) {
+                Poll::Ready(Ok(())) => flushed = true,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        writer.as_mut().poll_close(cx)
+    })
+}
+
+/// Block the thread until all of `reader` has been copied into `writer`, returning the number of bytes copied.
+///
+/// This is the blocking equivalent of `futures::io::copy`, for an asynchronous reader and a synchronous writer. Short
+/// reads and writes are handled by looping until the reader reaches EOF. `writer` is not flushed afterwards, as with
+/// [`std::io::copy`].
+///
+/// Uses an 8 KiB buffer: see [`CopyBuilder`] to change it.
+///
+/// # Errors
+///
+/// Returns the first error from either side, along with the number of bytes that were successfully written before
+/// it occurred. [`CopyError`] converts into [`io::Error`], so this can be used with `?` in functions returning
+/// [`io::Result`].
+///
+/// # Example
+///
+/// ```
+/// let reader = futures::io::Cursor::new(b"hello");
+/// let mut writer = Vec::new();
+///
+/// assert_eq!(pollster::io::copy(reader, &mut writer).unwrap(), 5);
+/// assert_eq!(writer, b"hello");
+/// ```
+pub fn copy<R: AsyncRead, W: Write + ?Sized>(reader: R, writer: &mut W) -> Result<u64, CopyError> {
+    CopyBuilder::new().copy(reader, writer)
+}
+
+/// Block the thread until all of `reader` has been copied into `writer` and `writer` has been flushed, returning the
+/// number of bytes copied.
+///
+/// Like [`copy`], but for an asynchronous writer. Both `reader` and `writer` are taken by value, but mutable references
+/// to [`Unpin`] readers and writers can be passed too.
+///
+/// # Example
+///
+/// ```
+/// let reader = futures::io::Cursor::new(b"hello");
+/// let mut writer = futures::io::Cursor::new(Vec::new());
+///
+/// assert_eq!(pollster::io::copy_async(reader, &mut writer).unwrap(), 5);
+/// assert_eq!(writer.into_inner(), b"hello");
+/// ```
+pub fn copy_async<R: AsyncRead, W: AsyncWrite>(reader: R, writer: W) -> Result<u64, CopyError> {
+    CopyBuilder::new().copy_async(reader, writer)
+}
+
+/// A builder for configuring [`copy`] and [`copy_async`].
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::CopyBuilder;
+///
+/// let reader = futures::io::Cursor::new(vec![0; 100_000]);
+/// let mut writer = Vec::new();
+///
+/// let copied = CopyBuilder::new().buffer_size(64 * 1024).copy(reader, &mut writer).unwrap();
+/// assert_eq!(copied, 100_000);
+/// ```
+#[must_use = "a builder does nothing until `copy` or `copy_async` is called"]
+#[derive(Clone, Copy, Debug)]
+pub struct CopyBuilder {
+    buffer_size: usize,
+}
+
+impl CopyBuilder {
+    /// Create a builder with the default 8 KiB buffer.
+    pub fn new() -> Self {
+        Self {
+            buffer_size: 8 * 1024,
+        }
+    }
+
+    /// Set the size of the buffer used to copy data, in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer_size` is zero.
+    pub fn buffer_size(self, buffer_size: usize) -> Self {
+        assert!(buffer_size > 0, "`buffer_size` must be non-zero");
+        Self { buffer_size }
+    }
+
+    /// Like [`copy`], using this builder's configuration.
+    pub fn copy<R: AsyncRead, W: Write + ?Sized>(
+        self,
+        reader: R,
+        writer: &mut W,
+    ) -> Result<u64, CopyError> {
+        self.run(reader, SyncWriter(writer))
+    }
+
+    /// Like [`copy_async`], using this builder's configuration.
+    pub fn copy_async<R: AsyncRead, W: AsyncWrite>(
+        self,
+        reader: R,
+        writer: W,
+    ) -> Result<u64, CopyError> {
+        self.run(reader, core::pin::pin!(writer))
+    }
+
+    fn run<R: AsyncRead, W: CopyWriter>(self, reader: R, mut writer: W) -> Result<u64, CopyError> {
+        let mut reader = core::pin::pin!(reader);
+        let mut buf = vec![0; self.buffer_size];
+        // The bytes in `buf[pos..len]` have been read, but not yet written
+        let (mut pos, mut len) = (0, 0);
+        let mut done = false;
+        let mut copied = 0;
+
+        block_on(poll_fn(|cx| loop {
+            let result = if pos < len {
+                match writer.poll_write(cx, &buf[pos..len]) {
+                    Poll::Ready(Ok(0)) => Err(io::ErrorKind::WriteZero.into()),
+                    Poll::Ready(Ok(n)) => {
+                        pos += n;
+                        copied += n as u64;
+                        continue;
+                    }
+                    Poll::Ready(Err(err)) => Err(err),
+                    Poll::Pending => return Poll::Pending,
+                }
+            } else if done {
+                match writer.poll_flush(cx) {
+                    Poll::Ready(Ok(())) => return Poll::Ready(Ok(copied)),
+                    Poll::Ready(Err(err)) => Err(err),
+                    Poll::Pending => return Poll::Pending,
+                }
+            } else {
+                match reader.as_mut().poll_read(cx, &mut buf) {
+                    Poll::Ready(Ok(0)) => {
+                        done = true;
+                        continue;
+                    }
+                    Poll::Ready(Ok(n)) => {
+                        (pos, len) = (0, n);
+                        continue;
+                    }
+                    Poll::Ready(Err(err)) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Poll::Ready(Err(err)) => Err(err),
+                    Poll::Pending => return Poll::Pending,
+                }
+            };
+            break Poll::Ready(result.map_err(|error| CopyError { error, copied }));
+        }))
+    }
+}
+
+// The writing side of a copy, which may be synchronous or asynchronous.
+trait CopyWriter {
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>>;
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+}
+
+struct SyncWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: Write + ?Sized> CopyWriter for SyncWriter<'_, W> {
+    fn poll_write(&mut self, _: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(loop {
+            match self.0.write(buf) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        })
+    }
+
+    // Like `std::io::copy`, leave flushing to the caller
+    fn poll_flush(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> CopyWriter for Pin<&mut W> {
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.as_mut().poll_write(cx, buf)
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.as_mut().poll_flush(cx)
+    }
+}
+
+impl Default for CopyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The error returned by [`copy`] and [`copy_async`], including the number of bytes copied before the error occurred.
+#[derive(Debug)]
+pub struct CopyError {
+    error: io::Error,
+    copied: u64,
+}
+
+impl CopyError {
+    /// The number of bytes that were written before the error occurred.
+    pub fn copied(&self) -> u64 {
+        self.copied
+    }
+
+    /// A reference to the underlying IO error.
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Consume this error, returning the underlying IO error.
+    pub fn into_inner(self) -> io::Error {
+        self.error
+    }
+}
+
+impl fmt::Display for CopyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "copy failed after {} bytes: {}", self.copied, self.error)
+    }
+}
+
+impl Error for CopyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<CopyError> for io::Error {
+    fn from(err: CopyError) -> Self {
+        err.error
+    }
+}
+
+/// An extension trait that allows blocking on reads from an [`AsyncRead`] without wrapping it in a
+/// [`BlockingReader`].
+///
+/// These behave like their counterparts on [`Read`], and retry reads that fail with [`io::ErrorKind::Interrupted`].
+pub trait AsyncReadExt: AsyncRead {
+    /// Block the thread until the reader reaches EOF, appending everything read to `buf` and returning the number of
+    /// bytes read.
+    ///
+    /// Any spare capacity in `buf` is used before it is grown. If an error occurs, everything read up to that point is
+    /// left in `buf`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::io::AsyncReadExt as _;
+    ///
+    /// let mut reader = futures::io::Cursor::new(b"hello");
+    ///
+    /// let mut buf = Vec::new();
+    /// assert_eq!(reader.blocking_read_to_end(&mut buf).unwrap(), 5);
+    /// assert_eq!(buf, b"hello");
+    /// ```
+    fn blocking_read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize>
+    where
+        Self: Unpin,
+    {
+        read_to_end(Pin::new(self), buf)
+    }
+
+    /// Block the thread until the reader reaches EOF, appending everything read to `buf` and returning the number of
+    /// bytes read.
+    ///
+    /// As with [`Read::read_to_string`], if the data read isn't valid UTF-8 then an [`io::ErrorKind::InvalidData`]
+    /// error is returned and `buf` is left unchanged. The bytes that were read aren't lost: the source of the error is
+    /// a [`FromUtf8Error`](std::string::FromUtf8Error) containing them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::io::AsyncReadExt as _;
+    ///
+    /// let mut reader = futures::io::Cursor::new("hello");
+    ///
+    /// let mut buf = String::new();
+    /// assert_eq!(reader.blocking_read_to_string(&mut buf).unwrap(), 5);
+    /// assert_eq!(buf, "hello");
+    /// ```
+    fn blocking_read_to_string(&mut self, buf: &mut String) -> io::Result<usize>
+    where
+        Self: Unpin,
+    {
+        let mut bytes = Vec::new();
+        let result = read_to_end(Pin::new(self), &mut bytes);
+        match String::from_utf8(bytes) {
+            Ok(s) => {
+                buf.push_str(&s);
+                result
+            }
+            // An IO error takes precedence, as it does for `Read::read_to_string`
+            Err(err) => result.and_then(|_| Err(io::Error::new(io::ErrorKind::InvalidData, err))),
+        }
+    }
+
+    /// Block the thread until `buf` has been filled by the reader.
+    ///
+    /// If the reader reaches EOF first, an [`io::ErrorKind::UnexpectedEof`] error is returned. The contents of `buf`
+    /// are unspecified after an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::io::AsyncReadExt as _;
+    ///
+    /// let mut reader = futures::io::Cursor::new(b"hello");
+    ///
+    /// let mut buf = [0; 4];
+    /// reader.blocking_read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"hell");
+    /// assert!(reader.blocking_read_exact(&mut buf).is_err());
+    /// ```
+    fn blocking_read_exact(&mut self, buf: &mut [u8]) -> io::Result<()>
+    where
+        Self: Unpin,
+    {
+        let mut reader = Pin::new(self);
+        let mut filled = 0;
+        block_on(poll_fn(|cx| loop {
+            if filled == buf.len() {
+                return Poll::Ready(Ok(()));
+            }
+            match reader.as_mut().poll_read(cx, &mut buf[filled..]) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    )))
+                }
+                Poll::Ready(Ok(n)) => filled += n,
+                Poll::Ready(Err(err)) if err.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }))
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadExt for R {}
+
+// The first read of `read_to_end` is this size, doubling each time a read fills the space it was given.
+const MIN_READ_SIZE: usize = 32;
+// Reads are capped at this size, so that a huge buffer doesn't need to be zeroed before the reader can fill it.
+const MAX_READ_SIZE: usize = 64 * 1024;
+
+fn read_to_end<R: AsyncRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    buf: &mut Vec<u8>,
+) -> io::Result<usize> {
+    let start = buf.len();
+    // The reader needs initialised memory to read into, so `buf` is zero-extended ahead of each read. This truncates
+    // it back to what was actually read, however the read ends.
+    let mut buf = Truncate { filled: start, buf };
+    let start_capacity = buf.buf.capacity();
+    let mut read_size = MIN_READ_SIZE;
+
+    block_on(poll_fn(|cx| loop {
+        let filled = buf.filled;
+        let result = if filled == buf.buf.len() && buf.buf.len() == start_capacity {
+            // The caller may have reserved exactly enough space, so check for EOF with a small read onto the stack
+            // before growing the buffer
+            let mut probe = [0; MIN_READ_SIZE];
+            let result = reader.as_mut().poll_read(cx, &mut probe);
+            if let Poll::Ready(Ok(n)) = result {
+                buf.buf.extend_from_slice(&probe[..n]);
+            }
+            result
+        } else {
+            if filled == buf.buf.len() {
+                // `Vec` doubles its capacity when it has to grow
+                if buf.buf.len() == buf.buf.capacity() {
+                    buf.buf.reserve(read_size);
+                }
+                let extra = (buf.buf.capacity() - buf.buf.len()).min(read_size);
+                buf.buf.resize(buf.buf.len() + extra, 0);
+            }
+            let result = reader.as_mut().poll_read(cx, &mut buf.buf[filled..]);
+            if let Poll::Ready(Ok(n)) = result {
+                if filled + n == buf.buf.len() {
+                    read_size = (read_size * 2).min(MAX_READ_SIZE);
+                }
+            }
+            result
+        };
+
+        match result {
+            Poll::Ready(Ok(0)) => return Poll::Ready(Ok(filled - start)),
+            Poll::Ready(Ok(n)) => buf.filled += n,
+            Poll::Ready(Err(err)) if err.kind() == io::ErrorKind::Interrupted => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        }
+    }))
+}
+
+struct Truncate<'a> {
+    buf: &'a mut Vec<u8>,
+    filled: usize,
+}
+
+impl Drop for Truncate<'_> {
+    fn drop(&mut self) {
+        self.buf.truncate(self.filled);
+    }
+}
+
+/// Split a stream that can be both read from and written to into halves that block the thread independently.
+///
+/// Each half can be moved to its own thread, so that one thread can read from the stream while another writes to
+/// it. The stream is only locked while it is being polled, so a half that is waiting on the stream doesn't stop the
+/// other half from using it. The halves can be put back together with [`BlockingReadHalf::reunite`].
+///
+/// # Example
+///
+/// ```
+/// use std::io::{Read, Write};
+///
+/// let stream = futures::io::Cursor::new(vec![0; 5]);
+/// let (mut reader, mut writer) = pollster::io::split(stream);
+///
+/// writer.write_all(b"hi").unwrap();
+/// let mut rest = Vec::new();
+/// reader.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, [0; 3]);
+///
+/// let stream = reader.reunite(writer).unwrap();
+/// assert_eq!(stream.into_inner(), b"hi\0\0\0");
+/// ```
+pub fn split<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+) -> (BlockingReadHalf<S>, BlockingWriteHalf<S>) {
+    let stream = Arc::new(Mutex::new(stream));
+    (
+        BlockingReadHalf {
+            stream: Arc::clone(&stream),
+        },
+        BlockingWriteHalf { stream },
+    )
+}
+
+/// The reading half of a stream, created by [`split`].
+pub struct BlockingReadHalf<S> {
+    stream: Arc<Mutex<S>>,
+}
+
+impl<S: Unpin> BlockingReadHalf<S> {
+    /// Put the halves of a stream back together, returning the original stream.
+    ///
+    /// # Errors
+    ///
+    /// If the halves came from different calls to [`split`], they are returned in a [`ReuniteError`].
+    pub fn reunite(self, other: BlockingWriteHalf<S>) -> Result<S, ReuniteError<S>> {
+        if !Arc::ptr_eq(&self.stream, &other.stream) {
+            return Err(ReuniteError {
+                read: self,
+                write: other,
+            });
+        }
+
+        drop(other);
+        let stream = Arc::try_unwrap(self.stream)
+            .ok()
+            .expect("both halves have been consumed");
+        Ok(stream.into_inner().unwrap())
+    }
+}
+
+impl<S: AsyncRead + Unpin> Read for BlockingReadHalf<S> {
+    /// Block the thread until the stream has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the stream.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_read(cx, buf)
+        }))
+    }
+
+    /// Block the thread until the stream has read some bytes into `bufs`, using [`AsyncRead::poll_read_vectored`].
+    ///
+    /// Reading into only empty buffers returns `Ok(0)` immediately, without polling the stream.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_read_vectored(cx, bufs)
+        }))
+    }
+}
+
+impl<S> fmt::Debug for BlockingReadHalf<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingReadHalf").finish_non_exhaustive()
+    }
+}
+
+/// The writing half of a stream, created by [`split`].
+pub struct BlockingWriteHalf<S> {
+    stream: Arc<Mutex<S>>,
+}
+
+impl<S: AsyncWrite + Unpin> BlockingWriteHalf<S> {
+    /// Block the thread until the stream has been flushed and closed.
+    pub fn close(&mut self) -> io::Result<()> {
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_close(cx)
+        }))
+    }
+}
+
+impl<S: AsyncWrite + Unpin> Write for BlockingWriteHalf<S> {
+    /// Block the thread until the stream has accepted some bytes from `buf`.
+    ///
+    /// Writing an empty `buf` returns `Ok(0)` immediately, without polling the stream.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_write(cx, buf)
+        }))
+    }
+
+    /// Block the thread until the stream has accepted some bytes from `bufs`, using
+    /// [`AsyncWrite::poll_write_vectored`].
+    ///
+    /// Writing only empty buffers returns `Ok(0)` immediately, without polling the stream.
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_write_vectored(cx, bufs)
+        }))
+    }
+
+    /// Block the thread until the stream has been flushed.
+    fn flush(&mut self) -> io::Result<()> {
+        block_on(poll_fn(|cx| {
+            Pin::new(&mut *self.stream.lock().unwrap()).poll_flush(cx)
+        }))
+    }
+}
+
+impl<S> fmt::Debug for BlockingWriteHalf<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingWriteHalf").finish_non_exhaustive()
+    }
+}
+
+/// The error returned by [`BlockingReadHalf::reunite`] when the halves came from different streams.
+pub struct ReuniteError<S> {
+    read: BlockingReadHalf<S>,
+    write: BlockingWriteHalf<S>,
+}
+
+impl<S> ReuniteError<S> {
+    /// Consume this error, returning the halves that couldn't be reunited.
+    pub fn into_inner(self) -> (BlockingReadHalf<S>, BlockingWriteHalf<S>) {
+        (self.read, self.write)
+    }
+}
+
+impl<S> fmt::Debug for ReuniteError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReuniteError").finish_non_exhaustive()
+    }
+}
+
+impl<S> fmt::Display for ReuniteError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tried to reunite halves that came from different streams"
+        )
+    }
+}
+
+impl<S> Error for ReuniteError<S> {}
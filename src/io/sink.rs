@@ -0,0 +1,176 @@
+//! A blocking writer over a [`Sink`] of frames.
+
+use std::{
+    error::Error,
+    fmt,
+    future::poll_fn,
+    io::{self, Write},
+    pin::Pin,
+};
+
+use futures_sink::Sink;
+
+use crate::block_on;
+
+/// A [`Write`] implementation that sends the bytes written to it as frames on a [`Sink`].
+///
+/// Each frame is built from a byte slice by a closure, such as `<[u8]>::to_vec` or `bytes::Bytes::copy_from_slice`.
+/// By default every call to [`Write::write`] becomes one frame. With [`SinkWriter::coalesce`], small writes are
+/// gathered into a buffer instead, which is only sent as a frame once it's full or the writer is flushed.
+///
+/// Each write blocks the thread until the sink is ready to accept a frame. [`Write::flush`] sends any buffered bytes
+/// and then blocks until the sink has been flushed. Errors from the sink are returned as [`io::Error`]s of kind
+/// [`Other`](io::ErrorKind::Other), with the sink's error as the source.
+///
+/// Buffered bytes are not sent when the writer is dropped: call [`Write::flush`] first.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::SinkWriter;
+/// use std::io::Write;
+///
+/// let (tx, mut rx) = futures::channel::mpsc::unbounded();
+/// let mut writer = SinkWriter::new(tx, <[u8]>::to_vec).coalesce(64);
+///
+/// write!(writer, "hello, {}", "world").unwrap();
+/// writer.flush().unwrap();
+///
+/// // The three writes made by `write!` were sent as a single frame
+/// assert_eq!(rx.try_next().unwrap(), Some(b"hello, world".to_vec()));
+/// ```
+pub struct SinkWriter<S, F> {
+    sink: S,
+    to_item: F,
+    buffer: Vec<u8>,
+    capacity: usize,
+}
+
+impl<S, F> SinkWriter<S, F> {
+    /// Wrap a sink, turning the bytes of each write into a frame with `to_item`.
+    pub fn new(sink: S, to_item: F) -> Self {
+        Self {
+            sink,
+            to_item,
+            buffer: Vec::new(),
+            capacity: 0,
+        }
+    }
+
+    /// Gather writes into a buffer of `capacity` bytes, sending it as a single frame once the next write wouldn't fit
+    /// (or the writer is flushed).
+    ///
+    /// A write of at least `capacity` bytes is sent as its own frame, after whatever was buffered before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn coalesce(self, capacity: usize) -> Self {
+        assert!(capacity > 0, "`capacity` must be non-zero");
+        Self {
+            buffer: Vec::with_capacity(capacity),
+            capacity,
+            ..self
+        }
+    }
+
+    /// Get the bytes that have been written but not yet sent as a frame.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Get a reference to the underlying sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the underlying sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consume this adapter, returning the underlying sink.
+    ///
+    /// Any bytes that haven't been sent yet are discarded.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S, F, Item> SinkWriter<S, F>
+where
+    S: Sink<Item> + Unpin,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    F: FnMut(&[u8]) -> Item,
+{
+    // Block the thread until the sink is ready to accept a frame.
+    fn ready(&mut self) -> io::Result<()> {
+        let mut sink = Pin::new(&mut self.sink);
+        block_on(poll_fn(|cx| sink.as_mut().poll_ready(cx))).map_err(other)
+    }
+
+    // Send `buf` as a single frame.
+    fn send(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.ready()?;
+        let item = (self.to_item)(buf);
+        Pin::new(&mut self.sink).start_send(item).map_err(other)
+    }
+
+    // Send the buffered bytes as a frame, if there are any. They stay buffered if the sink fails to become ready.
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.ready()?;
+        let item = (self.to_item)(&self.buffer);
+        self.buffer.clear();
+        Pin::new(&mut self.sink).start_send(item).map_err(other)
+    }
+}
+
+impl<S, F, Item> Write for SinkWriter<S, F>
+where
+    S: Sink<Item> + Unpin,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    F: FnMut(&[u8]) -> Item,
+{
+    /// Send `buf` as a frame, or add it to the buffer if writes are being coalesced.
+    ///
+    /// Writing an empty `buf` returns `Ok(0)` immediately, without sending an empty frame.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.buffer.len() + buf.len() > self.capacity {
+            self.send_buffer()?;
+        }
+        if buf.len() >= self.capacity {
+            self.send(buf)?;
+        } else {
+            self.buffer.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    /// Send any buffered bytes as a frame, and then block the thread until the sink has been flushed.
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffer()?;
+        let mut sink = Pin::new(&mut self.sink);
+        block_on(poll_fn(|cx| sink.as_mut().poll_flush(cx))).map_err(other)
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for SinkWriter<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SinkWriter")
+            .field("sink", &self.sink)
+            .field("buffered", &self.buffer.len())
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+fn other(error: impl Into<Box<dyn Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
@@ -0,0 +1,139 @@
+//! A blocking reader over a [`Stream`](futures_core::Stream) of byte chunks.
+
+use std::{
+    fmt,
+    future::poll_fn,
+    io::{self, BufRead, Read},
+    pin::Pin,
+};
+
+use futures_core::TryStream;
+
+use crate::block_on;
+
+/// A [`Read`] and [`BufRead`] implementation that reads from a [`Stream`](futures_core::Stream) of byte chunks.
+///
+/// This suits HTTP bodies and similar, which usually arrive as a stream of `Result<Bytes, E>`. The current chunk is
+/// kept until it has been read, and the thread blocks on the stream for the next chunk once it has been used up. Empty
+/// chunks are skipped, and the end of the stream is EOF.
+///
+/// Stream errors are converted to [`io::Error`]s with the closure passed to [`StreamReader::new`]. Reading after an
+/// error polls the stream for the next chunk as usual.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::StreamReader;
+/// use std::io::{self, Read};
+///
+/// let chunks = futures::stream::iter([Ok(&b"hello, "[..]), Ok(b"world")]);
+/// let mut reader = StreamReader::new(chunks, |err: io::Error| err);
+///
+/// let mut text = String::new();
+/// reader.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "hello, world");
+/// ```
+pub struct StreamReader<S: TryStream, M> {
+    stream: S,
+    map_err: M,
+    chunk: Option<S::Ok>,
+    // How much of `chunk` has been read
+    pos: usize,
+    done: bool,
+}
+
+impl<S: TryStream, M> StreamReader<S, M> {
+    /// Wrap a stream of byte chunks, converting its errors with `map_err`.
+    pub fn new(stream: S, map_err: M) -> Self {
+        Self {
+            stream,
+            map_err,
+            chunk: None,
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Get a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Get a mutable reference to the underlying stream.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consume this adapter, returning the underlying stream.
+    ///
+    /// Any part of the current chunk that hasn't been read yet is discarded.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, M> Read for StreamReader<S, M>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    M: FnMut(S::Error) -> io::Error,
+{
+    /// Read from the current chunk, blocking the thread on the stream for the next one if it has been used up.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<S, M> BufRead for StreamReader<S, M>
+where
+    S: TryStream + Unpin,
+    S::Ok: AsRef<[u8]>,
+    M: FnMut(S::Error) -> io::Error,
+{
+    /// Get the unread part of the current chunk, blocking the thread on the stream for the next one if it has been
+    /// used up.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let len = |chunk: &Option<S::Ok>| chunk.as_ref().map_or(0, |chunk| chunk.as_ref().len());
+        while !self.done && self.pos >= len(&self.chunk) {
+            let mut stream = Pin::new(&mut self.stream);
+            match block_on(poll_fn(|cx| stream.as_mut().try_poll_next(cx))) {
+                Some(Ok(chunk)) => {
+                    self.chunk = Some(chunk);
+                    self.pos = 0;
+                }
+                Some(Err(err)) => return Err((self.map_err)(err)),
+                // Streams don't have to support being polled again once they've finished
+                None => {
+                    self.chunk = None;
+                    self.done = true;
+                }
+            }
+        }
+
+        Ok(match &self.chunk {
+            Some(chunk) => &chunk.as_ref()[self.pos..],
+            None => &[],
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
+impl<S: TryStream + fmt::Debug, M> fmt::Debug for StreamReader<S, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamReader")
+            .field("stream", &self.stream)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,548 @@
+//! Blocking adapters for Tokio's IO traits.
+//!
+//! These behave identically to the adapters with the same names in the [parent module](super), including their
+//! [non-blocking mode](BlockingReader::set_nonblocking), so code can switch between them without any other changes.
+
+use std::{
+    fmt,
+    future::poll_fn,
+    io::{self, BufRead, IoSlice, Read, Seek, SeekFrom, Write},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use ::tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+use super::{block_on_io, poll_io, Deadline, Nonblocking};
+use crate::{block_on, LOCAL_WAKER};
+
+/// A [`Read`] implementation that blocks the thread on an [`AsyncRead`].
+///
+/// Each call to [`Read::read`] blocks until the reader makes progress. Errors from the reader are passed through
+/// unchanged.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::tokio::BlockingReader;
+/// use std::io::Read;
+///
+/// let mut reader = BlockingReader::new(std::io::Cursor::new(b"hello"));
+///
+/// let mut s = String::new();
+/// reader.read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "hello");
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingReader<R> {
+    reader: R,
+    timeout: Option<Duration>,
+    nonblocking: Option<Nonblocking>,
+}
+
+impl<R> BlockingReader<R> {
+    /// Wrap an asynchronous reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            timeout: None,
+            nonblocking: None,
+        }
+    }
+
+    /// Make each call to [`Read::read`] fail with [`io::ErrorKind::TimedOut`] if the reader doesn't make progress
+    /// within `timeout`.
+    ///
+    /// The timeout applies to each call separately, however many times the reader is woken during it. After a timeout
+    /// the adapter can still be used: the next call polls the reader again, with a new timeout.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set or clear the timeout for each call to [`Read::read`], as with [`with_timeout`](Self::with_timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The timeout for each call to [`Read::read`], if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Switch the adapter into or out of non-blocking mode, as with [`TcpStream::set_nonblocking`].
+    ///
+    /// In non-blocking mode, each read or seek polls the reader exactly once, failing with
+    /// [`io::ErrorKind::WouldBlock`] if it isn't ready rather than blocking the thread. The reader is polled with a
+    /// waker for the current thread, so [`ready`](Self::ready) and [`wait_readable`](Self::wait_readable) can tell when
+    /// it's worth trying again. The [timeout](Self::with_timeout) doesn't apply in non-blocking mode.
+    ///
+    /// A seek that fails with `WouldBlock` has still been started, so the next call to seek to the same position waits
+    /// for it to finish rather than seeking again.
+    ///
+    /// [`TcpStream::set_nonblocking`]: std::net::TcpStream::set_nonblocking
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        if nonblocking != self.nonblocking.is_some() {
+            self.nonblocking = nonblocking.then(Nonblocking::default);
+        }
+    }
+
+    /// Whether the adapter is in [non-blocking mode](Self::set_nonblocking).
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.is_some()
+    }
+
+    /// Whether the next read might make progress: that is, whether the last one didn't fail with
+    /// [`io::ErrorKind::WouldBlock`], or the reader has been woken since.
+    ///
+    /// This is always `true` in blocking mode.
+    pub fn ready(&self) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.ready())
+    }
+
+    /// Park the thread until [`ready`](Self::ready) returns `true`, or until `timeout` passes, returning whether the
+    /// adapter is ready.
+    ///
+    /// The reader wakes the thread that last read from it, so this should be called from that thread.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.wait(timeout))
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Read for BlockingReader<R> {
+    /// Block the thread until the reader has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the reader.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        read(Pin::new(&mut self.reader), buf, &mut self.nonblocking, self.timeout)
+    }
+}
+
+impl<R: AsyncSeek + Unpin> Seek for BlockingReader<R> {
+    /// Block the thread until the reader has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        seek(Pin::new(&mut self.reader), &mut self.nonblocking, pos)
+    }
+}
+
+/// A [`BufRead`] implementation that blocks the thread on an [`AsyncBufRead`].
+///
+/// This allows text protocols to be read line by line with [`BufRead::lines`] or [`BufRead::read_line`]. Errors from
+/// the reader are passed through unchanged.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::tokio::BlockingBufReader;
+/// use std::io::BufRead;
+///
+/// let reader = BlockingBufReader::new(std::io::Cursor::new("hello\nworld\n"));
+///
+/// let lines = reader.lines().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(lines, ["hello", "world"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockingBufReader<R> {
+    reader: R,
+    timeout: Option<Duration>,
+}
+
+impl<R> BlockingBufReader<R> {
+    /// Wrap an asynchronous buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            timeout: None,
+        }
+    }
+
+    /// Make each call to [`Read::read`] or [`BufRead::fill_buf`] fail with [`io::ErrorKind::TimedOut`] if the reader
+    /// doesn't make progress within `timeout`.
+    ///
+    /// The timeout applies to each call separately, however many times the reader is woken during it. After a timeout
+    /// the adapter can still be used: the next call polls the reader again, with a new timeout.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set or clear the timeout for each call to [`Read::read`] or [`BufRead::fill_buf`], as with
+    /// [`with_timeout`](Self::with_timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The timeout for each call to [`Read::read`] or [`BufRead::fill_buf`], if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consume this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Read for BlockingBufReader<R> {
+    /// Block the thread until the reader has read some bytes into `buf`.
+    ///
+    /// Reading into an empty `buf` returns `Ok(0)` immediately, without polling the reader.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        read(Pin::new(&mut self.reader), buf, &mut None, self.timeout)
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> BufRead for BlockingBufReader<R> {
+    /// Block the thread until the reader has filled its buffer, returning the buffered bytes.
+    ///
+    /// # Errors
+    ///
+    /// As well as passing through errors from the reader, this returns [`io::ErrorKind::WouldBlock`] if the reader is
+    /// no longer ready when polled again after filling its buffer, and [`io::ErrorKind::TimedOut`] if a
+    /// [timeout](BlockingBufReader::with_timeout) is set and the reader doesn't fill its buffer in time.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let mut reader = Pin::new(&mut self.reader);
+        let deadline = Deadline::after(self.timeout);
+
+        LOCAL_WAKER.with(|waker| {
+            let mut context = Context::from_waker(waker);
+
+            // The buffer borrowed from a poll can't be returned from within the loop, so once the reader is ready we
+            // poll it one more time (which it answers immediately, since its buffer is now full) and return that.
+            loop {
+                match reader.as_mut().poll_fill_buf(&mut context) {
+                    Poll::Pending => deadline.park()?,
+                    Poll::Ready(Err(err)) => return Err(err),
+                    Poll::Ready(Ok(_)) => break,
+                }
+            }
+            match reader.poll_fill_buf(&mut context) {
+                Poll::Ready(result) => result,
+                Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+            }
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        Pin::new(&mut self.reader).consume(amt)
+    }
+}
+
+impl<R: AsyncSeek + Unpin> Seek for BlockingBufReader<R> {
+    /// Block the thread until the reader has seeked to `pos`.
+    ///
+    /// This adapter doesn't buffer anything itself, so it relies on the reader discarding any data it has buffered, as
+    /// `tokio::io::BufReader` does.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        seek(Pin::new(&mut self.reader), &mut None, pos)
+    }
+}
+
+/// A [`Write`] implementation that blocks the thread on an [`AsyncWrite`].
+///
+/// Each call to [`Write::write`] blocks until the writer accepts some bytes. Like [`Write::write`] itself, only part of
+/// the buffer may be written: use [`Write::write_all`] to write all of it. Errors from the writer are passed through
+/// unchanged.
+///
+/// Dropping a `BlockingWriter` flushes and shuts down the underlying writer, waiting for up to a second (see
+/// [`with_drop_timeout`](BlockingWriter::with_drop_timeout)) so that a stalled writer can't hang the thread. Errors
+/// can't be reported from `drop`, so they are ignored, and a panic while shutting down is caught rather than unwinding
+/// out of `drop`. Call [`BlockingWriter::close`] first to see errors, or [`BlockingWriter::forget_close`] to leave the
+/// writer open.
+///
+/// # Example
+///
+/// ```
+/// use pollster::io::tokio::BlockingWriter;
+/// use std::io::Write;
+///
+/// let mut writer = BlockingWriter::new(std::io::Cursor::new(Vec::new()));
+///
+/// writer.write_all(b"hello").unwrap();
+/// writer.close().unwrap();
+/// assert_eq!(writer.into_inner().into_inner(), b"hello");
+/// ```
+pub struct BlockingWriter<W> {
+    // Only `None` once `into_inner` has taken it
+    writer: Option<W>,
+    nonblocking: Option<Nonblocking>,
+    drop_close: Option<CloseFn<W>>,
+    drop_timeout: Duration,
+}
+
+impl<W> BlockingWriter<W> {
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.as_ref().expect(TAKEN)
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.as_mut().expect(TAKEN)
+    }
+
+    /// Consume this adapter, returning the underlying writer without shutting down it.
+    pub fn into_inner(mut self) -> W {
+        self.writer.take().expect(TAKEN)
+    }
+
+    /// Set how long dropping the adapter waits for the writer to be flushed and shut down, instead of one second.
+    pub fn with_drop_timeout(mut self, timeout: Duration) -> Self {
+        self.drop_timeout = timeout;
+        self
+    }
+
+    /// Stop dropping the adapter from flushing and shutting down the writer, for callers that manage that themselves.
+    pub fn forget_close(&mut self) {
+        self.drop_close = None;
+    }
+
+    /// Switch the adapter into or out of non-blocking mode, as with [`TcpStream::set_nonblocking`].
+    ///
+    /// In non-blocking mode, each write, flush, shutdown or seek polls the writer exactly once, failing with
+    /// [`io::ErrorKind::WouldBlock`] if it isn't ready rather than blocking the thread. The writer is polled with a
+    /// waker for the current thread, so [`ready`](Self::ready) and [`wait_writable`](Self::wait_writable) can tell when
+    /// it's worth trying again.
+    ///
+    /// A seek that fails with `WouldBlock` has still been started, so the next call to seek to the same position waits
+    /// for it to finish rather than seeking again.
+    ///
+    /// [`TcpStream::set_nonblocking`]: std::net::TcpStream::set_nonblocking
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        if nonblocking != self.nonblocking.is_some() {
+            self.nonblocking = nonblocking.then(Nonblocking::default);
+        }
+    }
+
+    /// Whether the adapter is in [non-blocking mode](Self::set_nonblocking).
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.is_some()
+    }
+
+    /// Whether the next operation might make progress: that is, whether the last one didn't fail with
+    /// [`io::ErrorKind::WouldBlock`], or the writer has been woken since.
+    ///
+    /// This is always `true` in blocking mode.
+    pub fn ready(&self) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.ready())
+    }
+
+    /// Park the thread until [`ready`](Self::ready) returns `true`, or until `timeout` passes, returning whether the
+    /// adapter is ready.
+    ///
+    /// The writer wakes the thread that last used it, so this should be called from that thread.
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> bool {
+        self.nonblocking
+            .as_ref()
+            .map_or(true, |nonblocking| nonblocking.wait(timeout))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> BlockingWriter<W> {
+    /// Wrap an asynchronous writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            nonblocking: None,
+            drop_close: Some(close_within::<W>),
+            drop_timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Block the thread until the writer has been flushed and shut down.
+    ///
+    /// Once this succeeds, dropping the adapter won't try to shut down the writer again.
+    pub fn close(&mut self) -> io::Result<()> {
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_shutdown(cx)
+        })?;
+        self.drop_close = None;
+        Ok(())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Write for BlockingWriter<W> {
+    /// Block the thread until the writer has accepted some bytes from `buf`.
+    ///
+    /// Writing an empty `buf` returns `Ok(0)` immediately, without polling the writer.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_write(cx, buf)
+        })
+    }
+
+    /// Block the thread until the writer has accepted some bytes from `bufs`, using
+    /// [`AsyncWrite::poll_write_vectored`].
+    ///
+    /// Writing only empty buffers returns `Ok(0)` immediately, without polling the writer.
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Ok(0);
+        }
+
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_write_vectored(cx, bufs)
+        })
+    }
+
+    /// Block the thread until the writer has been flushed.
+    fn flush(&mut self) -> io::Result<()> {
+        let mut writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        poll_io(&mut self.nonblocking, None, |cx| {
+            writer.as_mut().poll_flush(cx)
+        })
+    }
+}
+
+impl<W: AsyncSeek + Unpin> Seek for BlockingWriter<W> {
+    /// Block the thread until the writer has seeked to `pos`.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let writer = Pin::new(self.writer.as_mut().expect(TAKEN));
+        seek(writer, &mut self.nonblocking, pos)
+    }
+}
+
+impl<W: AsyncWrite + Unpin + Default> Default for BlockingWriter<W> {
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for BlockingWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingWriter")
+            .field("writer", self.get_ref())
+            .field("nonblocking", &self.nonblocking)
+            .field("drop_timeout", &self.drop_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> Drop for BlockingWriter<W> {
+    fn drop(&mut self) {
+        if let (Some(close), Some(writer)) = (self.drop_close, self.writer.as_mut()) {
+            // There's nowhere to report an error to, and a panic mustn't unwind out of `drop`
+            let timeout = self.drop_timeout;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| close(writer, timeout)));
+        }
+    }
+}
+
+const TAKEN: &str = "the writer has been taken by `into_inner`";
+
+// `Drop` can't require `W: AsyncWrite`, so `new` stores the function that shuts down the writer instead.
+type CloseFn<W> = fn(&mut W, Duration) -> io::Result<()>;
+
+// Flushes and then shuts down `writer`, giving up once `timeout` has elapsed.
+fn close_within<W: AsyncWrite + Unpin>(writer: &mut W, timeout: Duration) -> io::Result<()> {
+    let mut writer = Pin::new(writer);
+    let mut flushed = false;
+    block_on_io(Some(timeout), |cx| {
+        if !flushed {
+            match writer.as_mut().poll_flush(cx) {
+                Poll::Ready(Ok(())) => flushed = true,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        writer.as_mut().poll_shutdown(cx)
+    })
+}
+
+fn read<R: AsyncRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    buf: &mut [u8],
+    nonblocking: &mut Option<Nonblocking>,
+    timeout: Option<Duration>,
+) -> io::Result<usize> {
+    poll_io(nonblocking, timeout, |cx| {
+        // `buf` is already initialized, so the reader can't expose uninitialized memory and we can trust the length it
+        // reports.
+        let mut read_buf = ReadBuf::new(buf);
+        reader
+            .as_mut()
+            .poll_read(cx, &mut read_buf)
+            .map_ok(|()| read_buf.filled().len())
+    })
+}
+
+fn seek<S: AsyncSeek + ?Sized>(
+    mut seeker: Pin<&mut S>,
+    nonblocking: &mut Option<Nonblocking>,
+    pos: SeekFrom,
+) -> io::Result<u64> {
+    let nonblocking = match nonblocking {
+        Some(nonblocking) => nonblocking,
+        None => {
+            // Finish any seek that was started (and then abandoned) elsewhere, since only one can be in progress at a
+            // time
+            block_on(poll_fn(|cx| seeker.as_mut().poll_complete(cx)))?;
+            seeker.as_mut().start_seek(pos)?;
+            return block_on(poll_fn(|cx| seeker.as_mut().poll_complete(cx)));
+        }
+    };
+
+    // A seek that would block is left in progress, so that retrying it only has to wait for it to finish
+    if nonblocking.seeking != Some(pos) {
+        nonblocking.poll(|cx| seeker.as_mut().poll_complete(cx))?;
+        seeker.as_mut().start_seek(pos)?;
+        nonblocking.seeking = Some(pos);
+    }
+    let result = nonblocking.poll(|cx| seeker.as_mut().poll_complete(cx));
+    if !nonblocking.pending {
+        nonblocking.seeking = None;
+    }
+    result
+}
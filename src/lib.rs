@@ -2,13 +2,98 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
 use std::{
+    cell::Cell,
+    fmt,
     future::{Future, IntoFuture},
-    sync::{Arc, Condvar, Mutex},
-    task::{Context, Poll, Wake, Waker},
+    mem,
+    panic::{self, UnwindSafe},
+    pin::Pin,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, RawWaker, RawWakerVTable, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "macro")]
-pub use pollster_macro::{main, test};
+pub use pollster_macro::{bench, join, main, test, try_join};
+
+mod annotated;
+mod builder;
+#[cfg(any(feature = "futures-channel", feature = "tokio-channel"))]
+pub mod channel;
+mod checked;
+mod clock;
+#[cfg(feature = "condvar")]
+mod condvar;
+mod executor;
+mod future;
+#[cfg(all(windows, feature = "hi-res-timer"))]
+mod hi_res;
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "ctrlc"))]
+mod interrupt;
+mod interval;
+#[cfg(any(
+    feature = "embedded-io",
+    feature = "futures-io",
+    feature = "tokio-io",
+    feature = "sink",
+    feature = "stream"
+))]
+pub mod io;
+pub mod oneshot;
+mod servicing;
+mod signal;
+#[cfg(feature = "sink")]
+pub mod sink;
+mod sleep;
+mod stepper;
+#[cfg(feature = "stream")]
+pub mod stream;
+pub mod sync;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod threaded;
+pub mod time;
+mod timer;
+#[cfg(feature = "spawn")]
+mod unblock;
+mod wasm;
+
+pub use annotated::block_on_annotated;
+pub use builder::BlockOnBuilder;
+pub use checked::{block_on_checked, NoWakerHeld};
+pub use executor::{block_on_any, join, Either, Join, LocalExecutor, Remaining};
+pub use future::{
+    block_on_chain, wrap_wakeable, yield_now, AndThen, InspectPoll, Shared, WakeHandle, Wakeable,
+    YieldNow,
+};
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "ctrlc"))]
+pub use interrupt::{block_on_interruptible, Interrupted};
+pub use interval::{interval, Interval, IntervalBuilder, MissedTicks, Tick};
+pub use servicing::{block_on_servicing, waker_channel, WakerReceiver, WakerSender};
+pub use signal::{signal_pair, WakeSignal};
+#[cfg(feature = "sink")]
+pub use sink::SinkExt;
+pub use sleep::{sleep, sleep_until, Sleep};
+pub use stepper::Stepper;
+#[cfg(feature = "stream")]
+pub use stream::{
+    block_on_stream, block_on_stream_for_each, try_block_on_stream_for_each,
+    BlockingBufferUnordered, BlockingBuffered, BlockingChunksTimeout, BlockingReadyChunks,
+    BlockingStream, StreamExt,
+};
+pub use threaded::{block_on_par, block_on_thread, spawn_block_on, BlockOnHandle, ThreadedBlockOn};
+pub use time::{
+    block_on_deadline, block_on_deadline_with, block_on_timeout, block_on_timeout_with, timeout,
+    Clock, Elapsed, StdClock, WithTimeout,
+};
+#[cfg(feature = "spawn")]
+pub use unblock::{unblock, Unblock};
+pub use wasm::{block_on_spin, block_on_wasm, StillPending};
 
 /// An extension trait that allows blocking on a future in suffix position.
 pub trait FutureExt: Future {
@@ -24,108 +109,1159 @@ pub trait FutureExt: Future {
     /// let result = my_fut.block_on();
     /// ```
     fn block_on(self) -> Self::Output where Self: Sized { block_on(self) }
+
+    /// Call a closure with the result of every poll of this future, without otherwise changing its behaviour.
+    ///
+    /// This is useful for debugging, such as when trying to figure out why a future keeps returning
+    /// [`Poll::Pending`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::FutureExt as _;
+    ///
+    /// let my_fut = async { 42 };
+    ///
+    /// let result = my_fut
+    ///     .inspect_poll(|poll| eprintln!("polled: {:?}", poll))
+    ///     .block_on();
+    /// assert_eq!(result, 42);
+    /// ```
+    fn inspect_poll<G: FnMut(&Poll<Self::Output>)>(self, f: G) -> InspectPoll<Self, G>
+    where
+        Self: Sized,
+    {
+        InspectPoll::new(self, f)
+    }
+
+    /// Fail with [`Elapsed`] if the future isn't ready within `duration` of first being polled.
+    ///
+    /// This behaves like [`timeout`], except that the duration is measured from the first poll rather than from when
+    /// this is called, so the future can be created well before it's run. Unlike [`block_on_timeout`], this returns a
+    /// future, so it can be composed with other futures or used with other executors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::FutureExt as _;
+    /// use std::time::Duration;
+    ///
+    /// let my_fut = std::future::pending::<()>();
+    ///
+    /// let result = my_fut.timeout(Duration::from_millis(10)).block_on();
+    /// assert!(result.is_err());
+    /// ```
+    fn timeout(self, duration: Duration) -> WithTimeout<Self>
+    where
+        Self: Sized,
+    {
+        WithTimeout::deferred(self, duration)
+    }
+
+    /// Block the thread until the future is ready, catching any panic that occurs while polling it.
+    ///
+    /// See [`block_on_catch_unwind`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::FutureExt as _;
+    ///
+    /// let my_fut = async { panic!("oh no") };
+    ///
+    /// let result = my_fut.block_on_catch_unwind();
+    /// assert!(result.is_err());
+    /// ```
+    fn block_on_catch_unwind(self) -> thread::Result<Self::Output>
+    where
+        Self: Sized + UnwindSafe,
+    {
+        block_on_catch_unwind(self)
+    }
+
+    /// Once this future is ready, build a second future from its output with `then`, and wait for that one too.
+    ///
+    /// The output of the returned future is the output of the second future. See also [`block_on_chain`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::FutureExt as _;
+    ///
+    /// let my_fut = async { "42" }.and_then(|s| async move { s.parse::<i32>() });
+    ///
+    /// assert_eq!(my_fut.block_on(), Ok(42));
+    /// ```
+    fn and_then<G: FnOnce(Self::Output) -> H, H: Future>(self, then: G) -> AndThen<Self, G, H>
+    where
+        Self: Sized,
+    {
+        AndThen::new(self, then)
+    }
+
+    /// Turn this future into one that can be cloned, with every clone resolving to a clone of its output.
+    ///
+    /// This lets several threads (or tasks) wait on the result of the same future. See [`Shared`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::FutureExt as _;
+    /// use std::thread;
+    ///
+    /// let my_fut = async { String::from("hello") }.shared();
+    ///
+    /// let other = my_fut.clone();
+    /// let handle = thread::spawn(move || other.block_on());
+    ///
+    /// assert_eq!(my_fut.block_on(), "hello");
+    /// assert_eq!(handle.join().unwrap(), "hello");
+    /// ```
+    fn shared(self) -> Shared<Self>
+    where
+        Self: Sized,
+        Self::Output: Clone,
+    {
+        Shared::new(self)
+    }
 }
 
 impl<F: Future> FutureExt for F {}
 
-enum SignalState {
-    Empty,
-    Waiting,
-    Notified,
+/// A [`Wake`] implementation that unparks a particular thread, as used by [`block_on`].
+///
+/// [`Signal::for_thread`] turns any [`Thread`] handle into a waker, which need not be for the current thread: for
+/// example, a coordinator thread can hand out wakers for itself to workers, and then [`thread::park`] until one of them
+/// wakes it. A wake that arrives before the thread parks isn't lost: it makes the next `thread::park` return
+/// immediately. To find out whether the waker was actually woken, rather than the thread only being unparked, see
+/// [`signal_pair`].
+///
+/// # Example
+///
+/// ```
+/// use pollster::Signal;
+/// use std::{task::Waker, thread};
+///
+/// let waker = Waker::from(Signal::for_thread(thread::current()));
+/// let worker = thread::spawn(move || waker.wake());
+///
+/// // Returns once the worker has woken us (or immediately, if it already has)
+/// thread::park();
+/// worker.join().unwrap();
+/// ```
+//
+// `Thread` is itself reference-counted, but that count is only touched when a `Signal` is created (once per thread).
+// Cloning a waker clones the outer `Arc<Signal>` alone, so storing the `Thread` directly costs a single extra pointer
+// indirection on wake and nothing on clone: see the `waker_clone` and `wake_by_ref` benchmarks.
+pub struct Signal {
+    owning_thread: Thread,
 }
 
-struct Signal {
-    state: Mutex<SignalState>,
-    cond: Condvar,
+impl Signal {
+    /// Create a signal that unparks `thread` when woken.
+    pub fn for_thread(thread: Thread) -> Arc<Self> {
+        Arc::new(Self {
+            owning_thread: thread,
+        })
+    }
+
+    /// Get the thread that this signal unparks.
+    pub fn thread(&self) -> &Thread {
+        &self.owning_thread
+    }
 }
 
-impl Signal {
-    fn new() -> Self {
-        Self {
-            state: Mutex::new(SignalState::Empty),
-            cond: Condvar::new(),
-        }
+impl fmt::Debug for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signal")
+            .field("thread", &self.owning_thread)
+            .finish()
     }
+}
 
-    fn wait(&self) {
-        let mut state = self.state.lock().unwrap();
-        match *state {
-            // Notify() was called before we got here, consume it here without waiting and return immediately.
-            SignalState::Notified => *state = SignalState::Empty,
-            // This should not be possible because our signal is created within a function and never handed out to any
-            // other threads. If this is the case, we have a serious problem so we panic immediately to avoid anything
-            // more problematic happening.
-            SignalState::Waiting => {
-                unreachable!("Multiple threads waiting on the same signal: Open a bug report!");
-            }
-            SignalState::Empty => {
-                // Nothing has happened yet, and we're the only thread waiting (as should be the case!). Set the state
-                // accordingly and begin polling the condvar in a loop until it's no longer telling us to wait. The
-                // loop prevents incorrect spurious wakeups.
-                *state = SignalState::Waiting;
-                while let SignalState::Waiting = *state {
-                    state = self.cond.wait(state).unwrap();
-                }
-            }
-        }
+impl Wake for Signal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
     }
 
-    fn notify(&self) {
-        let mut state = self.state.lock().unwrap();
-        match *state {
-            // The signal was already notified, no need to do anything because the thread will be waking up anyway
-            SignalState::Notified => {}
-            // The signal wasn't notified but a thread isn't waiting on it, so we can avoid doing unnecessary work by
-            // skipping the condvar and leaving behind a message telling the thread that a notification has already
-            // occurred should it come along in the future.
-            SignalState::Empty => *state = SignalState::Notified,
-            // The signal wasn't notified and there's a waiting thread. Reset the signal so it can be wait()'ed on again
-            // and wake up the thread. Because there should only be a single thread waiting, `notify_all` would also be
-            // valid.
-            SignalState::Waiting => {
-                *state = SignalState::Empty;
-                self.cond.notify_one();
-            }
+    fn wake_by_ref(self: &Arc<Self>) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("wakeup");
+        self.owning_thread.unpark();
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_task::ArcWake for Signal {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.owning_thread.unpark();
+    }
+}
+
+/// Returns a waker that unparks the current thread, built with [`futures_task::waker`].
+///
+/// This is the same kind of waker that [`block_on`] uses, for use with code that drives futures by hand (with
+/// [`thread::park`] in place of an executor) through the `futures` crate's waker machinery.
+///
+/// # Example
+///
+/// ```
+/// use std::{pin::pin, task::{Context, Poll}, thread};
+/// use std::future::Future;
+///
+/// let waker = pollster::futures_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// let mut fut = pin!(async { 42 });
+///
+/// let result = loop {
+///     match fut.as_mut().poll(&mut cx) {
+///         Poll::Ready(x) => break x,
+///         Poll::Pending => thread::park(),
+///     }
+/// };
+/// assert_eq!(result, 42);
+/// ```
+#[cfg(feature = "futures")]
+pub fn futures_waker() -> Waker {
+    futures_task::waker(Signal::for_thread(thread::current()))
+}
+
+// A per-call signal that also records whether it has been woken, for poll loops that need to know whether a wake
+// occurred rather than only being unparked by one.
+struct FlagSignal {
+    owning_thread: Thread,
+    woken: AtomicBool,
+}
+
+impl FlagSignal {
+    fn new() -> Self {
+        Self {
+            owning_thread: thread::current(),
+            woken: AtomicBool::new(false),
         }
     }
+
+    // Returns whether the signal has been woken since this was last called.
+    fn take_woken(&self) -> bool {
+        self.woken.swap(false, Ordering::Acquire)
+    }
 }
 
-impl Wake for Signal {
+impl Wake for FlagSignal {
     fn wake(self: Arc<Self>) {
-        self.notify();
+        self.wake_by_ref();
     }
 
     fn wake_by_ref(self: &Arc<Self>) {
-        self.notify();
+        self.woken.store(true, Ordering::Release);
+        self.owning_thread.unpark();
+    }
+}
+
+thread_local! {
+    // The waker for the current thread. Because parking is per-thread, every `block_on` call on a given thread can
+    // share the same waker, so we only need to allocate it once (and only on threads that actually block).
+    static LOCAL_WAKER: Waker = Waker::from(Signal::for_thread(thread::current()));
+
+    // The waker that `block_on` polls with. It's the same as `LOCAL_WAKER`, but kept distinct so that a `Sleep` can
+    // tell that it's being polled by `block_on`, which waits out its deadline, rather than by some other loop.
+    #[cfg(not(feature = "condvar"))]
+    static BLOCK_ON_WAKER: Waker = Waker::from(Signal::for_thread(thread::current()));
+}
+
+// Returns whether `waker` is the one that `block_on` polls with on this thread.
+#[inline(always)]
+fn is_block_on_waker(waker: &Waker) -> bool {
+    #[cfg(not(feature = "condvar"))]
+    return BLOCK_ON_WAKER.with(|block_on| waker.will_wake(block_on));
+    #[cfg(feature = "condvar")]
+    return condvar::LOCAL_SIGNAL.with(|(_, block_on)| waker.will_wake(block_on));
+}
+
+/// A strategy used to block the thread while a future is pending.
+///
+/// [`block_on`] uses [`ThreadPark`], but other strategies can be supplied to [`block_on_with`]: for example, a strategy
+/// that returns immediately (for testing), one that waits for an interrupt (for embedded targets), or one that
+/// implements a custom backoff.
+///
+/// # Contract
+///
+/// A wake of the waker returned by [`ParkStrategy::waker`] must cause the current call to [`ParkStrategy::park`] or
+/// [`ParkStrategy::park_timeout`] to return or, if no such call is in progress, the next one. Failing to uphold this
+/// may result in lost wakeups and a thread that blocks forever. Returning without having been woken (spuriously) is
+/// always permitted: the future will simply be polled again.
+pub trait ParkStrategy {
+    /// Returns a waker that unblocks this strategy when woken.
+    fn waker(&self) -> Waker;
+
+    /// Block until woken.
+    fn park(&self);
+
+    /// Block until woken, or until `timeout` has elapsed.
+    ///
+    /// Returns `true` if the strategy may have been woken, or `false` if the timeout elapsed first.
+    fn park_timeout(&self, timeout: Duration) -> bool;
+}
+
+impl<S: ParkStrategy + ?Sized> ParkStrategy for &S {
+    fn waker(&self) -> Waker {
+        (**self).waker()
+    }
+
+    fn park(&self) {
+        (**self).park()
+    }
+
+    fn park_timeout(&self, timeout: Duration) -> bool {
+        (**self).park_timeout(timeout)
+    }
+}
+
+/// The default [`ParkStrategy`], which blocks the current thread using [`thread::park`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadPark;
+
+impl ParkStrategy for ThreadPark {
+    fn waker(&self) -> Waker {
+        LOCAL_WAKER.with(Waker::clone)
+    }
+
+    fn park(&self) {
+        park();
+    }
+
+    fn park_timeout(&self, timeout: Duration) -> bool {
+        // `thread::park_timeout` doesn't tell us why it returned, so we infer it from the time that has passed.
+        let start = Instant::now();
+        park_timeout(timeout);
+        start.elapsed() < timeout
     }
 }
 
 /// Block the thread until the future is ready.
 ///
+/// `block_on` is always inlined, so a future that is ready immediately (such as [`std::future::ready`]) costs little
+/// more than a single poll. For futures that are usually ready immediately but may not be, [`block_on_ready`] also
+/// avoids touching the thread's cached waker.
+///
+/// While the future is pending, the thread waits with [`thread::park`]. With the `condvar` feature, it waits on a
+/// [`Condvar`](std::sync::Condvar) instead, for platforms where parking misbehaves under heavy wakeup traffic. This
+/// only changes the wait: the future is still polled once per wake, a wake that arrives before the thread starts
+/// waiting isn't lost, and the thread still wakes itself for any [`sleep`] deadline.
+///
+/// The feature applies to `block_on`, [`block_on_pinned`], [`block_on_dyn`], [`block_on_uncached`] and
+/// [`block_on_timed`], and to the functions that only call them (such as [`FutureExt::block_on`], the attribute
+/// macros, and the blocking stream adapters). Every other blocking function keeps parking the thread, since each has
+/// its own waker or its own way of waiting: for example [`block_on_with`], [`block_on_timeout`], [`block_on_budget`],
+/// [`block_on_resilient`], [`block_on_with_progress`], [`BlockOnBuilder`], and the IO adapters when they have a
+/// timeout set.
+///
+/// Under [Miri](https://github.com/rust-lang/miri), the thread yields and polls the future again instead of parking,
+/// so that tests using pollster can run on targets where Miri can't emulate parking.
+///
+/// With the `wasm` feature on `wasm32`, where the thread usually can't park (and never can on the main thread of
+/// `wasm32-unknown-unknown`), the thread spins for a bounded time waiting for a wake instead, and panics with an
+/// explanation if the future still isn't woken. Futures that are already ready work as usual. Use [`block_on_spin`]
+/// to choose the bound and get an error instead of a panic.
+///
+/// # Metrics
+///
+/// With the `metrics` feature, each call reports the following to the [`metrics`](https://docs.rs/metrics) recorder.
+/// These apply to [`block_on_pinned`] and [`block_on_uncached`] too, and their names won't change between releases.
+///
+/// | Name                         | Kind      | Meaning                                             |
+/// |------------------------------|-----------|-----------------------------------------------------|
+/// | `pollster.block_on.calls`    | Counter   | Incremented once per call                           |
+/// | `pollster.block_on.parks`    | Counter   | Incremented each time the thread waits for a wakeup |
+/// | `pollster.block_on.duration` | Histogram | How long the call took, in seconds                  |
+///
 /// # Example
 ///
 /// ```
 /// let my_fut = async {};
 /// let result = pollster::block_on(my_fut);
 /// ```
+///
+/// The output of a fallible future can be propagated with `?` directly:
+///
+/// ```
+/// fn read_config() -> Result<String, std::io::Error> {
+///     let config = pollster::block_on(async { Ok::<_, std::io::Error>("config".to_string()) })?;
+///     Ok(config)
+/// }
+/// # assert_eq!(read_config().unwrap(), "config");
+/// ```
+#[inline(always)]
 pub fn block_on<F: IntoFuture>(fut: F) -> F::Output {
+    block_on_pinned(core::pin::pin!(fut.into_future()))
+}
+
+/// Block the thread until an already-pinned future is ready.
+///
+/// Unlike [`block_on`], the future is only borrowed. This allows a future to be kept alive across multiple calls, or
+/// to be driven by pollster after being partially polled elsewhere.
+///
+/// # Example
+///
+/// ```
+/// let mut my_fut = core::pin::pin!(async { 42 });
+/// let result = pollster::block_on_pinned(my_fut.as_mut());
+/// assert_eq!(result, 42);
+/// ```
+#[inline(always)]
+pub fn block_on_pinned<F: Future + ?Sized>(fut: Pin<&mut F>) -> F::Output {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    return wasm::block_on_main_thread(fut);
+    #[cfg(all(
+        not(feature = "condvar"),
+        not(all(feature = "wasm", target_arch = "wasm32"))
+    ))]
+    return BLOCK_ON_WAKER.with(
+        #[inline(always)]
+        |waker| poll_to_completion(fut, waker, park_until),
+    );
+    #[cfg(all(
+        feature = "condvar",
+        not(all(feature = "wasm", target_arch = "wasm32"))
+    ))]
+    return condvar::LOCAL_SIGNAL.with(
+        #[inline(always)]
+        |(signal, waker)| poll_to_completion(fut, waker, |deadline| signal.wait_until(deadline)),
+    );
+}
+
+/// Block the thread until a future behind a trait object is ready.
+///
+/// This is [`block_on_pinned`] for `dyn Future`s: the poll loop is only instantiated once per output type rather than
+/// once per future type, which can reduce code size when many different futures are already behind trait objects.
+///
+/// # Example
+///
+/// ```
+/// use std::{future::Future, pin::Pin};
+///
+/// let mut my_fut: Pin<Box<dyn Future<Output = i32>>> = Box::pin(async { 42 });
+/// let result = pollster::block_on_dyn(my_fut.as_mut());
+/// assert_eq!(result, 42);
+/// ```
+pub fn block_on_dyn<T>(fut: Pin<&mut (dyn Future<Output = T> + '_)>) -> T {
+    block_on_pinned(fut)
+}
+
+/// Block the thread until the future is ready, optimised for futures that are usually ready the first time they're
+/// polled.
+///
+/// The first poll uses a waker that does nothing, so cloning it costs nothing either. If the future isn't ready, it
+/// falls back to [`block_on_pinned`], polling the future again with a waker that works.
+///
+/// # Example
+///
+/// ```
+/// let result = pollster::block_on_ready(std::future::ready(42));
+/// assert_eq!(result, 42);
+/// ```
+#[inline]
+pub fn block_on_ready<F: IntoFuture>(fut: F) -> F::Output {
     let mut fut = core::pin::pin!(fut.into_future());
 
-    // Signal used to wake up the thread for polling as the future moves to completion. We need to use an `Arc`
-    // because, although the lifetime of `fut` is limited to this function, the underlying IO abstraction might keep
-    // the signal alive for far longer. `Arc` is a thread-safe way to allow this to happen.
-    // TODO: Investigate ways to reuse this `Arc<Signal>`... perhaps via a `static`?
-    let signal = Arc::new(Signal::new());
+    let waker = noop_waker();
+    match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(item) => item,
+        // The future may be holding on to the no-op waker, so it needs polling again before it can be waited on
+        Poll::Pending => block_on_pinned(fut),
+    }
+}
+
+/// Returns a waker that does nothing when woken.
+///
+/// This is useful for polling a future by hand when it's known not to need waking, such as in tests of futures that
+/// never suspend. Unlike an `Arc`-based waker, it doesn't allocate, and cloning or dropping it costs nothing.
+///
+/// # Example
+///
+/// ```
+/// use std::{future::Future, task::{Context, Poll}};
+///
+/// let waker = pollster::noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+///
+/// let mut my_fut = core::pin::pin!(async { 42 });
+/// assert_eq!(my_fut.as_mut().poll(&mut cx), Poll::Ready(42));
+/// ```
+pub fn noop_waker() -> Waker {
+    // A `static` rather than a `const`, so that every clone shares the same vtable address and `will_wake` holds
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(raw, |_| {}, |_| {}, |_| {});
+
+    fn raw(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    // SAFETY: None of the vtable's functions use the data pointer, and cloning returns another waker with the same
+    // (null) data and vtable, so the `RawWaker` contract is trivially upheld.
+    unsafe { Waker::from_raw(raw(std::ptr::null())) }
+}
 
+/// Returns whether two wakers are known to wake the same task.
+///
+/// [`Waker`] doesn't implement [`PartialEq`], so this is a shorthand for [`Waker::will_wake`], for use in tests that
+/// check a future holds on to the right waker. Every [`block_on`] call on a thread polls with the same waker, so two
+/// wakers captured from futures blocked on by the same thread compare equal. Like `will_wake`, this can return `false`
+/// for different wakers that happen to wake the same task, but never `true` for wakers that don't.
+///
+/// # Example
+///
+/// ```
+/// use std::future::poll_fn;
+/// use std::task::Poll;
+///
+/// let first = pollster::block_on(poll_fn(|cx| Poll::Ready(cx.waker().clone())));
+/// let second = pollster::block_on(poll_fn(|cx| Poll::Ready(cx.waker().clone())));
+/// assert!(pollster::same_waker(&first, &second));
+/// assert!(!pollster::same_waker(&first, &pollster::noop_waker()));
+/// ```
+pub fn same_waker(a: &Waker, b: &Waker) -> bool {
+    a.will_wake(b)
+}
+
+/// Returns whether `waker` is one of pollster's wakers for the current thread.
+///
+/// This recognises the waker that [`block_on`] (and the other functions that share its per-thread waker) polls with
+/// on the calling thread, so interop code can tell when it's being driven by pollster: for example, to skip handing
+/// the waker off to another thread when it's about to be woken from the same one. Wakers from other threads, and the
+/// one-off wakers created by functions such as [`block_on_uncached`], aren't recognised. Neither is
+/// [`noop_waker`], since it doesn't wake anything.
+///
+/// # Example
+///
+/// ```
+/// use std::future::poll_fn;
+/// use std::task::Poll;
+///
+/// let is_pollster = pollster::block_on(poll_fn(|cx| Poll::Ready(pollster::is_pollster_waker(cx.waker()))));
+/// assert!(is_pollster);
+/// assert!(!pollster::is_pollster_waker(&pollster::noop_waker()));
+/// ```
+pub fn is_pollster_waker(waker: &Waker) -> bool {
+    is_block_on_waker(waker) || LOCAL_WAKER.with(|local| waker.will_wake(local))
+}
+
+/// Block the thread until the future is ready, without using the thread's cached waker.
+///
+/// [`block_on`] creates a waker the first time it's called on each thread, and keeps it in a thread-local for later
+/// calls. On a thread that only ever blocks once, that cache is never reused: this creates a waker for the one call
+/// instead, without initialising the thread-local (or registering it to be dropped when the thread exits).
+///
+/// # Example
+///
+/// ```
+/// let handle = std::thread::spawn(|| pollster::block_on_uncached(async { 42 }));
+/// assert_eq!(handle.join().unwrap(), 42);
+/// ```
+pub fn block_on_uncached<F: IntoFuture>(fut: F) -> F::Output {
+    let fut = core::pin::pin!(fut.into_future());
+    #[cfg(not(feature = "condvar"))]
+    return poll_to_completion(
+        fut,
+        &Waker::from(Signal::for_thread(thread::current())),
+        park_until,
+    );
+    #[cfg(feature = "condvar")]
+    return {
+        let (signal, waker) = condvar::signal();
+        poll_to_completion(fut, &waker, |deadline| signal.wait_until(deadline))
+    };
+}
+
+/// Block the thread until the future is ready, returning its output along with how long the thread spent parked.
+///
+/// The duration only counts time spent waiting for the future to be woken, not time spent polling it, so comparing it
+/// against the total time taken shows whether a future is bound by the CPU (mostly polling) or by IO (mostly parked).
+/// Timing each wait costs a pair of [`Instant::now`] calls, but a future that's ready immediately is never timed.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let (output, parked) = pollster::block_on_timed(async { 42 });
+/// assert_eq!(output, 42);
+/// // The future was ready straight away, so the thread never parked
+/// assert_eq!(parked, Duration::ZERO);
+/// ```
+pub fn block_on_timed<F: IntoFuture>(fut: F) -> (F::Output, Duration) {
+    let fut = core::pin::pin!(fut.into_future());
+    let parked = Cell::new(Duration::ZERO);
+    let timed = |park: &dyn Fn()| {
+        let start = Instant::now();
+        park();
+        parked.set(parked.get() + start.elapsed());
+    };
+
+    #[cfg(not(feature = "condvar"))]
+    let output = BLOCK_ON_WAKER
+        .with(|waker| poll_to_completion(fut, waker, |deadline| timed(&|| park_until(deadline))));
+    #[cfg(feature = "condvar")]
+    let output = condvar::LOCAL_SIGNAL.with(|(signal, waker)| {
+        poll_to_completion(fut, waker, |deadline| timed(&|| signal.wait_until(deadline)))
+    });
+    (output, parked.get())
+}
+
+// Wait for the current thread to be unparked.
+//
+// Miri can't emulate `thread::park` on every target it supports, so under Miri this yields to the other threads
+// instead, and the future is polled again straight away. That busy-waits (and turns a lost wakeup into a hang rather
+// than a reported deadlock), but Miri is only used for testing, where being able to run at all matters more.
+#[inline(always)]
+pub(crate) fn park() {
+    #[cfg(not(miri))]
+    thread::park();
+    #[cfg(miri)]
+    thread::yield_now();
+}
+
+// Wait for the current thread to be unparked, or until `timeout` has passed.
+//
+// With the `hi-res-timer` feature, short waits on Windows raise the system timer resolution so that they're accurate
+// to about a millisecond, rather than to the default tick of about 15.6ms.
+#[inline(always)]
+pub(crate) fn park_timeout(timeout: Duration) {
+    #[cfg(all(windows, feature = "hi-res-timer"))]
+    let _period = hi_res::Period::begin(timeout);
+    thread::park_timeout(timeout);
+}
+
+// Wait for the current thread to be unparked, or until `deadline` (if any) has passed.
+#[cfg(not(feature = "condvar"))]
+#[inline(always)]
+fn park_until(deadline: Option<Instant>) {
+    match deadline {
+        #[cfg(not(miri))]
+        Some(deadline) => park_timeout(deadline.saturating_duration_since(clock::now())),
+        _ => park(),
+    }
+}
+
+// Kept separate from the thread-local access so that, once inlined, futures that are immediately ready compile down
+// to little more than a single poll.
+#[inline(always)]
+fn poll_to_completion<F: Future + ?Sized>(
+    mut fut: Pin<&mut F>,
+    waker: &Waker,
+    park: impl Fn(Option<Instant>),
+) -> F::Output {
     // Create a context that will be passed to the future.
+    let mut context = Context::from_waker(waker);
+
+    #[cfg(feature = "tracing")]
+    let span = tracing::trace_span!("block_on");
+
+    #[cfg(feature = "metrics")]
+    let start = {
+        metrics::counter!("pollster.block_on.calls").increment(1);
+        Instant::now()
+    };
+
+    // Any `Sleep` polled with our waker adds the deadline by which it needs the thread to wake
+    let mut timers = sleep::Timers::new();
+
+    // Poll the future to completion
+    let output = loop {
+        let poll = {
+            // The span is only entered while polling, so that it is exited while the thread is parked
+            #[cfg(feature = "tracing")]
+            let _entered = span.enter();
+            timers.listen(|| fut.as_mut().poll(&mut context))
+        };
+        match poll {
+            Poll::Pending => {
+                let deadline = timers.next_deadline();
+                // With time paused, there's no need to wait for the deadline to come
+                #[cfg(feature = "testing")]
+                if deadline.map_or(false, clock::skip_to) {
+                    continue;
+                }
+                #[cfg(feature = "tokio-detect")]
+                warn_if_in_tokio();
+                #[cfg(feature = "metrics")]
+                metrics::counter!("pollster.block_on.parks").increment(1);
+                park(deadline)
+            }
+            Poll::Ready(item) => break item,
+        }
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("pollster.block_on.duration").record(start.elapsed());
+
+    output
+}
+
+// Parking a Tokio worker thread stalls every other task scheduled on it (or, on a current-thread runtime, deadlocks it
+// entirely), so log a `tracing` warning the first time it looks like that's about to happen. Tokio doesn't let us
+// distinguish its worker threads from its blocking threads, so this can't panic: blocking inside `spawn_blocking` is
+// perfectly fine.
+#[cfg(feature = "tokio-detect")]
+#[cold]
+fn warn_if_in_tokio() {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+
+    if tokio::runtime::Handle::try_current().is_ok() && !WARNED.swap(true, Ordering::Relaxed) {
+        tracing::warn!(
+            "`pollster::block_on` is blocking a thread inside a Tokio runtime context. If this is an async task, use \
+            tokio::task::spawn_blocking or tokio::runtime::Handle::block_on instead."
+        );
+    }
+}
+
+/// Poll a future once using an existing [`Context`], rather than blocking.
+///
+/// This is useful when code that would otherwise call [`block_on`] might itself be running inside another executor's
+/// task: by polling with that task's context, wakeups propagate to the outer executor instead of unparking pollster's
+/// thread.
+///
+/// # Example
+///
+/// ```
+/// use std::{future::poll_fn, task::Poll};
+///
+/// let mut inner = core::pin::pin!(async { 42 });
+///
+/// // Some outer executor's task, which drives `inner` cooperatively
+/// let outer = poll_fn(|cx| pollster::poll_with_context(inner.as_mut(), cx));
+/// assert_eq!(pollster::block_on(outer), 42);
+/// ```
+#[inline]
+pub fn poll_with_context<F: Future + ?Sized>(fut: Pin<&mut F>, cx: &mut Context<'_>) -> Poll<F::Output> {
+    fut.poll(cx)
+}
+
+/// Poll a future once with a [waker that does nothing](noop_waker), to check whether it's ready right now.
+///
+/// Unlike [`Stepper::step`], which polls with the thread's waker, this has no scheduling side effects at all: a wake
+/// is neither recorded nor delivered anywhere. The caveat is that a future which relies on being woken before it can
+/// make progress may return [`Poll::Pending`] here even though it would be ready if it were woken and polled again.
+///
+/// # Example
+///
+/// ```
+/// use std::task::Poll;
+///
+/// assert_eq!(pollster::try_poll_once(core::pin::pin!(async { 42 })), Poll::Ready(42));
+/// assert_eq!(pollster::try_poll_once(core::pin::pin!(std::future::pending::<()>())), Poll::Pending);
+/// ```
+#[inline]
+pub fn try_poll_once<F: Future + ?Sized>(fut: Pin<&mut F>) -> Poll<F::Output> {
+    fut.poll(&mut Context::from_waker(&noop_waker()))
+}
+
+/// Block the thread until the future is ready, using the given [`ParkStrategy`] to wait while it is pending.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async {};
+/// let result = pollster::block_on_with(my_fut, pollster::ThreadPark);
+/// ```
+pub fn block_on_with<F: IntoFuture, S: ParkStrategy>(fut: F, strategy: S) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let waker = strategy.waker();
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut context) {
+            Poll::Pending => strategy.park(),
+            Poll::Ready(item) => break item,
+        }
+    }
+}
+
+/// Block the thread until the future is ready, catching any panic that occurs while polling it.
+///
+/// If the future panics, the panic payload is returned as an `Err` (as with [`std::panic::catch_unwind`]) and the
+/// future is dropped.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async { panic!("oh no") };
+/// let result = pollster::block_on_catch_unwind(my_fut);
+/// assert!(result.is_err());
+/// ```
+pub fn block_on_catch_unwind<F: IntoFuture + UnwindSafe>(fut: F) -> thread::Result<F::Output> {
+    panic::catch_unwind(move || block_on(fut))
+}
+
+/// Block the thread until the future is ready, aborting the process if the future panics.
+///
+/// The panic message is printed by the panic hook as usual, and then [`std::process::abort`] is called instead of
+/// letting the panic unwind out of this function. This matters when blocking inside an FFI callback, where unwinding
+/// into foreign code is undefined behaviour, or in servers that should never continue after a task has panicked.
+///
+/// Rather than swapping out the process-wide panic hook (which would race with other threads), this uses a guard that
+/// aborts if it's dropped while the panic unwinds. A panic while the future is being dropped aborts too.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async { 42 };
+/// assert_eq!(pollster::block_on_abort_on_panic(my_fut), 42);
+/// ```
+pub fn block_on_abort_on_panic<F: IntoFuture>(fut: F) -> F::Output {
+    let guard = AbortOnPanic;
+    let output = block_on(fut);
+    mem::forget(guard);
+    output
+}
+
+// Aborts the process when dropped, which only happens if a panic unwinds past it: it is forgotten otherwise.
+struct AbortOnPanic;
+
+impl Drop for AbortOnPanic {
+    fn drop(&mut self) {
+        process::abort();
+    }
+}
+
+/// Block the thread until the future is ready, yielding to the OS scheduler if the future repeatedly wakes itself.
+///
+/// Some futures return [`Poll::Pending`] but wake themselves immediately (or are woken while being polled), so the
+/// thread never actually blocks and can hog its CPU. After every `polls_per_yield` consecutive polls like this, the
+/// thread calls [`thread::yield_now`] to give other threads a chance to run. The count resets whenever the thread
+/// genuinely waits for a wakeup.
+///
+/// # Panics
+///
+/// Panics if `polls_per_yield` is zero.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async {};
+/// let result = pollster::block_on_budget(my_fut, 16);
+/// ```
+pub fn block_on_budget<F: IntoFuture>(fut: F, polls_per_yield: usize) -> F::Output {
+    assert!(polls_per_yield > 0, "`polls_per_yield` must be non-zero");
+
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let signal = Arc::new(FlagSignal::new());
     let waker = Waker::from(Arc::clone(&signal));
     let mut context = Context::from_waker(&waker);
 
-    // Poll the future to completion
+    let mut busy_polls = 0;
     loop {
+        signal.take_woken();
         match fut.as_mut().poll(&mut context) {
-            Poll::Pending => signal.wait(),
+            Poll::Pending if signal.take_woken() => {
+                // Woken before we even got around to parking, so parking won't block
+                busy_polls += 1;
+                if busy_polls >= polls_per_yield {
+                    busy_polls = 0;
+                    thread::yield_now();
+                }
+                // Consume the unpark token the wake left behind
+                park();
+            }
+            Poll::Pending => {
+                busy_polls = 0;
+                park();
+            }
             Poll::Ready(item) => break item,
         }
     }
 }
+
+/// Block the thread until the future is ready, panicking with `message` if it's ever pending.
+///
+/// This is for futures that should never need to wait, such as deterministic futures in tests: a bug that makes one
+/// pending fails loudly instead of leaving the thread blocked. It's the same as [`block_on_or_panic_after`] with
+/// `polls_before_panic` set to 1.
+///
+/// The check only happens with debug assertions enabled. Otherwise, this is the same as [`block_on`].
+///
+/// # Panics
+///
+/// With debug assertions enabled, panics if the future returns [`Poll::Pending`].
+///
+/// # Example
+///
+/// ```
+/// let result = pollster::block_on_or_panic(async { 42 }, "the answer should be ready");
+/// assert_eq!(result, 42);
+/// ```
+pub fn block_on_or_panic<F: IntoFuture>(fut: F, message: &str) -> F::Output {
+    block_on_or_panic_after(fut, 1, message)
+}
+
+/// Block the thread until the future is ready, panicking with `message` if it has been pending `polls_before_panic`
+/// times.
+///
+/// Like [`block_on_or_panic`], but allows a future to wait a bounded number of times (for example, one that yields
+/// once) before it's considered a bug. The thread parks between polls as with [`block_on`], so the count is of genuine
+/// waits as well as self-wakes.
+///
+/// The check only happens with debug assertions enabled. Otherwise, this is the same as [`block_on`].
+///
+/// # Panics
+///
+/// Panics if `polls_before_panic` is zero. With debug assertions enabled, panics if the future returns
+/// [`Poll::Pending`] `polls_before_panic` times.
+///
+/// # Example
+///
+/// ```
+/// let result = pollster::block_on_or_panic_after(
+///     async {
+///         pollster::yield_now().await;
+///         42
+///     },
+///     2,
+///     "the answer should be ready after yielding",
+/// );
+/// assert_eq!(result, 42);
+/// ```
+pub fn block_on_or_panic_after<F: IntoFuture>(
+    fut: F,
+    polls_before_panic: usize,
+    message: &str,
+) -> F::Output {
+    assert!(
+        polls_before_panic > 0,
+        "`polls_before_panic` must be non-zero"
+    );
+    if !cfg!(debug_assertions) {
+        return block_on(fut);
+    }
+
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    LOCAL_WAKER.with(|waker| {
+        let mut context = Context::from_waker(waker);
+
+        let mut pending = 0;
+        loop {
+            match fut.as_mut().poll(&mut context) {
+                Poll::Pending => {
+                    pending += 1;
+                    if pending >= polls_before_panic {
+                        panic!("{} (future still pending after {} polls)", message, pending);
+                    }
+                    park();
+                }
+                Poll::Ready(item) => break item,
+            }
+        }
+    })
+}
+
+/// Block the thread until the future is ready, re-polling it at least once every `interval` even if it isn't woken.
+///
+/// A correct future will always wake its waker when it's ready to make progress, and for those this behaves just like
+/// [`block_on`]. However, buggy futures can lose wakeups (for example, by dropping the waker under a race) which would
+/// otherwise leave the thread blocked forever. This trades a small amount of wasted work for guaranteed forward
+/// progress: if the future is still pending after being re-polled, the thread goes back to waiting.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let my_fut = async {};
+/// let result = pollster::block_on_resilient(my_fut, Duration::from_millis(100));
+/// ```
+pub fn block_on_resilient<F: IntoFuture>(fut: F, interval: Duration) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    LOCAL_WAKER.with(|waker| {
+        let mut context = Context::from_waker(waker);
+
+        loop {
+            match fut.as_mut().poll(&mut context) {
+                Poll::Pending => park_timeout(interval),
+                Poll::Ready(item) => break item,
+            }
+        }
+    })
+}
+
+/// Block the thread until the future is ready, calling `driver` after each poll that returns [`Poll::Pending`].
+///
+/// This allows an IO reactor (such as one built on `mio` or `polling`) to be driven alongside the future. `driver` is
+/// passed the waker that the future was polled with, and may block in the OS (for example, waiting on an `epoll`
+/// instance) rather than returning immediately. If the waker was woken by the time `driver` returns, the future is
+/// polled again straight away. Otherwise, the thread parks until it is woken, as with [`block_on`].
+///
+/// A driver that blocks should make sure that a wake of the waker also interrupts its wait (for example, with a
+/// `mio::Waker` or `polling::Poller::notify`), or wakeups from other threads may be delayed until the wait ends.
+///
+/// # Example
+///
+/// ```
+/// let mut steps = 0;
+/// let result = pollster::block_on_with_driver(async { 42 }, |_waker| steps += 1);
+/// assert_eq!(result, 42);
+/// assert_eq!(steps, 0);
+/// ```
+pub fn block_on_with_driver<F: IntoFuture, D: FnMut(&Waker)>(fut: F, mut driver: D) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let signal = Arc::new(FlagSignal::new());
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut context) {
+            Poll::Pending => {
+                driver(&waker);
+                if !signal.take_woken() {
+                    park();
+                }
+            }
+            Poll::Ready(item) => break item,
+        }
+    }
+}
+
+/// Block the thread until the future is ready, calling `on_poll` each time the future returns [`Poll::Pending`].
+///
+/// `on_poll` receives the number of times the future has been polled so far, allowing callers to rate-limit any work
+/// they do in it (such as updating a spinner). It is always called on the thread that called this function, just
+/// before the thread blocks waiting for the future to be woken.
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async {};
+/// let result = pollster::block_on_with_progress(my_fut, |polls| eprintln!("Still waiting after {} polls", polls));
+/// ```
+pub fn block_on_with_progress<F: IntoFuture, G: FnMut(u64)>(fut: F, mut on_poll: G) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    LOCAL_WAKER.with(|waker| {
+        let mut context = Context::from_waker(waker);
+
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            match fut.as_mut().poll(&mut context) {
+                Poll::Pending => {
+                    on_poll(polls);
+                    park();
+                }
+                Poll::Ready(item) => break item,
+            }
+        }
+    })
+}
+
+/// Block the thread until the future is ready, calling `progress` after every `every` polls that leave it pending.
+///
+/// This is a lighter alternative to [`block_on_with_progress`] for things like pumping a terminal spinner or checking
+/// a cancellation flag, where only the rate matters. `progress` is called on the thread that called this function,
+/// just before the thread blocks waiting for the future to be woken, and never once the future has completed.
+///
+/// # Panics
+///
+/// Panics if `every` is zero.
+///
+/// # Example
+///
+/// ```
+/// let mut ticks = 0;
+/// let result = pollster::block_on_with_progress_every(
+///     async {
+///         for _ in 0..10 {
+///             pollster::yield_now().await;
+///         }
+///         42
+///     },
+///     4,
+///     || ticks += 1,
+/// );
+/// assert_eq!(result, 42);
+/// assert_eq!(ticks, 2);
+/// ```
+pub fn block_on_with_progress_every<F: IntoFuture, P: FnMut()>(
+    fut: F,
+    every: u32,
+    mut progress: P,
+) -> F::Output {
+    assert!(every > 0, "`every` must be non-zero");
+
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    LOCAL_WAKER.with(|waker| {
+        let mut context = Context::from_waker(waker);
+
+        let mut polls = 0;
+        loop {
+            match fut.as_mut().poll(&mut context) {
+                Poll::Pending => {
+                    polls += 1;
+                    if polls == every {
+                        polls = 0;
+                        progress();
+                    }
+                    park();
+                }
+                Poll::Ready(item) => break item,
+            }
+        }
+    })
+}
+
+/// Block the thread until the future is ready, calling `on_park` to do other work each time the future returns
+/// [`Poll::Pending`], or give up on the future if `on_park` returns `false`.
+///
+/// This allows simple cooperative multitasking without an executor: `on_park` can drain a synchronous queue or
+/// process GUI events while the future waits for IO. After it returns `true`, the future is polled again straight away
+/// if it was woken in the meantime, and otherwise the thread parks until it is woken, as with [`block_on`]. Once
+/// `on_park` returns `false`, the future is dropped and `None` is returned.
+///
+/// `on_park` is always called on the thread that called this function.
+///
+/// # Example
+///
+/// ```
+/// let (tx, rx) = futures::channel::oneshot::channel();
+/// let mut tx = Some(tx);
+///
+/// // Handling an event completes the future
+/// let result = pollster::block_on_with_yield(rx, || {
+///     if let Some(tx) = tx.take() {
+///         tx.send(42).unwrap();
+///     }
+///     true
+/// });
+/// assert_eq!(result, Some(Ok(42)));
+///
+/// // Giving up on a future that isn't ready
+/// let result = pollster::block_on_with_yield(std::future::pending::<()>(), || false);
+/// assert_eq!(result, None);
+/// ```
+pub fn block_on_with_yield<F: IntoFuture, Y: FnMut() -> bool>(
+    fut: F,
+    mut on_park: Y,
+) -> Option<F::Output> {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let signal = Arc::new(FlagSignal::new());
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut context) {
+            Poll::Pending => {
+                if !on_park() {
+                    break None;
+                }
+                if !signal.take_woken() {
+                    park();
+                }
+            }
+            Poll::Ready(item) => break Some(item),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! A channel for sending a single value to a blocked thread.
+//!
+//! This is the smallest primitive for handing a result from async code (such as a callback) back to a thread that's
+//! waiting for it. The receiver can either block the thread, parking it in the same way as [`block_on`], or be
+//! awaited as a future.
+//!
+//! # Example
+//!
+//! ```
+//! use std::thread;
+//!
+//! let (tx, rx) = pollster::oneshot::channel();
+//!
+//! thread::spawn(move || tx.send(42).unwrap());
+//!
+//! assert_eq!(rx.recv(), Ok(42));
+//! ```
+
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{block_on, block_on_timeout};
+
+/// Create a oneshot channel, returning its sending and receiving halves.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let state = Arc::new(Mutex::new(State {
+        value: None,
+        waker: None,
+        sender: true,
+        receiver: true,
+    }));
+    (
+        Sender {
+            state: Arc::clone(&state),
+        },
+        Receiver { state },
+    )
+}
+
+struct State<T> {
+    value: Option<T>,
+    // The waker of the receiver's last poll that found nothing
+    waker: Option<Waker>,
+    // Whether each half is still alive
+    sender: bool,
+    receiver: bool,
+}
+
+/// The sending half of a oneshot channel.
+///
+/// Created by [`channel`]. Dropping it without sending a value wakes the receiver with a [`RecvError`].
+pub struct Sender<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send `value` to the receiver, waking it if it's waiting. This never blocks.
+    ///
+    /// If the receiver has already been dropped, `value` is given back as the error.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            if !state.receiver {
+                return Err(value);
+            }
+            state.value = Some(value);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Returns whether the receiver has been dropped, so that a value sent now would be given back.
+    pub fn is_closed(&self) -> bool {
+        !self.state.lock().unwrap().receiver
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.sender = false;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a oneshot channel.
+///
+/// Created by [`channel`]. The value can be waited for with [`Receiver::recv`] or [`Receiver::recv_timeout`], which
+/// block the thread, or by awaiting the receiver as a future from async code.
+#[must_use = "the value can't be received once the receiver has been dropped"]
+pub struct Receiver<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Block the thread until the value has been sent.
+    ///
+    /// If the sender is dropped without sending a value (including while the thread is waiting), [`RecvError`] is
+    /// returned.
+    pub fn recv(mut self) -> Result<T, RecvError> {
+        block_on(&mut self)
+    }
+
+    /// Block the thread until the value has been sent, or until `timeout` has elapsed.
+    ///
+    /// The receiver is kept if the timeout elapses, so that the value can still be waited for later.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match block_on_timeout(&mut *self, timeout) {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(RecvError)) => Err(RecvTimeoutError::Disconnected),
+            Err(_) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+}
+
+/// Resolves to the value once it has been sent, or to [`RecvError`] if the sender is dropped without sending one.
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+        if !state.sender {
+            return Poll::Ready(Err(RecvError));
+        }
+        match &state.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // A value that was sent but never received is dropped now (outside the lock), rather than with the sender
+        let _value = {
+            let mut state = self.state.lock().unwrap();
+            state.receiver = false;
+            state.value.take()
+        };
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+/// The error returned by [`Receiver::recv`] when the sender is dropped without sending a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sender dropped without sending a value")
+    }
+}
+
+impl Error for RecvError {}
+
+/// The error returned by [`Receiver::recv_timeout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// The value wasn't sent before the timeout elapsed.
+    Timeout,
+    /// The sender was dropped without sending a value.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RecvTimeoutError::Timeout => "timed out waiting for a value",
+            RecvTimeoutError::Disconnected => "sender dropped without sending a value",
+        })
+    }
+}
+
+impl Error for RecvTimeoutError {}
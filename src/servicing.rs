@@ -0,0 +1,165 @@
+//! Flushing wakers sent from other threads while blocking on a future.
+
+use std::{
+    fmt,
+    future::{Future, IntoFuture},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{park, FlagSignal};
+
+/// Create a channel for sending [`Waker`]s to a thread in [`block_on_servicing`], to be woken from that thread.
+///
+/// Unlike a [`std::sync::mpsc`] channel, sending a waker also unparks the receiving thread if it's blocked, so that the
+/// waker is woken without delay.
+pub fn waker_channel() -> (WakerSender, WakerReceiver) {
+    let state = Arc::new(Mutex::new(State {
+        wakers: Vec::new(),
+        notify: None,
+        closed: false,
+    }));
+    (
+        WakerSender {
+            state: Arc::clone(&state),
+        },
+        WakerReceiver { state },
+    )
+}
+
+struct State {
+    wakers: Vec<Waker>,
+    // Woken when a waker is sent, while the receiver is in `block_on_servicing`
+    notify: Option<Waker>,
+    // Whether the receiver has been dropped
+    closed: bool,
+}
+
+/// The sending half of a [`waker_channel`].
+///
+/// Senders are cheap to clone and can be used from any thread.
+#[derive(Clone)]
+pub struct WakerSender {
+    state: Arc<Mutex<State>>,
+}
+
+impl WakerSender {
+    /// Send `waker` to be woken by the receiving thread, unparking it if it's in [`block_on_servicing`].
+    ///
+    /// This never blocks. If the receiver has been dropped, `waker` is given back as the error.
+    pub fn send(&self, waker: Waker) -> Result<(), Waker> {
+        let notify = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Err(waker);
+            }
+            state.wakers.push(waker);
+            state.notify.clone()
+        };
+        if let Some(notify) = notify {
+            notify.wake();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for WakerSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakerSender").finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a [`waker_channel`].
+pub struct WakerReceiver {
+    state: Arc<Mutex<State>>,
+}
+
+impl WakerReceiver {
+    /// Wake every waker that has been sent since the last time the channel was drained, returning how many there were.
+    pub fn wake_all(&self) -> usize {
+        let wakers = std::mem::take(&mut self.state.lock().unwrap().wakers);
+        let n = wakers.len();
+        for waker in wakers {
+            waker.wake();
+        }
+        n
+    }
+}
+
+impl Drop for WakerReceiver {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.wakers.clear();
+    }
+}
+
+impl fmt::Debug for WakerReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakerReceiver").finish_non_exhaustive()
+    }
+}
+
+/// Block the thread until the future is ready, waking the wakers sent on `rx` from this thread while it waits.
+///
+/// Whenever the future is pending, any wakers waiting in the channel are woken before the thread parks, and sending
+/// another waker unparks the thread to wake it too. This makes the blocked thread the single place where wakeups from
+/// other threads are flushed. A waker that arrives just as the thread is about to park is never missed: it's woken
+/// straight away rather than once the future is next woken.
+///
+/// Sending a waker doesn't cause the future to be polled again, unless the future itself is woken as a result.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+///
+/// let (tx, mut rx) = pollster::waker_channel();
+/// let (done_tx, done_rx) = pollster::oneshot::channel();
+///
+/// // Another thread asks for its waker to be woken from the blocked thread
+/// thread::spawn(move || {
+///     let (signal, waker) = pollster::signal_pair();
+///     tx.send(waker).unwrap();
+///     signal.wait();
+///     done_tx.send("woken").unwrap();
+/// });
+///
+/// assert_eq!(pollster::block_on_servicing(done_rx, &mut rx), Ok("woken"));
+/// ```
+pub fn block_on_servicing<F: IntoFuture>(fut: F, rx: &mut WakerReceiver) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let signal = Arc::new(FlagSignal::new());
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut context = Context::from_waker(&waker);
+
+    // A separate signal for the channel, so that a waker arriving doesn't cause a needless poll of the future
+    let channel = Arc::new(FlagSignal::new());
+    rx.state.lock().unwrap().notify = Some(Waker::from(Arc::clone(&channel)));
+
+    // Every future needs to be polled at least once, and wakers sent before now need waking
+    signal.wake_by_ref();
+    channel.wake_by_ref();
+    let output = loop {
+        let woken = signal.take_woken();
+        if woken {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut context) {
+                break output;
+            }
+        }
+
+        // The flag is cleared before draining, so any waker sent from now on sets it again
+        let sent = channel.take_woken();
+        if sent {
+            rx.wake_all();
+        }
+
+        if !woken && !sent {
+            park();
+        }
+    };
+
+    rx.state.lock().unwrap().notify = None;
+    output
+}
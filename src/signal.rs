@@ -0,0 +1,78 @@
+//! Wakers that can be waited on by hand, outside of `block_on`.
+
+use std::{
+    fmt,
+    sync::Arc,
+    task::Waker,
+    thread::{self, Thread},
+};
+
+use crate::{park, FlagSignal};
+
+/// Create a [`Waker`] that unparks the current thread, along with a [`WakeSignal`] for waiting until it is woken.
+///
+/// This is the same machinery that [`block_on`](crate::block_on) uses, exposed so that custom waiting patterns can be
+/// built without an executor: the waker can be handed to another thread (or stored in a future's state), and the
+/// current thread can wait for it with [`WakeSignal::wait`].
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+///
+/// let (signal, waker) = pollster::signal_pair();
+///
+/// thread::spawn(move || waker.wake());
+///
+/// // Returns once the other thread has called the waker
+/// signal.wait();
+/// ```
+pub fn signal_pair() -> (WakeSignal, Waker) {
+    let signal = Arc::new(FlagSignal::new());
+    let waker = Waker::from(Arc::clone(&signal));
+    (WakeSignal { signal }, waker)
+}
+
+/// A handle for waiting until the [`Waker`] created alongside it by [`signal_pair`] is woken.
+pub struct WakeSignal {
+    signal: Arc<FlagSignal>,
+}
+
+impl WakeSignal {
+    /// Block the thread until the waker has been woken.
+    ///
+    /// Wakes are remembered, so this returns immediately if the waker was woken since the last call. Any number of
+    /// wakes in between calls only count once.
+    ///
+    /// # Panics
+    ///
+    /// The waker unparks the thread that called [`signal_pair`], so this panics if called from any other thread.
+    pub fn wait(&self) {
+        assert!(
+            thread::current().id() == self.thread().id(),
+            "`WakeSignal::wait` called from a thread other than the one that created it"
+        );
+        while !self.signal.take_woken() {
+            park();
+        }
+    }
+
+    /// Returns whether the waker has been woken since the last call to this or [`wait`](WakeSignal::wait), without
+    /// blocking.
+    pub fn take_woken(&self) -> bool {
+        self.signal.take_woken()
+    }
+
+    /// The thread that the waker unparks.
+    pub fn thread(&self) -> &Thread {
+        &self.signal.owning_thread
+    }
+}
+
+impl fmt::Debug for WakeSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakeSignal")
+            .field("thread", self.thread())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,164 @@
+//! Blocking adapters for [`Sink`]s.
+//!
+//! The most commonly used items in this module are also re-exported from the crate root.
+
+use std::{error::Error, fmt, future::poll_fn, pin::Pin, task::Poll};
+
+use futures_sink::Sink;
+
+use crate::block_on;
+
+/// An extension trait that allows blocking on the operations of a sink in suffix position.
+pub trait SinkExt<Item>: Sink<Item> {
+    /// Block the thread until the sink has accepted and flushed an item.
+    ///
+    /// The thread waits for the sink to become ready first, so this applies backpressure in the same way as
+    /// `futures::SinkExt::send`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::SinkExt as _;
+    ///
+    /// let (mut tx, mut rx) = futures::channel::mpsc::channel(1);
+    ///
+    /// tx.blocking_send(42).unwrap();
+    /// assert_eq!(rx.try_next().unwrap(), Some(42));
+    /// ```
+    fn blocking_send(&mut self, item: Item) -> Result<(), Self::Error>
+    where
+        Self: Unpin,
+    {
+        let mut sink = Pin::new(self);
+        block_on(poll_fn(|cx| sink.as_mut().poll_ready(cx)))?;
+        sink.as_mut().start_send(item)?;
+        block_on(poll_fn(|cx| sink.as_mut().poll_flush(cx)))
+    }
+
+    /// Block the thread until all items sent to the sink have been flushed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::SinkExt as _;
+    ///
+    /// let (mut tx, _rx) = futures::channel::mpsc::unbounded::<()>();
+    ///
+    /// tx.blocking_flush().unwrap();
+    /// ```
+    fn blocking_flush(&mut self) -> Result<(), Self::Error>
+    where
+        Self: Unpin,
+    {
+        let mut sink = Pin::new(self);
+        block_on(poll_fn(|cx| sink.as_mut().poll_flush(cx)))
+    }
+
+    /// Block the thread until the sink has been flushed and closed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::SinkExt as _;
+    ///
+    /// let (mut tx, mut rx) = futures::channel::mpsc::unbounded::<()>();
+    ///
+    /// tx.blocking_close().unwrap();
+    /// assert_eq!(rx.try_next().unwrap(), None);
+    /// ```
+    fn blocking_close(&mut self) -> Result<(), Self::Error>
+    where
+        Self: Unpin,
+    {
+        let mut sink = Pin::new(self);
+        block_on(poll_fn(|cx| sink.as_mut().poll_close(cx)))
+    }
+}
+
+impl<S: Sink<Item> + ?Sized, Item> SinkExt<Item> for S {}
+
+/// Block the thread until every item from `iter` has been sent to `sink`, and the sink has been flushed.
+///
+/// Items are pulled from the iterator one at a time, only once the sink is ready to accept them, so this applies
+/// backpressure without collecting the iterator up front. If the sink returns an error, no further items are pulled
+/// and the error is returned along with the number of items that the sink had accepted.
+///
+/// # Example
+///
+/// ```
+/// let (tx, rx) = futures::channel::mpsc::unbounded();
+///
+/// pollster::sink::blocking_send_all(tx, 0..10).unwrap();
+/// assert_eq!(pollster::block_on(futures::StreamExt::count(rx)), 10);
+/// ```
+pub fn blocking_send_all<S, I>(sink: S, iter: I) -> Result<(), SendAllError<S::Error>>
+where
+    S: Sink<I::Item> + Unpin,
+    I: IntoIterator,
+{
+    let mut sink = sink;
+    let mut sink = Pin::new(&mut sink);
+    let mut iter = iter.into_iter();
+    let mut sent = 0;
+
+    block_on(poll_fn(|cx| loop {
+        let result = match sink.as_mut().poll_ready(cx) {
+            Poll::Ready(Ok(())) => match iter.next() {
+                Some(item) => match sink.as_mut().start_send(item) {
+                    Ok(()) => {
+                        sent += 1;
+                        continue;
+                    }
+                    Err(error) => Err(error),
+                },
+                None => match sink.as_mut().poll_flush(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                },
+            },
+            Poll::Ready(Err(error)) => Err(error),
+            Poll::Pending => return Poll::Pending,
+        };
+        break Poll::Ready(result.map_err(|error| SendAllError { error, sent }));
+    }))
+}
+
+/// The error returned by [`blocking_send_all`] when the sink fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendAllError<E> {
+    error: E,
+    sent: usize,
+}
+
+impl<E> SendAllError<E> {
+    /// The number of items that the sink accepted before it failed.
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    /// A reference to the error returned by the sink.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Consume this error, returning the error returned by the sink.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for SendAllError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sink failed after accepting {} items: {}",
+            self.sent, self.error
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for SendAllError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
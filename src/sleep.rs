@@ -0,0 +1,205 @@
+//! Futures that wait for a duration, without needing a runtime.
+
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    clock, is_block_on_waker,
+    timer::{self, WakerSlot},
+};
+
+thread_local! {
+    // While `block_on` is polling its future, the timers that `Sleep`s have asked it to wake the thread for. `None`
+    // while nothing is listening.
+    static TIMERS: RefCell<Option<Timers>> = const { RefCell::new(None) };
+}
+
+// Identifies each `Timers`, so that a `Sleep` only adds its deadline once
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+// The deadlines that a single `block_on` call must wake the thread for.
+//
+// A deadline stays queued until it has passed, even if the `Sleep` that added it isn't polled again, since a
+// combinator that passes its context through may only poll some of its futures each time.
+#[derive(Default)]
+pub(crate) struct Timers {
+    // Only assigned once a `Sleep` has added a deadline, so that calls without any don't pay for it
+    id: Option<usize>,
+    deadlines: BinaryHeap<Reverse<Instant>>,
+}
+
+impl Timers {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // Run `poll` (a poll of `block_on`'s future), with any `Sleep` polled with its waker adding to these timers.
+    #[inline(always)]
+    pub(crate) fn listen<T>(&mut self, poll: impl FnOnce() -> T) -> T {
+        let outer = TIMERS.with(|timers| timers.replace(Some(mem::take(self))));
+        let _listening = Listening {
+            timers: self,
+            outer,
+        };
+        poll()
+    }
+
+    // Returns the earliest deadline that's still to come, discarding the ones that have passed.
+    pub(crate) fn next_deadline(&mut self) -> Option<Instant> {
+        let mut now = None;
+        while let Some(&Reverse(deadline)) = self.deadlines.peek() {
+            if deadline > *now.get_or_insert_with(clock::now) {
+                return Some(deadline);
+            }
+            self.deadlines.pop();
+        }
+        None
+    }
+}
+
+// Takes back the timers once a poll finishes, and restores those of an outer `block_on` (if any), even if it panics.
+struct Listening<'a> {
+    timers: &'a mut Timers,
+    outer: Option<Timers>,
+}
+
+impl Drop for Listening<'_> {
+    fn drop(&mut self) {
+        let outer = self.outer.take();
+        *self.timers = TIMERS
+            .with(|timers| timers.replace(outer))
+            .unwrap_or_default();
+    }
+}
+
+// Ask the `block_on` that's polling with `waker` to wake the thread by `at`, returning whether there is one. `added`
+// records which call already has the deadline.
+fn wake_at(at: Instant, waker: &Waker, added: &mut Option<usize>) -> bool {
+    if !is_block_on_waker(waker) {
+        return false;
+    }
+    TIMERS.with(|timers| match &mut *timers.borrow_mut() {
+        Some(timers) => {
+            let id = *timers
+                .id
+                .get_or_insert_with(|| NEXT_ID.fetch_add(1, Ordering::Relaxed));
+            if *added != Some(id) {
+                timers.deadlines.push(Reverse(at));
+                *added = Some(id);
+            }
+            true
+        }
+        None => false,
+    })
+}
+
+/// Returns a future that resolves once `duration` has passed.
+///
+/// The duration is measured from when this is called, not from when the future is first polled. A zero duration
+/// resolves on the first poll, without the thread ever waiting.
+///
+/// No runtime is needed. When the future is polled directly by [`block_on`](crate::block_on) (including from inside
+/// an `async` block, or a combinator that passes its context through), it tells `block_on` when to wake, and the
+/// blocked thread waits with [`thread::park_timeout`](std::thread::park_timeout) (or, with the `condvar` feature, with
+/// [`Condvar::wait_timeout`](std::sync::Condvar::wait_timeout)). Anywhere else, such as under another executor, it
+/// falls back to a shared timer thread that's spawned the first time it's needed.
+///
+/// On Windows, waits are only accurate to the system timer tick of about 15.6ms by default. The `hi-res-timer` feature
+/// raises the timer resolution to 1ms while waiting out short deadlines (of up to 50ms), restoring it afterwards. It
+/// has no effect on other platforms, where waits are already this accurate.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// pollster::block_on(pollster::sleep(Duration::from_millis(10)));
+/// assert!(start.elapsed() >= Duration::from_millis(10));
+/// ```
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        // A deadline too far in the future to represent will never be reached
+        deadline: clock::now().checked_add(duration),
+        added: None,
+        slot: None,
+    }
+}
+
+/// Returns a future that resolves once `deadline` has passed.
+///
+/// A deadline that has already passed resolves on the first poll, without the thread ever waiting.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep {
+        deadline: Some(deadline),
+        added: None,
+        slot: None,
+    }
+}
+
+/// A future that resolves once a deadline has passed.
+///
+/// Created by [`sleep`] and [`sleep_until`]. See [`sleep`] for how it waits.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    deadline: Option<Instant>,
+    // The `block_on` call whose timers already have the deadline, if any
+    added: Option<usize>,
+    // Only created once the timer thread has been asked to wake this
+    slot: Option<WakerSlot>,
+}
+
+impl Sleep {
+    /// Returns the instant at which this future resolves, or `None` if it's too far in the future to represent.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let deadline = match self.deadline {
+            Some(deadline) => deadline,
+            None => return Poll::Pending,
+        };
+        if clock::now() >= deadline {
+            return Poll::Ready(());
+        }
+
+        let this = &mut *self;
+        if !wake_at(deadline, cx.waker(), &mut this.added) {
+            match &this.slot {
+                Some(slot) => *slot.lock().unwrap() = Some(cx.waker().clone()),
+                None => {
+                    let slot = Arc::new(Mutex::new(Some(cx.waker().clone())));
+                    timer::register(deadline, &slot);
+                    this.slot = Some(slot);
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,58 @@
+//! Manual, single-step polling.
+
+use std::{
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::LOCAL_WAKER;
+
+/// Drives a future one poll at a time.
+///
+/// This is the manual counterpart to [`block_on`](crate::block_on): rather than blocking the thread until the future
+/// is ready, each call to [`Stepper::step`] polls the future exactly once and returns immediately. This is useful for
+/// testing futures, or for embedding a future in a larger scheduler of your own.
+///
+/// The future is polled with a per-thread waker that unparks the thread, just like the one `block_on` uses, so a wake
+/// will cause the next `block_on` (or [`std::thread::park`]) on this thread to return.
+///
+/// # Example
+///
+/// ```
+/// use pollster::Stepper;
+/// use std::task::Poll;
+///
+/// let mut stepper = Stepper::new(async { 42 });
+///
+/// assert_eq!(stepper.step(), Poll::Ready(42));
+/// ```
+#[must_use = "futures do nothing unless polled"]
+pub struct Stepper<F: Future> {
+    fut: Pin<Box<F>>,
+}
+
+impl<F: Future> Stepper<F> {
+    /// Create a new `Stepper` that will drive the given future.
+    pub fn new<T: IntoFuture<IntoFuture = F>>(fut: T) -> Self {
+        Self {
+            fut: Box::pin(fut.into_future()),
+        }
+    }
+
+    /// Poll the future once, without blocking.
+    ///
+    /// As with any future, the behaviour of stepping again after [`Poll::Ready`] has been returned is determined by the
+    /// future itself (most will panic).
+    pub fn step(&mut self) -> Poll<F::Output> {
+        let fut = self.fut.as_mut();
+        LOCAL_WAKER.with(|waker| fut.poll(&mut Context::from_waker(waker)))
+    }
+}
+
+impl<F: Future> fmt::Debug for Stepper<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stepper").finish_non_exhaustive()
+    }
+}
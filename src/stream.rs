@@ -0,0 +1,855 @@
+//! Blocking adapters for [`Stream`]s.
+//!
+//! The most commonly used items in this module are also re-exported from the crate root.
+
+use std::{
+    collections::VecDeque,
+    future::{poll_fn, Future},
+    iter::FromIterator,
+    mem,
+    ops::ControlFlow,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use futures_core::Stream;
+
+use crate::{block_on, block_on_deadline, block_on_timeout, Elapsed};
+
+/// An extension trait that allows blocking on the items of a stream in suffix position.
+pub trait StreamExt: Stream {
+    /// Block the thread until the stream yields its next item, or `None` if the stream has ended.
+    ///
+    /// The stream is only borrowed, so it can continue to be used asynchronously afterwards. Streams that are not
+    /// [`Unpin`] can be pinned first (with [`core::pin::pin!`] or [`Box::pin`]), since `Pin<&mut S>` is itself an
+    /// [`Unpin`] stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let mut my_stream = futures::stream::iter([1, 2, 3]);
+    ///
+    /// assert_eq!(my_stream.blocking_next(), Some(1));
+    /// assert_eq!(my_stream.blocking_next(), Some(2));
+    /// ```
+    fn blocking_next(&mut self) -> Option<Self::Item>
+    where
+        Self: Unpin,
+    {
+        let mut stream = Pin::new(self);
+        block_on(poll_fn(|cx| stream.as_mut().poll_next(cx)))
+    }
+
+    /// Block the thread until a stream of [`Result`]s yields its next item, returning `Ok(None)` if the stream has
+    /// ended.
+    ///
+    /// This is the blocking equivalent of `futures::TryStreamExt::try_next`, and so can be used with the `?` operator.
+    /// An `Err` is returned as soon as the stream yields one, without consuming any further items, and the stream
+    /// remains usable afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// fn first_two() -> Result<(u32, u32), String> {
+    ///     let mut my_stream = futures::stream::iter([Ok(1), Ok(2), Err("oops".to_string())]);
+    ///
+    ///     let a = my_stream.blocking_try_next()?.unwrap_or_default();
+    ///     let b = my_stream.blocking_try_next()?.unwrap_or_default();
+    ///     Ok((a, b))
+    /// }
+    ///
+    /// assert_eq!(first_two(), Ok((1, 2)));
+    /// ```
+    fn blocking_try_next<T, E>(&mut self) -> Result<Option<T>, E>
+    where
+        Self: Stream<Item = Result<T, E>> + Unpin,
+    {
+        self.blocking_next().transpose()
+    }
+
+    /// Block the thread until the stream ends, collecting its items into a collection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter([1, 2, 3]);
+    ///
+    /// let items = my_stream.blocking_collect::<Vec<_>>();
+    /// assert_eq!(items, [1, 2, 3]);
+    /// ```
+    fn blocking_collect<C: FromIterator<Self::Item>>(self) -> C
+    where
+        Self: Sized,
+    {
+        let mut stream = core::pin::pin!(self);
+        std::iter::from_fn(|| stream.blocking_next()).collect()
+    }
+
+    /// Block the thread until the stream ends, calling a closure on each of its items.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter([1, 2, 3]);
+    ///
+    /// my_stream.blocking_for_each(|item| println!("{}", item));
+    /// ```
+    fn blocking_for_each<G: FnMut(Self::Item)>(self, mut f: G)
+    where
+        Self: Sized,
+    {
+        let mut stream = core::pin::pin!(self);
+        while let Some(item) = stream.blocking_next() {
+            f(item);
+        }
+    }
+
+    /// Block the thread, calling a closure on each item of the stream until either the closure returns
+    /// [`ControlFlow::Break`] or the stream ends.
+    ///
+    /// The stream is only borrowed and no items are consumed after the closure breaks, so any remaining items can still
+    /// be retrieved afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use pollster::StreamExt as _;
+    ///
+    /// let mut my_stream = futures::stream::iter([1, 2, 3, 4]);
+    ///
+    /// let result = my_stream.blocking_try_for_each(|item| {
+    ///     if item == 2 { ControlFlow::Break(item) } else { ControlFlow::Continue(()) }
+    /// });
+    /// assert_eq!(result, ControlFlow::Break(2));
+    /// assert_eq!(my_stream.blocking_next(), Some(3));
+    /// ```
+    fn blocking_try_for_each<B, G: FnMut(Self::Item) -> ControlFlow<B>>(&mut self, mut f: G) -> ControlFlow<B>
+    where
+        Self: Unpin,
+    {
+        while let Some(item) = self.blocking_next() {
+            f(item)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Block the thread until the stream ends, folding each of its items into an accumulator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter([1, 2, 3]);
+    ///
+    /// let sum = my_stream.blocking_fold(0, |acc, item| acc + item);
+    /// assert_eq!(sum, 6);
+    /// ```
+    fn blocking_fold<B, G: FnMut(B, Self::Item) -> B>(self, init: B, mut f: G) -> B
+    where
+        Self: Sized,
+    {
+        let mut stream = core::pin::pin!(self);
+        let mut acc = init;
+        while let Some(item) = stream.blocking_next() {
+            acc = f(acc, item);
+        }
+        acc
+    }
+
+    /// Turn the stream into a blocking [`Iterator`] over batches of up to `cap` items.
+    ///
+    /// Each batch contains every item that is immediately available, up to `cap`. The thread only blocks when no items
+    /// are available at all, so batches are never empty. When the stream ends, any partial batch is yielded before the
+    /// iterator ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cap` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter(0..5);
+    ///
+    /// let batches = my_stream.blocking_ready_chunks(2).collect::<Vec<_>>();
+    /// assert_eq!(batches, [vec![0, 1], vec![2, 3], vec![4]]);
+    /// ```
+    fn blocking_ready_chunks(self, cap: usize) -> BlockingReadyChunks<Self>
+    where
+        Self: Sized,
+    {
+        assert!(cap > 0, "chunk capacity must be non-zero");
+        BlockingReadyChunks {
+            stream: Box::pin(self),
+            cap,
+            done: false,
+        }
+    }
+
+    /// Turn the stream into a blocking [`Iterator`] over batches of up to `cap` items, waiting no longer than
+    /// `max_wait` for each batch to fill.
+    ///
+    /// The thread blocks until the first item of a batch arrives, and then collects further items until either the
+    /// batch holds `cap` items or `max_wait` has elapsed since the first item arrived. Batches are therefore never
+    /// empty. When the stream ends, any partial batch is yielded before the iterator ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cap` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter(0..5);
+    ///
+    /// let batches = my_stream.blocking_chunks_timeout(2, Duration::from_millis(10)).collect::<Vec<_>>();
+    /// assert_eq!(batches, [vec![0, 1], vec![2, 3], vec![4]]);
+    /// ```
+    fn blocking_chunks_timeout(self, cap: usize, max_wait: Duration) -> BlockingChunksTimeout<Self>
+    where
+        Self: Sized,
+    {
+        assert!(cap > 0, "chunk capacity must be non-zero");
+        BlockingChunksTimeout {
+            stream: Box::pin(self),
+            cap,
+            max_wait,
+            done: false,
+        }
+    }
+
+    /// Turn a stream of futures into a blocking [`Iterator`] over their outputs, driving up to `n` of them
+    /// concurrently.
+    ///
+    /// Outputs are yielded in the order that their futures were produced by the stream. More futures are pulled from
+    /// the stream as earlier ones are yielded, so no more than `n` are ever in flight at once.
+    ///
+    /// All in-flight futures are polled whenever any of them is woken, so this is best suited to small values of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter((0..4).map(|i| async move { i * 2 }));
+    ///
+    /// let outputs = my_stream.blocking_buffered(2).collect::<Vec<_>>();
+    /// assert_eq!(outputs, [0, 2, 4, 6]);
+    /// ```
+    fn blocking_buffered(self, n: usize) -> BlockingBuffered<Self>
+    where
+        Self: Sized,
+        Self::Item: Future,
+    {
+        assert!(n > 0, "buffer size must be non-zero");
+        BlockingBuffered {
+            stream: Box::pin(self),
+            in_flight: VecDeque::new(),
+            n,
+            done: false,
+        }
+    }
+
+    /// Like [`StreamExt::blocking_buffered`], but yields outputs in the order that their futures complete.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pollster::StreamExt as _;
+    ///
+    /// let my_stream = futures::stream::iter((0..4).map(|i| async move { i * 2 }));
+    ///
+    /// let mut outputs = my_stream.blocking_buffer_unordered(2).collect::<Vec<_>>();
+    /// outputs.sort();
+    /// assert_eq!(outputs, [0, 2, 4, 6]);
+    /// ```
+    fn blocking_buffer_unordered(self, n: usize) -> BlockingBufferUnordered<Self>
+    where
+        Self: Sized,
+        Self::Item: Future,
+    {
+        assert!(n > 0, "buffer size must be non-zero");
+        BlockingBufferUnordered {
+            stream: Box::pin(self),
+            in_flight: Vec::new(),
+            n,
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream + ?Sized> StreamExt for S {}
+
+/// Turn a [`Stream`] into a blocking [`Iterator`].
+///
+/// Each call to [`Iterator::next`] blocks the thread until the stream yields its next item, in the same way that
+/// [`block_on`] does for futures.
+///
+/// # Example
+///
+/// ```
+/// let my_stream = futures::stream::iter([1, 2, 3]);
+///
+/// let items = pollster::block_on_stream(my_stream).collect::<Vec<_>>();
+/// assert_eq!(items, [1, 2, 3]);
+/// ```
+pub fn block_on_stream<S: Stream>(stream: S) -> BlockingStream<S> {
+    BlockingStream {
+        stream: Box::pin(stream),
+    }
+}
+
+/// Block the thread until the stream ends, calling a closure on each of its items.
+///
+/// This is the same as [`StreamExt::blocking_for_each`], for use in prefix position.
+///
+/// # Example
+///
+/// ```
+/// let my_stream = futures::stream::iter([1, 2, 3]);
+///
+/// let mut sum = 0;
+/// pollster::block_on_stream_for_each(my_stream, |item| sum += item);
+/// assert_eq!(sum, 6);
+/// ```
+pub fn block_on_stream_for_each<S: Stream, F: FnMut(S::Item)>(stream: S, f: F) {
+    stream.blocking_for_each(f)
+}
+
+/// Block the thread until the stream ends, calling a fallible closure on each of its items.
+///
+/// If the closure returns an error, the stream is dropped without consuming any further items and the error is
+/// returned.
+///
+/// # Example
+///
+/// ```
+/// let my_stream = futures::stream::iter([1, 2, 3]);
+///
+/// let result = pollster::try_block_on_stream_for_each(my_stream, |item| {
+///     if item < 2 { Ok(()) } else { Err(item) }
+/// });
+/// assert_eq!(result, Err(2));
+/// ```
+pub fn try_block_on_stream_for_each<S, F, E>(stream: S, mut f: F) -> Result<(), E>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Result<(), E>,
+{
+    let mut stream = core::pin::pin!(stream);
+    while let Some(item) = stream.blocking_next() {
+        f(item)?;
+    }
+    Ok(())
+}
+
+/// An [`Iterator`] that blocks the thread on each item of a [`Stream`].
+///
+/// Created by [`block_on_stream`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingStream<S> {
+    // Boxed so that `!Unpin` streams can be driven without requiring the caller to pin them first.
+    stream: Pin<Box<S>>,
+}
+
+impl<S: Stream> BlockingStream<S> {
+    /// Block the thread until the stream yields its next item, or until `timeout` has elapsed.
+    ///
+    /// Returns `Ok(None)` if the stream has ended, or [`Elapsed`] if no item arrived in time. The stream is left
+    /// intact after a timeout, so this can simply be called again. The stream is always polled once more after the
+    /// timeout elapses before giving up, so an item that arrives just as the deadline passes is either returned by
+    /// this call or left in the stream for the next one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mut items = pollster::block_on_stream(futures::stream::pending::<()>());
+    ///
+    /// assert!(items.next_timeout(Duration::from_millis(10)).is_err());
+    /// ```
+    pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<S::Item>, Elapsed> {
+        let stream = &mut self.stream;
+        block_on_timeout(poll_fn(|cx| stream.as_mut().poll_next(cx)), timeout)
+    }
+}
+
+impl<S: Stream> Iterator for BlockingStream<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.as_mut().blocking_next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// A blocking [`Iterator`] over batches of the items that are immediately available from a [`Stream`].
+///
+/// Created by [`StreamExt::blocking_ready_chunks`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingReadyChunks<S> {
+    stream: Pin<Box<S>>,
+    cap: usize,
+    done: bool,
+}
+
+impl<S: Stream> Iterator for BlockingReadyChunks<S> {
+    type Item = Vec<S::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (stream, cap, done) = (&mut self.stream, self.cap, &mut self.done);
+        let mut batch = Vec::new();
+        block_on(poll_fn(|cx| loop {
+            if batch.len() >= cap {
+                break Poll::Ready(Some(mem::take(&mut batch)));
+            }
+
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => batch.push(item),
+                Poll::Ready(None) => {
+                    *done = true;
+                    break Poll::Ready(Some(mem::take(&mut batch)).filter(|batch| !batch.is_empty()));
+                }
+                // Only wait if we've got nothing to hand back yet
+                Poll::Pending if batch.is_empty() => break Poll::Pending,
+                Poll::Pending => break Poll::Ready(Some(mem::take(&mut batch))),
+            }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+        (
+            if lower == 0 { 0 } else { 1 },
+            upper.map(|upper| upper / self.cap + (upper % self.cap != 0) as usize),
+        )
+    }
+}
+
+/// A blocking [`Iterator`] over batches of items from a [`Stream`], each collected within a time window.
+///
+/// Created by [`StreamExt::blocking_chunks_timeout`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingChunksTimeout<S> {
+    stream: Pin<Box<S>>,
+    cap: usize,
+    max_wait: Duration,
+    done: bool,
+}
+
+impl<S: Stream> Iterator for BlockingChunksTimeout<S> {
+    type Item = Vec<S::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Wait as long as it takes for the first item of the batch, which starts the clock
+        let mut batch = match self.stream.as_mut().blocking_next() {
+            Some(item) => vec![item],
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+        let deadline = Instant::now().checked_add(self.max_wait);
+
+        let (stream, cap, done) = (&mut self.stream, self.cap, &mut self.done);
+        let fill = poll_fn(|cx| {
+            while batch.len() < cap {
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => batch.push(item),
+                    Poll::Ready(None) => {
+                        *done = true;
+                        break;
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            Poll::Ready(())
+        });
+        // Whether the batch filled up or the deadline elapsed, we hand back whatever we've got
+        match deadline {
+            Some(deadline) => {
+                let _ = block_on_deadline(fill, deadline);
+            }
+            None => block_on(fill),
+        }
+
+        Some(batch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+        // Batches may be cut short by the timeout, so the number of items is the only upper bound
+        (if lower == 0 { 0 } else { 1 }, upper)
+    }
+}
+
+// Pull futures from the stream until there are `n` in flight, returning whether the stream has ended.
+fn fill<S: Stream>(
+    mut stream: Pin<&mut S>,
+    cx: &mut Context<'_>,
+    n: usize,
+    in_flight: usize,
+    mut push: impl FnMut(S::Item),
+) -> bool {
+    for _ in in_flight..n {
+        match stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(fut)) => push(fut),
+            Poll::Ready(None) => return true,
+            Poll::Pending => break,
+        }
+    }
+    false
+}
+
+enum Slot<F: Future> {
+    Pending(Pin<Box<F>>),
+    Done(F::Output),
+}
+
+/// A blocking [`Iterator`] over the outputs of a [`Stream`] of futures, driven concurrently and yielded in order.
+///
+/// Created by [`StreamExt::blocking_buffered`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingBuffered<S: Stream>
+where
+    S::Item: Future,
+{
+    stream: Pin<Box<S>>,
+    in_flight: VecDeque<Slot<S::Item>>,
+    n: usize,
+    done: bool,
+}
+
+impl<S: Stream> Iterator for BlockingBuffered<S>
+where
+    S::Item: Future,
+{
+    type Item = <S::Item as Future>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self {
+            stream,
+            in_flight,
+            n,
+            done,
+        } = self;
+
+        block_on(poll_fn(|cx| {
+            if !*done {
+                let len = in_flight.len();
+                *done = fill(stream.as_mut(), cx, *n, len, |fut| {
+                    in_flight.push_back(Slot::Pending(Box::pin(fut)))
+                });
+            }
+
+            for slot in in_flight.iter_mut() {
+                if let Slot::Pending(fut) = slot {
+                    if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+                        *slot = Slot::Done(output);
+                    }
+                }
+            }
+
+            match in_flight.front() {
+                Some(Slot::Done(_)) => match in_flight.pop_front() {
+                    Some(Slot::Done(output)) => Poll::Ready(Some(output)),
+                    _ => unreachable!(),
+                },
+                None if *done => Poll::Ready(None),
+                _ => Poll::Pending,
+            }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let in_flight = self.in_flight.len();
+        if self.done {
+            return (in_flight, Some(in_flight));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+        (
+            lower.saturating_add(in_flight),
+            upper.and_then(|upper| upper.checked_add(in_flight)),
+        )
+    }
+}
+
+/// A blocking [`Iterator`] over the outputs of a [`Stream`] of futures, driven concurrently and yielded as they
+/// complete.
+///
+/// Created by [`StreamExt::blocking_buffer_unordered`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingBufferUnordered<S: Stream>
+where
+    S::Item: Future,
+{
+    stream: Pin<Box<S>>,
+    in_flight: Vec<Pin<Box<S::Item>>>,
+    n: usize,
+    done: bool,
+}
+
+impl<S: Stream> Iterator for BlockingBufferUnordered<S>
+where
+    S::Item: Future,
+{
+    type Item = <S::Item as Future>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self {
+            stream,
+            in_flight,
+            n,
+            done,
+        } = self;
+
+        block_on(poll_fn(|cx| {
+            if !*done {
+                let len = in_flight.len();
+                *done = fill(stream.as_mut(), cx, *n, len, |fut| in_flight.push(Box::pin(fut)));
+            }
+
+            for i in 0..in_flight.len() {
+                if let Poll::Ready(output) = in_flight[i].as_mut().poll(cx) {
+                    in_flight.swap_remove(i);
+                    return Poll::Ready(Some(output));
+                }
+            }
+
+            if in_flight.is_empty() && *done {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let in_flight = self.in_flight.len();
+        if self.done {
+            return (in_flight, Some(in_flight));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+        (
+            lower.saturating_add(in_flight),
+            upper.and_then(|upper| upper.checked_add(in_flight)),
+        )
+    }
+}
+
+/// Merge several [`Stream`]s into a single blocking [`Iterator`], yielding items from whichever stream is ready.
+///
+/// Each stream is given its own waker, so only the streams that have been woken are polled again. The thread blocks
+/// only when none of the streams are ready. Streams that end are dropped and never polled again, and the iterator
+/// ends once every stream has ended.
+///
+/// # Example
+///
+/// ```
+/// let streams = vec![futures::stream::iter([1, 2]), futures::stream::iter([3, 4])];
+///
+/// let mut items = pollster::stream::merge(streams).collect::<Vec<_>>();
+/// items.sort();
+/// assert_eq!(items, [1, 2, 3, 4]);
+/// ```
+pub fn merge<I>(streams: I) -> BlockingMerge<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream,
+{
+    let shared = Arc::new(MergeShared {
+        state: Mutex::new(MergeState {
+            ready: VecDeque::new(),
+            queued: Vec::new(),
+            waker: None,
+        }),
+    });
+
+    let streams = streams
+        .into_iter()
+        .enumerate()
+        .map(|(index, stream)| {
+            let waker = Waker::from(Arc::new(MergeWaker {
+                index,
+                shared: Arc::clone(&shared),
+            }));
+            Some((Box::pin(stream), waker))
+        })
+        .collect::<Vec<_>>();
+
+    {
+        // Every stream needs to be polled at least once to find out whether it's ready
+        let mut state = shared.state.lock().unwrap();
+        state.ready.extend(0..streams.len());
+        state.queued.resize(streams.len(), true);
+    }
+
+    BlockingMerge {
+        remaining: streams.len(),
+        streams,
+        shared,
+    }
+}
+
+struct MergeState {
+    // Indices of the streams that have been woken and need polling.
+    ready: VecDeque<usize>,
+    // Whether each stream is already in `ready`, to avoid queueing it more than once.
+    queued: Vec<bool>,
+    // The waker of the thread driving the merged streams.
+    waker: Option<Waker>,
+}
+
+struct MergeShared {
+    state: Mutex<MergeState>,
+}
+
+impl MergeShared {
+    fn enqueue(&self, index: usize) -> Option<Waker> {
+        let mut state = self.state.lock().unwrap();
+        if !state.queued[index] {
+            state.queued[index] = true;
+            state.ready.push_back(index);
+        }
+        state.waker.clone()
+    }
+}
+
+struct MergeWaker {
+    index: usize,
+    shared: Arc<MergeShared>,
+}
+
+impl Wake for MergeWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Wake outside of the lock, in case the waker does something expensive
+        if let Some(waker) = self.shared.enqueue(self.index) {
+            waker.wake();
+        }
+    }
+}
+
+/// A blocking [`Iterator`] over the items of several merged [`Stream`]s.
+///
+/// Created by [`merge`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlockingMerge<S> {
+    streams: Vec<Option<(Pin<Box<S>>, Waker)>>,
+    remaining: usize,
+    shared: Arc<MergeShared>,
+}
+
+impl<S: Stream> Iterator for BlockingMerge<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self {
+            streams,
+            remaining,
+            shared,
+        } = self;
+
+        block_on(poll_fn(|cx| loop {
+            let index = {
+                let mut state = shared.state.lock().unwrap();
+                // Register the waker before looking for work, so that a wake that races with us is never missed
+                match &state.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => state.waker = Some(cx.waker().clone()),
+                }
+                match state.ready.pop_front() {
+                    Some(index) => {
+                        state.queued[index] = false;
+                        index
+                    }
+                    None if *remaining == 0 => break Poll::Ready(None),
+                    None => break Poll::Pending,
+                }
+            };
+
+            let slot = &mut streams[index];
+            let (stream, waker) = match slot {
+                Some(slot) => slot,
+                // The stream has already ended, but a stale wake queued it again
+                None => continue,
+            };
+
+            match stream.as_mut().poll_next(&mut Context::from_waker(waker)) {
+                Poll::Ready(Some(item)) => {
+                    // The stream may have more items ready, so give it another turn after the others
+                    shared.enqueue(index);
+                    break Poll::Ready(Some(item));
+                }
+                Poll::Ready(None) => {
+                    *slot = None;
+                    *remaining -= 1;
+                }
+                Poll::Pending => {}
+            }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.streams
+            .iter()
+            .flatten()
+            .map(|(stream, _)| stream.size_hint())
+            .fold((0, Some(0)), |(lower, upper), (l, u)| {
+                (
+                    lower.saturating_add(l),
+                    upper.and_then(|upper| u.and_then(|u| upper.checked_add(u))),
+                )
+            })
+    }
+}
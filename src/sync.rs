@@ -0,0 +1,425 @@
+//! Synchronisation primitives that work from both blocking and async code.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{block_on, block_on_timeout};
+
+// The lowest bit of an event's state is whether it's set. The rest count how many times it has been set, so that a
+// waiter can tell that it was set in between polls even if it has been reset since.
+const SET: usize = 1;
+const GENERATION: usize = 2;
+
+/// A notification that can be set from anywhere, and waited for by blocked threads and async tasks alike.
+///
+/// Once [`set`](Event::set), an event stays set (waking every waiter, and letting later waits return immediately)
+/// until it's [`reset`](Event::reset). A waiter that was waiting when the event was set always returns, even if the
+/// event is reset again before it gets a chance to run.
+///
+/// # Example
+///
+/// ```
+/// use pollster::sync::Event;
+/// use std::{sync::Arc, thread};
+///
+/// let event = Arc::new(Event::new());
+///
+/// let waiter = {
+///     let event = Arc::clone(&event);
+///     thread::spawn(move || event.wait())
+/// };
+///
+/// // Async code can set the event, as can any thread
+/// pollster::block_on(async { event.set() });
+/// waiter.join().unwrap();
+/// ```
+pub struct Event {
+    state: AtomicUsize,
+    // The wakers of blocked threads and async tasks that are waiting
+    waiters: Mutex<Waiters>,
+}
+
+// The wakers of everything waiting on a primitive, each with the ID of the future that registered it.
+struct Waiters {
+    wakers: Vec<(usize, Waker)>,
+    next_id: usize,
+}
+
+impl Waiters {
+    const fn new() -> Self {
+        Self {
+            wakers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    // Register (or update) the waker of the future with the given ID, giving it an ID if it doesn't have one yet.
+    fn register(&mut self, id: &mut Option<usize>, waker: &Waker) {
+        let registered = id.and_then(|id| self.wakers.iter_mut().find(|(other, _)| *other == id));
+        match registered {
+            Some((_, registered)) if registered.will_wake(waker) => {}
+            Some((_, registered)) => *registered = waker.clone(),
+            None => {
+                let new_id = self.next_id;
+                self.next_id = self.next_id.wrapping_add(1);
+                self.wakers.push((new_id, waker.clone()));
+                *id = Some(new_id);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.wakers.retain(|(other, _)| *other != id);
+    }
+
+    // Take every waker, to be woken once the lock has been released.
+    fn take(&mut self) -> Vec<(usize, Waker)> {
+        std::mem::take(&mut self.wakers)
+    }
+}
+
+impl Default for Waiters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn wake_all(wakers: Vec<(usize, Waker)>) {
+    for (_, waker) in wakers {
+        waker.wake();
+    }
+}
+
+impl Event {
+    /// Create an event that isn't set.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            waiters: Mutex::new(Waiters::new()),
+        }
+    }
+
+    /// Set the event, waking everything that's waiting for it.
+    ///
+    /// Setting an event that's already set does nothing.
+    pub fn set(&self) {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & SET != 0 {
+                return;
+            }
+            match self.state.compare_exchange_weak(
+                state,
+                state.wrapping_add(GENERATION) | SET,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => state = actual,
+            }
+        }
+
+        // Waiters check the state while holding the lock, so none can be registered after this without seeing it set
+        let wakers = self.waiters.lock().unwrap().take();
+        wake_all(wakers);
+    }
+
+    /// Reset the event, so that waits block until it's next set.
+    pub fn reset(&self) {
+        self.state.fetch_and(!SET, Ordering::Release);
+    }
+
+    /// Returns whether the event is set.
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) & SET != 0
+    }
+
+    /// Block the thread until the event is set.
+    pub fn wait(&self) {
+        block_on(self.wait_async());
+    }
+
+    /// Block the thread until the event is set, or until `timeout` has elapsed, returning whether the event was set.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        block_on_timeout(self.wait_async(), timeout).is_ok()
+    }
+
+    /// Returns a future that resolves once the event is set.
+    ///
+    /// The event counts as set for this future if it's set at any point after the future is first polled, even if
+    /// it's reset before the future is polled again.
+    pub fn wait_async(&self) -> Wait<'_> {
+        Wait {
+            event: self,
+            start: None,
+            id: None,
+        }
+    }
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Event")
+            .field("set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A future that resolves once an [`Event`] is set.
+///
+/// Created by [`Event::wait_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Wait<'a> {
+    event: &'a Event,
+    // The event's state when this was first polled
+    start: Option<usize>,
+    // The ID this future's waker is registered under, once it has been
+    id: Option<usize>,
+}
+
+impl Wait<'_> {
+    // Whether the event has been set since this was first polled.
+    fn is_done(&mut self) -> bool {
+        let state = self.event.state.load(Ordering::Acquire);
+        let start = *self.start.get_or_insert(state);
+        state & SET != 0 || state & !SET != start & !SET
+    }
+}
+
+impl Future for Wait<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.is_done() {
+            return Poll::Ready(());
+        }
+
+        let event = self.event;
+        let mut waiters = event.waiters.lock().unwrap();
+        // Checked again under the lock, in case the event was set (and its waiters woken) in the meantime
+        if self.is_done() {
+            return Poll::Ready(());
+        }
+        waiters.register(&mut self.id, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl Drop for Wait<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.event.waiters.lock().unwrap().remove(id);
+        }
+    }
+}
+
+impl fmt::Debug for Wait<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wait")
+            .field("event", self.event)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A counter of outstanding work, which threads and async tasks can wait to reach zero.
+///
+/// This bridges fan-out and fan-in: hand a [`Worker`] to each task or thread that's started (or call
+/// [`add`](WaitGroup::add) and [`done`](WaitGroup::done) by hand), and [`wait`](WaitGroup::wait) for them all to
+/// finish. Workers that finish before the wait starts are counted all the same.
+///
+/// A wait returns once the count reaches zero, even if more work is added straight afterwards, before the waiter
+/// gets a chance to run. Cloning a `WaitGroup` gives another handle to the same count.
+///
+/// # Example
+///
+/// ```
+/// use pollster::sync::WaitGroup;
+/// use std::thread;
+///
+/// let group = WaitGroup::new();
+/// for _ in 0..4 {
+///     let worker = group.worker();
+///     thread::spawn(move || {
+///         // ...
+///         drop(worker);
+///     });
+/// }
+///
+/// // Returns once every worker has been dropped
+/// group.wait();
+/// ```
+#[derive(Clone, Default)]
+pub struct WaitGroup {
+    state: Arc<Mutex<WaitGroupState>>,
+}
+
+#[derive(Default)]
+struct WaitGroupState {
+    count: usize,
+    // How many times the count has reached zero, so that a waiter can tell that it did in between polls
+    generation: usize,
+    waiters: Waiters,
+}
+
+impl WaitGroup {
+    /// Create a wait group with a count of zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `n` to the count.
+    pub fn add(&self, n: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.count = state
+            .count
+            .checked_add(n)
+            .expect("`WaitGroup` count overflowed");
+    }
+
+    /// Subtract one from the count, waking everything that's waiting if it reaches zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count is already zero.
+    pub fn done(&self) {
+        let wakers = {
+            let mut state = self.state.lock().unwrap();
+            state.count = state
+                .count
+                .checked_sub(1)
+                .expect("`WaitGroup::done` called more times than `add`");
+            if state.count > 0 {
+                return;
+            }
+            state.generation = state.generation.wrapping_add(1);
+            state.waiters.take()
+        };
+        wake_all(wakers);
+    }
+
+    /// Add one to the count, returning a guard that subtracts it again when dropped.
+    pub fn worker(&self) -> Worker {
+        self.add(1);
+        Worker {
+            group: self.clone(),
+        }
+    }
+
+    /// Get the current count.
+    pub fn count(&self) -> usize {
+        self.state.lock().unwrap().count
+    }
+
+    /// Block the thread until the count reaches zero.
+    pub fn wait(&self) {
+        block_on(self.wait_async());
+    }
+
+    /// Block the thread until the count reaches zero, or until `timeout` has elapsed, returning whether it reached
+    /// zero.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        block_on_timeout(self.wait_async(), timeout).is_ok()
+    }
+
+    /// Returns a future that resolves once the count reaches zero.
+    pub fn wait_async(&self) -> WaitGroupWait<'_> {
+        WaitGroupWait {
+            group: self,
+            start: None,
+            id: None,
+        }
+    }
+}
+
+impl fmt::Debug for WaitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitGroup")
+            .field("count", &self.count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A guard that counts as one piece of outstanding work in a [`WaitGroup`] until it's dropped.
+///
+/// Created by [`WaitGroup::worker`]. Cloning a worker adds one to the count, for the clone to subtract when dropped.
+pub struct Worker {
+    group: WaitGroup,
+}
+
+impl Clone for Worker {
+    fn clone(&self) -> Self {
+        self.group.worker()
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.group.done();
+    }
+}
+
+impl fmt::Debug for Worker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker")
+            .field("group", &self.group)
+            .finish()
+    }
+}
+
+/// A future that resolves once the count of a [`WaitGroup`] reaches zero.
+///
+/// Created by [`WaitGroup::wait_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitGroupWait<'a> {
+    group: &'a WaitGroup,
+    // The group's generation when this was first polled
+    start: Option<usize>,
+    // The ID this future's waker is registered under, once it has been
+    id: Option<usize>,
+}
+
+impl Future for WaitGroupWait<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let group = self.group;
+        let mut state = group.state.lock().unwrap();
+        let start = *self.start.get_or_insert(state.generation);
+        if state.count == 0 || state.generation != start {
+            return Poll::Ready(());
+        }
+        state.waiters.register(&mut self.id, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitGroupWait<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.group.state.lock().unwrap().waiters.remove(id);
+        }
+    }
+}
+
+impl fmt::Debug for WaitGroupWait<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitGroupWait")
+            .field("group", self.group)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,56 @@
+//! Utilities for stress-testing futures.
+//!
+//! These are available behind the `testing` feature, which is intended to be enabled for dev-dependencies only.
+
+use std::future::IntoFuture;
+
+use crate::block_on;
+
+/// Block on `n` futures created by `factory` one after the other, returning their outputs in order.
+///
+/// This is useful for shaking out races and other intermittent bugs by running the same async code many times.
+///
+/// # Example
+///
+/// ```
+/// use pollster::testing::block_on_n_times;
+///
+/// let outputs = block_on_n_times(|| async { 2 + 2 }, 1000);
+/// assert!(outputs.iter().all(|&x| x == 4));
+/// ```
+pub fn block_on_n_times<F, T>(factory: F, n: usize) -> Vec<T::Output>
+where
+    F: Fn() -> T,
+    T: IntoFuture,
+{
+    (0..n).map(|_| block_on(factory())).collect()
+}
+
+/// Block on `n` futures created by `factory` across `threads` worker threads, returning their outputs in order.
+///
+/// Each future is created and blocked on by the worker thread that runs it, so the futures themselves don't need to
+/// be [`Send`]. As with [`block_on_par`](crate::block_on_par), if any of the futures panic, the first panic is resumed
+/// (with its original payload) once all of the workers have finished.
+///
+/// # Panics
+///
+/// Panics if `threads` is zero.
+///
+/// # Example
+///
+/// ```
+/// use pollster::testing::block_on_n_times_parallel;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let counter = AtomicUsize::new(0);
+/// block_on_n_times_parallel(|| async { counter.fetch_add(1, Ordering::Relaxed) }, 1000, 4);
+/// assert_eq!(counter.into_inner(), 1000);
+/// ```
+pub fn block_on_n_times_parallel<F, T>(factory: F, n: usize, threads: usize) -> Vec<T::Output>
+where
+    F: Fn() -> T + Sync,
+    T: IntoFuture,
+    T::Output: Send,
+{
+    crate::threaded::run_par(0..n, threads, |_| block_on(factory()))
+}
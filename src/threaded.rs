@@ -0,0 +1,301 @@
+//! Blocking on futures from other threads.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use crate::{block_on, block_on_uncached};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// An executor that drives futures to completion on a dedicated background thread.
+///
+/// Futures passed to [`ThreadedBlockOn::block_on`] are polled on the background thread while the calling thread waits
+/// for their output. This is useful when a future must be driven from a particular thread, or when it relies on
+/// thread-local state that shouldn't be touched by the caller.
+///
+/// Dropping a `ThreadedBlockOn` waits for the background thread to finish any outstanding work before returning.
+///
+/// # Example
+///
+/// ```
+/// let executor = pollster::ThreadedBlockOn::new();
+///
+/// let my_fut = async { 42 };
+/// assert_eq!(executor.block_on(my_fut), 42);
+/// ```
+pub struct ThreadedBlockOn {
+    jobs: Option<mpsc::Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ThreadedBlockOn {
+    /// Spawn a new background thread to drive futures on.
+    pub fn new() -> Self {
+        let (jobs, rx) = mpsc::channel::<Job>();
+        let thread = thread::spawn(move || {
+            for job in rx {
+                job();
+            }
+        });
+
+        Self {
+            jobs: Some(jobs),
+            thread: Some(thread),
+        }
+    }
+
+    /// Block the calling thread until the future, driven on the background thread, is ready.
+    ///
+    /// If the future panics, the panic is propagated to the calling thread. The background thread remains usable.
+    pub fn block_on<F>(&self, fut: F) -> F::Output
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (tx, rx) = mpsc::sync_channel(1);
+        let job = Box::new(move || {
+            // The receiver can't have gone away, since the calling thread is blocked waiting on it
+            let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(|| block_on(fut))));
+        });
+
+        self.jobs
+            .as_ref()
+            .and_then(|jobs| jobs.send(job).ok())
+            .expect("background thread has stopped: Open a bug report!");
+
+        match rx.recv().expect("background thread has stopped: Open a bug report!") {
+            Ok(output) => output,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl Default for ThreadedBlockOn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ThreadedBlockOn {
+    fn drop(&mut self) {
+        // Dropping the sender ends the background thread's job loop
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Block on a future on a newly spawned thread with the given name, returning a handle to join the thread.
+///
+/// The name is visible in debuggers and panic messages. The future's output, or the panic that it caused, is returned
+/// from [`JoinHandle::join`].
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread, as with [`thread::spawn`].
+///
+/// # Example
+///
+/// ```
+/// let handle = pollster::block_on_thread("worker", async { 42 });
+///
+/// assert_eq!(handle.join().unwrap(), 42);
+/// ```
+pub fn block_on_thread<F>(name: impl Into<String>, fut: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    thread::Builder::new()
+        .name(name.into())
+        // The thread only blocks once, so caching its waker would be wasted
+        .spawn(move || block_on_uncached(fut))
+        .expect("failed to spawn thread")
+}
+
+/// Block on a future on a newly spawned thread, returning a handle to its output without blocking the caller.
+///
+/// This starts the work straight away, and leaves the caller to decide when (or whether) to wait for it with
+/// [`BlockOnHandle::join`], or to check on it with [`BlockOnHandle::try_join`]. Dropping the handle detaches the
+/// thread, as with [`JoinHandle`]: the future still runs to completion, but its output is discarded.
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread, as with [`thread::spawn`].
+///
+/// # Example
+///
+/// ```
+/// let handle = pollster::spawn_block_on(async { 42 });
+///
+/// // Do something else in the meantime...
+///
+/// assert_eq!(handle.join(), 42);
+/// ```
+pub fn spawn_block_on<F>(fut: F) -> BlockOnHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let output = Arc::new(Mutex::new(None));
+    let thread = thread::Builder::new()
+        .spawn({
+            let output = Arc::clone(&output);
+            move || {
+                // The thread only blocks once, so caching its waker would be wasted
+                let result = panic::catch_unwind(AssertUnwindSafe(|| block_on_uncached(fut)));
+                *output.lock().unwrap() = Some(result);
+            }
+        })
+        .expect("failed to spawn thread");
+
+    BlockOnHandle { output, thread }
+}
+
+/// A handle to a future being blocked on by another thread.
+///
+/// Created by [`spawn_block_on`].
+#[must_use = "dropping the handle detaches the thread, discarding the future's output"]
+pub struct BlockOnHandle<T> {
+    output: Arc<Mutex<Option<thread::Result<T>>>>,
+    thread: JoinHandle<()>,
+}
+
+impl<T> BlockOnHandle<T> {
+    /// Block the calling thread until the future is ready, returning its output.
+    ///
+    /// # Panics
+    ///
+    /// If the future panicked, the panic is propagated to the calling thread. Also panics if the output has already
+    /// been taken by [`BlockOnHandle::try_join`].
+    pub fn join(self) -> T {
+        // Panics from the future are caught, so the thread itself can't have panicked
+        let _ = self.thread.join();
+        let output = self.output.lock().unwrap().take();
+        match output {
+            Some(Ok(output)) => output,
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => panic!("the output has already been taken by `try_join`"),
+        }
+    }
+
+    /// Take the future's output if it's ready, without blocking.
+    ///
+    /// Once this has returned `Some`, the output is gone: later calls return `None`, and [`BlockOnHandle::join`]
+    /// panics.
+    ///
+    /// # Panics
+    ///
+    /// If the future panicked, the panic is propagated to the calling thread.
+    pub fn try_join(&self) -> Option<T> {
+        let output = self.output.lock().unwrap().take();
+        match output {
+            Some(Ok(output)) => Some(output),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => None,
+        }
+    }
+
+    /// Get the thread that the future is being blocked on by.
+    pub fn thread(&self) -> &thread::Thread {
+        self.thread.thread()
+    }
+}
+
+impl<T> fmt::Debug for BlockOnHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockOnHandle")
+            .field("thread", self.thread())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Block on a collection of futures in parallel using `threads` worker threads, returning their outputs in order.
+///
+/// Each worker repeatedly takes the next future from a shared queue and blocks on it until it's ready, so this is best
+/// suited to CPU-bound futures: a future that is waiting on IO will occupy its worker until it is ready. Futures may
+/// borrow from the caller, since all of the workers have finished by the time this returns.
+///
+/// # Panics
+///
+/// Panics if `threads` is zero. If any of the futures panic, the first panic is resumed (with its original payload)
+/// once all of the workers have finished.
+///
+/// # Example
+///
+/// ```
+/// let inputs = [1, 2, 3, 4, 5];
+///
+/// let outputs = pollster::block_on_par(inputs.iter().map(|x| async move { x * 2 }), 2);
+/// assert_eq!(outputs, [2, 4, 6, 8, 10]);
+/// ```
+pub fn block_on_par<F, I>(futs: I, threads: usize) -> Vec<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+    I: IntoIterator<Item = F>,
+{
+    run_par(futs, threads, block_on)
+}
+
+// Call `run` on each job across `threads` scoped worker threads, returning the outputs in order.
+//
+// Each worker repeatedly takes the next job from a shared queue, and stops taking jobs if one panics. `thread::scope`
+// would re-panic with a generic message of its own, so each panic is caught instead, and the first one is resumed with
+// its original payload once every worker has finished.
+pub(crate) fn run_par<J, T, I>(jobs: I, threads: usize, run: impl Fn(J) -> T + Sync) -> Vec<T>
+where
+    J: Send,
+    T: Send,
+    I: IntoIterator<Item = J>,
+{
+    assert!(threads > 0, "`threads` must be non-zero");
+
+    let queue = jobs.into_iter().enumerate().collect::<VecDeque<_>>();
+    let len = queue.len();
+    let queue = Mutex::new(queue);
+    let panicked = Mutex::new(None);
+    let (outputs_tx, outputs_rx) = mpsc::channel();
+
+    thread::scope(|s| {
+        for _ in 0..threads.min(len) {
+            let (queue, panicked, run) = (&queue, &panicked, &run);
+            let outputs_tx = outputs_tx.clone();
+            s.spawn(move || loop {
+                // Take the next job without holding the lock while running it
+                let next = queue.lock().unwrap().pop_front();
+                let Some((i, job)) = next else { break };
+                match panic::catch_unwind(AssertUnwindSafe(|| run(job))) {
+                    Ok(output) => {
+                        let _ = outputs_tx.send((i, output));
+                    }
+                    Err(payload) => {
+                        panicked.lock().unwrap().get_or_insert(payload);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    drop(outputs_tx);
+
+    if let Some(payload) = panicked.into_inner().unwrap() {
+        panic::resume_unwind(payload);
+    }
+
+    let mut outputs = (0..len).map(|_| None).collect::<Vec<_>>();
+    for (i, output) in outputs_rx {
+        outputs[i] = Some(output);
+    }
+    outputs
+        .into_iter()
+        .map(|output| output.expect("every job has completed"))
+        .collect()
+}
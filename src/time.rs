@@ -0,0 +1,276 @@
+//! Time-bounded blocking, and (with the `testing` feature) control over the time that sleeps see.
+//!
+//! Everything here apart from [`pause`], [`resume`] and [`advance`] is also exported from the crate root.
+
+use std::{
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "testing")]
+pub use crate::clock::{advance, pause, resume};
+use crate::{block_on_with, clock, sleep, ParkStrategy, Sleep, ThreadPark};
+
+/// A source of monotonic time, used to enforce deadlines.
+///
+/// [`StdClock`] is the implementation used by [`block_on_timeout`] and [`block_on_deadline`]. Targets without
+/// [`std::time::Instant`] (such as bare metal) can implement this trait over a monotonic counter and use
+/// [`block_on_timeout_with`] or [`block_on_deadline_with`] instead.
+pub trait Clock {
+    /// A point in time, as measured by this clock.
+    type Instant: Copy + Ord;
+
+    /// Returns the current time.
+    fn now(&self) -> Self::Instant;
+
+    /// Returns the amount of time that elapsed from `earlier` to `later`, or zero if `earlier` is later than `later`.
+    fn saturating_duration_since(&self, later: Self::Instant, earlier: Self::Instant) -> Duration;
+
+    /// Returns the instant `duration` after `instant`, or `None` if it cannot be represented.
+    fn checked_add(&self, instant: Self::Instant, duration: Duration) -> Option<Self::Instant>;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    type Instant = C::Instant;
+
+    fn now(&self) -> Self::Instant {
+        (**self).now()
+    }
+
+    fn saturating_duration_since(&self, later: Self::Instant, earlier: Self::Instant) -> Duration {
+        (**self).saturating_duration_since(later, earlier)
+    }
+
+    fn checked_add(&self, instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        (**self).checked_add(instant, duration)
+    }
+}
+
+/// A [`Clock`] backed by [`std::time::Instant`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdClock;
+
+impl Clock for StdClock {
+    type Instant = Instant;
+
+    fn now(&self) -> Self::Instant {
+        Instant::now()
+    }
+
+    fn saturating_duration_since(&self, later: Self::Instant, earlier: Self::Instant) -> Duration {
+        later.saturating_duration_since(earlier)
+    }
+
+    fn checked_add(&self, instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant.checked_add(duration)
+    }
+}
+
+/// The error returned when a deadline elapses before an operation completes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elapsed {
+    elapsed: Duration,
+}
+
+impl Elapsed {
+    pub(crate) fn new(elapsed: Duration) -> Self {
+        Self { elapsed }
+    }
+
+    /// The time that had passed since the operation started when it gave up.
+    ///
+    /// This is always at least the duration of the timeout that elapsed.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Returns a future that fails with [`Elapsed`] if `fut` isn't ready within `duration`.
+///
+/// Like [`sleep`], the duration is measured from when this is called, and the future waits in the same way: when
+/// polled by [`block_on`](crate::block_on), the blocked thread wakes at the deadline itself, so any number of
+/// timeouts and sleeps can be joined together without a timer thread. The inner future is always polled first, so one
+/// that's ready in time always succeeds, even if the deadline passes before the timeout is polled again.
+///
+/// To measure the duration from the first poll instead, use [`FutureExt::timeout`](crate::FutureExt::timeout).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let (result, ()) = pollster::block_on(pollster::join(
+///     pollster::timeout(Duration::from_millis(10), std::future::pending::<()>()),
+///     pollster::sleep(Duration::from_millis(20)),
+/// ));
+/// assert!(result.is_err());
+/// ```
+pub fn timeout<F: IntoFuture>(duration: Duration, fut: F) -> WithTimeout<F::IntoFuture> {
+    WithTimeout {
+        fut: fut.into_future(),
+        duration,
+        started: Some((clock::now(), sleep(duration))),
+    }
+}
+
+/// A future that fails with [`Elapsed`] if an inner future isn't ready by a deadline.
+///
+/// Created by [`timeout`] or [`FutureExt::timeout`](crate::FutureExt::timeout).
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WithTimeout<F> {
+    fut: F,
+    duration: Duration,
+    // When the timeout started, and the sleep until its deadline. `None` until the first poll for a timeout created by
+    // `FutureExt::timeout`.
+    started: Option<(Instant, Sleep)>,
+}
+
+impl<F> WithTimeout<F> {
+    // Create a timeout that starts when it's first polled.
+    pub(crate) fn deferred(fut: F, duration: Duration) -> Self {
+        Self {
+            fut,
+            duration,
+            started: None,
+        }
+    }
+
+    /// Returns the instant at which this future fails, or `None` if it's too far in the future to represent.
+    ///
+    /// A timeout created by [`FutureExt::timeout`](crate::FutureExt::timeout) has no deadline until it's first polled,
+    /// so this also returns `None` before then.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.started
+            .as_ref()
+            .and_then(|(_, sleep)| sleep.deadline())
+    }
+}
+
+impl<F: Future> Future for WithTimeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut` is structurally pinned: it's only ever accessed through this `Pin`, and `WithTimeout` has no
+        // `Drop` impl. `Sleep` is `Unpin`, so it doesn't need to stay pinned.
+        let (fut, duration, started) = unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.fut), this.duration, &mut this.started)
+        };
+        let (start, sleep) = started.get_or_insert_with(|| (clock::now(), sleep(duration)));
+
+        if let Poll::Ready(item) = fut.poll(cx) {
+            return Poll::Ready(Ok(item));
+        }
+        Pin::new(sleep)
+            .poll(cx)
+            .map(|()| Err(Elapsed::new(clock::now().saturating_duration_since(*start))))
+    }
+}
+
+impl<F> fmt::Debug for WithTimeout<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithTimeout")
+            .field("duration", &self.duration)
+            .field("deadline", &self.deadline())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Block the thread until the future is ready, or until `timeout` has elapsed.
+///
+/// The future is always polled at least once, even if `timeout` is zero. If the timeout elapses first, the future is
+/// dropped and [`Elapsed`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let my_fut = std::future::pending::<()>();
+/// let result = pollster::block_on_timeout(my_fut, Duration::from_millis(10));
+/// assert!(result.is_err());
+/// ```
+pub fn block_on_timeout<F: IntoFuture>(fut: F, timeout: Duration) -> Result<F::Output, Elapsed> {
+    block_on_timeout_with(fut, timeout, ThreadPark, StdClock)
+}
+
+/// Block the thread until the future is ready, or until `deadline` is reached.
+///
+/// The future is always polled at least once, even if `deadline` has already passed. If the deadline is reached first,
+/// the future is dropped and [`Elapsed`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// let my_fut = async { 42 };
+/// let result = pollster::block_on_deadline(my_fut, Instant::now() + Duration::from_secs(1));
+/// assert_eq!(result, Ok(42));
+/// ```
+pub fn block_on_deadline<F: IntoFuture>(fut: F, deadline: Instant) -> Result<F::Output, Elapsed> {
+    block_on_deadline_with(fut, deadline, ThreadPark, StdClock)
+}
+
+/// Like [`block_on_timeout`], but using the given [`ParkStrategy`] and [`Clock`].
+pub fn block_on_timeout_with<F: IntoFuture, S: ParkStrategy, C: Clock>(
+    fut: F,
+    timeout: Duration,
+    strategy: S,
+    clock: C,
+) -> Result<F::Output, Elapsed> {
+    let start = clock.now();
+    match clock.checked_add(start, timeout) {
+        Some(deadline) => block_on_until(fut, start, deadline, strategy, clock),
+        // A deadline too far in the future to represent will never be reached
+        None => Ok(block_on_with(fut, strategy)),
+    }
+}
+
+/// Like [`block_on_deadline`], but using the given [`ParkStrategy`] and [`Clock`].
+pub fn block_on_deadline_with<F: IntoFuture, S: ParkStrategy, C: Clock>(
+    fut: F,
+    deadline: C::Instant,
+    strategy: S,
+    clock: C,
+) -> Result<F::Output, Elapsed> {
+    let start = clock.now();
+    block_on_until(fut, start, deadline, strategy, clock)
+}
+
+// Block on `fut` until `deadline`, reporting the time since `start` if it's reached first.
+fn block_on_until<F: IntoFuture, S: ParkStrategy, C: Clock>(
+    fut: F,
+    start: C::Instant,
+    deadline: C::Instant,
+    strategy: S,
+    clock: C,
+) -> Result<F::Output, Elapsed> {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let waker = strategy.waker();
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(item) = fut.as_mut().poll(&mut context) {
+            break Ok(item);
+        }
+
+        let now = clock.now();
+        if now >= deadline {
+            break Err(Elapsed::new(clock.saturating_duration_since(now, start)));
+        }
+        strategy.park_timeout(clock.saturating_duration_since(deadline, now));
+    }
+}
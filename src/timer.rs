@@ -0,0 +1,78 @@
+//! A shared background thread that wakes tasks when their deadlines pass.
+//!
+//! `block_on` can wait out a deadline with `thread::park_timeout`, but a future being polled doesn't know how (or even
+//! whether) the thread is parked. Futures that need to be woken at a certain time instead register their waker here,
+//! and a single timer thread (spawned the first time it's needed) wakes them.
+
+use std::{
+    sync::{Arc, Condvar, Mutex, Once, Weak},
+    task::Waker,
+    thread,
+    time::Instant,
+};
+
+/// The waker to wake when a deadline passes.
+///
+/// The owner of a timer keeps the only strong reference to this, and updates the waker each time it's polled. Once
+/// the owner is dropped, the timer thread discards its entry the next time it looks at it.
+pub(crate) type WakerSlot = Arc<Mutex<Option<Waker>>>;
+
+struct Entry {
+    deadline: Instant,
+    waker: Weak<Mutex<Option<Waker>>>,
+}
+
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static CHANGED: Condvar = Condvar::new();
+static SPAWN: Once = Once::new();
+
+/// Wake the waker in `slot` once `deadline` has passed.
+pub(crate) fn register(deadline: Instant, slot: &WakerSlot) {
+    SPAWN.call_once(|| {
+        thread::Builder::new()
+            .name("pollster-timer".into())
+            .spawn(run)
+            .expect("failed to spawn timer thread");
+    });
+
+    ENTRIES.lock().unwrap().push(Entry {
+        deadline,
+        waker: Arc::downgrade(slot),
+    });
+    CHANGED.notify_one();
+}
+
+fn run() {
+    let mut entries = ENTRIES.lock().unwrap();
+    loop {
+        let now = Instant::now();
+
+        // Take everything that has expired, and forget anything whose owner has gone away
+        let mut expired = Vec::new();
+        entries.retain(|entry| match entry.waker.upgrade() {
+            Some(slot) if entry.deadline <= now => {
+                expired.extend(slot.lock().unwrap().take());
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+
+        // Wakers can do anything, including registering new timers, so don't hold the lock while waking them
+        if !expired.is_empty() {
+            drop(entries);
+            expired.into_iter().for_each(Waker::wake);
+            entries = ENTRIES.lock().unwrap();
+            continue;
+        }
+
+        entries = match entries.iter().map(|entry| entry.deadline).min() {
+            Some(next) => {
+                #[cfg(all(windows, feature = "hi-res-timer"))]
+                let _period = crate::hi_res::Period::begin(next - now);
+                CHANGED.wait_timeout(entries, next - now).unwrap().0
+            }
+            None => CHANGED.wait(entries).unwrap(),
+        };
+    }
+}
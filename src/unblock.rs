@@ -0,0 +1,102 @@
+//! Running blocking code from async code.
+
+use std::{
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+/// Run a blocking closure on a newly spawned thread, returning a future that resolves to its output.
+///
+/// This is the inverse of [`block_on`](crate::block_on): it lets async code wait for blocking code (such as file IO or
+/// a heavy computation) without blocking the task that's waiting for it. If `f` panics, the panic is resumed when the
+/// future is next polled.
+///
+/// The closure runs to completion even if the future is dropped.
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread, as with [`thread::spawn`].
+///
+/// # Example
+///
+/// ```
+/// let my_fut = pollster::unblock(|| std::fs::read_dir(".").is_ok());
+///
+/// assert!(pollster::block_on(my_fut));
+/// ```
+pub fn unblock<T, F>(f: F) -> Unblock<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        output: None,
+        waker: None,
+    }));
+
+    thread::Builder::new()
+        .name("pollster-unblock".into())
+        .spawn({
+            let shared = Arc::clone(&shared);
+            move || {
+                let output = panic::catch_unwind(AssertUnwindSafe(f));
+                let waker = {
+                    let mut shared = shared.lock().unwrap();
+                    shared.output = Some(output);
+                    shared.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+        })
+        .expect("failed to spawn thread");
+
+    Unblock { shared }
+}
+
+struct Shared<T> {
+    output: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future that resolves to the output of a closure running on another thread.
+///
+/// Created by [`unblock`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Unblock<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Future for Unblock<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(payload)) => {
+                drop(shared);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                match &mut shared.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    waker => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Unblock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unblock").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,149 @@
+//! Support for blocking on WebAssembly targets.
+
+use std::{
+    error::Error,
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+// How many times `block_on` spins waiting for a wake on the main thread of `wasm32`, before giving up
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+const MAIN_THREAD_SPINS: u32 = 1 << 20;
+
+/// Block the thread until the future is ready, in a way that also works on single-threaded WebAssembly targets.
+///
+/// On `wasm32`, parking a thread isn't generally possible, so this polls the future and then spins (using
+/// [`core::hint::spin_loop`]) until the future's waker is woken before polling again. Note that spinning prevents the
+/// JavaScript event loop from running, so this can only complete futures that are woken without the event loop's
+/// involvement: typically futures that are already ready, or that are woken from another thread (when targeting
+/// `wasm32` with atomics enabled). Futures that wait on JavaScript promises will spin forever.
+///
+/// On all other targets, this is exactly [`block_on`](crate::block_on), so it can be used as a single portable name.
+/// To give up rather than spinning forever, use [`block_on_spin`].
+///
+/// # Example
+///
+/// ```
+/// let my_fut = async { 42 };
+/// let result = pollster::block_on_wasm(my_fut);
+/// assert_eq!(result, 42);
+/// ```
+#[inline]
+pub fn block_on_wasm<F: IntoFuture>(fut: F) -> F::Output {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        crate::block_on(fut)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        spin_on(fut)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spin_on<F: IntoFuture>(fut: F) -> F::Output {
+    let mut fut = core::pin::pin!(fut.into_future());
+
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(Arc::clone(&flag));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut context) {
+            Poll::Pending => {
+                while !flag.0.swap(false, Ordering::Acquire) {
+                    core::hint::spin_loop();
+                }
+            }
+            Poll::Ready(item) => break item,
+        }
+    }
+}
+
+/// Block until the future is ready by spinning rather than parking, giving up if the future isn't woken in time.
+///
+/// Whenever the future is pending, this spins (using [`core::hint::spin_loop`]) up to `spins` times waiting for its
+/// waker to be woken, and polls it again as soon as it is. If it isn't woken before the spins run out, the future is
+/// dropped and [`StillPending`] is returned. This works on any target, including those where the thread can't park
+/// (such as the main thread of `wasm32-unknown-unknown`): futures that are already ready, or that are woken quickly
+/// from elsewhere, complete as usual, and others fail rather than hanging.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(pollster::block_on_spin(async { 42 }, 1000), Ok(42));
+///
+/// // Nothing will ever wake this future
+/// let result = pollster::block_on_spin(std::future::pending::<()>(), 1000);
+/// assert_eq!(result, Err(pollster::StillPending));
+/// ```
+pub fn block_on_spin<F: IntoFuture>(fut: F, spins: u32) -> Result<F::Output, StillPending> {
+    spin_pinned(core::pin::pin!(fut.into_future()), spins)
+}
+
+fn spin_pinned<F: Future + ?Sized>(
+    mut fut: Pin<&mut F>,
+    spins: u32,
+) -> Result<F::Output, StillPending> {
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(Arc::clone(&flag));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(item) = fut.as_mut().poll(&mut context) {
+            return Ok(item);
+        }
+        let mut remaining = spins;
+        while !flag.0.swap(false, Ordering::Acquire) {
+            if remaining == 0 {
+                return Err(StillPending);
+            }
+            remaining -= 1;
+            core::hint::spin_loop();
+        }
+    }
+}
+
+// `block_on` on `wasm32` with the `wasm` feature, where parking the thread may not be possible.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) fn block_on_main_thread<F: Future + ?Sized>(fut: Pin<&mut F>) -> F::Output {
+    match spin_pinned(fut, MAIN_THREAD_SPINS) {
+        Ok(item) => item,
+        Err(StillPending) => panic!(
+            "`pollster::block_on` can't block on wasm32, and the future wasn't woken while spinning. Blocking isn't \
+            possible on the main thread (use `wasm_bindgen_futures::spawn_local` or similar instead), and only \
+            futures that are ready, or woken from another thread, can complete."
+        ),
+    }
+}
+
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// The error returned by [`block_on_spin`] when the future isn't woken before the spins run out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StillPending;
+
+impl fmt::Display for StillPending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("future still pending after spinning")
+    }
+}
+
+impl Error for StillPending {}
@@ -56,3 +56,920 @@ fn mpsc() {
     thread_a.join().expect("join thread_a");
     thread_b.join().expect("join thread_b");
 }
+
+#[test]
+fn progress() {
+    let mut counts = Vec::new();
+
+    pollster::block_on_with_progress(futures_timer::Delay::new(Duration::from_millis(50)), |polls| {
+        counts.push(polls)
+    });
+
+    // The delay must have been pending at least once, and each call receives the running poll count
+    assert!(!counts.is_empty());
+    assert!(counts.iter().copied().eq(1..=counts.len() as u64));
+
+    // Immediately ready futures never report progress
+    let mut called = false;
+    assert_eq!(pollster::block_on_with_progress(std::future::ready(42), |_| called = true), 42);
+    assert!(!called);
+}
+
+#[test]
+fn with_progress_every() {
+    let yields = |n| async move {
+        for _ in 0..n {
+            pollster::yield_now().await;
+        }
+    };
+
+    // Pending 9 times, so every third poll reports progress
+    let mut ticks = 0;
+    pollster::block_on_with_progress_every(yields(9), 3, || ticks += 1);
+    assert_eq!(ticks, 3);
+
+    // The poll that completes the future doesn't count
+    let mut ticks = 0;
+    pollster::block_on_with_progress_every(yields(2), 3, || ticks += 1);
+    assert_eq!(ticks, 0);
+    pollster::block_on_with_progress_every(async {}, 1, || ticks += 1);
+    assert_eq!(ticks, 0);
+}
+
+#[test]
+fn park_strategy() {
+    use pollster::ParkStrategy;
+    use std::{
+        cell::Cell,
+        sync::Arc,
+        task::{Wake, Waker},
+    };
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    // A strategy that never blocks, spinning until the future completes
+    #[derive(Default)]
+    struct Spin {
+        parks: Cell<usize>,
+    }
+
+    impl ParkStrategy for Spin {
+        fn waker(&self) -> Waker {
+            Waker::from(Arc::new(Noop))
+        }
+
+        fn park(&self) {
+            self.parks.set(self.parks.get() + 1);
+        }
+
+        fn park_timeout(&self, _: Duration) -> bool {
+            self.park();
+            true
+        }
+    }
+
+    let spin = Spin::default();
+    assert_eq!(pollster::block_on_with(std::future::ready(42), &spin), 42);
+    assert_eq!(spin.parks.get(), 0);
+
+    let then = Instant::now();
+    pollster::block_on_with(futures_timer::Delay::new(Duration::from_millis(50)), &spin);
+    assert!(Instant::now().duration_since(then) >= Duration::from_millis(50));
+    assert!(spin.parks.get() > 0);
+}
+
+#[test]
+fn resilient() {
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    // A future that loses its wakeups, never waking the waker it was given
+    struct Forgetful(usize);
+
+    impl Future for Forgetful {
+        type Output = usize;
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<usize> {
+            if self.0 == 3 {
+                Poll::Ready(self.0)
+            } else {
+                self.0 += 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    assert_eq!(pollster::block_on_resilient(Forgetful(0), Duration::from_millis(10)), 3);
+}
+
+#[test]
+fn pinned() {
+    use futures::FutureExt as _;
+
+    let mut fut = core::pin::pin!(async {
+        futures_timer::Delay::new(Duration::from_millis(10)).await;
+        42
+    });
+
+    // Poll the future a little elsewhere before handing it to pollster to finish off
+    assert!(fut.as_mut().now_or_never().is_none());
+    assert_eq!(pollster::block_on_pinned(fut.as_mut()), 42);
+
+    // Works through trait objects too
+    let mut fut: std::pin::Pin<Box<dyn std::future::Future<Output = i32>>> = Box::pin(async { 7 });
+    assert_eq!(pollster::block_on_pinned(fut.as_mut()), 7);
+}
+
+#[test]
+fn inspect_poll() {
+    use pollster::FutureExt as _;
+    use std::task::Poll;
+
+    let mut polls = Vec::new();
+    let result = async {
+        futures_timer::Delay::new(Duration::from_millis(10)).await;
+        42
+    }
+    .inspect_poll(|poll| polls.push(*poll))
+    .block_on();
+
+    assert_eq!(result, 42);
+    assert_eq!(polls.last(), Some(&Poll::Ready(42)));
+    assert!(polls[..polls.len() - 1].iter().all(Poll::is_pending));
+}
+
+#[test]
+fn stepper() {
+    use std::task::Poll;
+
+    let mut stepper = pollster::Stepper::new(async {
+        futures_timer::Delay::new(Duration::from_millis(50)).await;
+        42
+    });
+
+    // Stepping never blocks, so the delay is still pending straight away
+    assert_eq!(stepper.step(), Poll::Pending);
+
+    let then = Instant::now();
+    let result = loop {
+        if let Poll::Ready(result) = stepper.step() {
+            break result;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    };
+    assert_eq!(result, 42);
+    assert!(then.elapsed() > Duration::from_millis(25));
+}
+
+#[test]
+fn catch_unwind() {
+    use pollster::FutureExt as _;
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+        thread,
+    };
+
+    // Panics on its second poll, after being woken from another thread
+    struct PanicAfterWake(bool);
+
+    impl Future for PanicAfterWake {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            if self.0 {
+                panic!("second poll");
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                waker.wake();
+            });
+            Poll::Pending
+        }
+    }
+
+    let err = PanicAfterWake(false).block_on_catch_unwind().unwrap_err();
+    assert_eq!(err.downcast_ref::<&str>(), Some(&"second poll"));
+
+    assert_eq!(pollster::block_on_catch_unwind(async { 42 }).ok(), Some(42));
+}
+
+#[test]
+fn checked() {
+    use std::{future::poll_fn, task::Poll, thread};
+
+    // Ready futures and futures that hold their waker are fine
+    assert_eq!(pollster::block_on_checked(std::future::ready(42)), Ok(42));
+    assert_eq!(
+        pollster::block_on_checked(futures_timer::Delay::new(Duration::from_millis(10))),
+        Ok(())
+    );
+
+    // Waking from another thread, with the only clone of the waker
+    let mut spawned = false;
+    let result = pollster::block_on_checked(poll_fn(|cx| {
+        if spawned {
+            return Poll::Ready(());
+        }
+        spawned = true;
+        let waker = cx.waker().clone();
+        thread::spawn(move || waker.wake());
+        Poll::Pending
+    }));
+    assert_eq!(result, Ok(()));
+
+    // Self-waking with `wake_by_ref` doesn't need to hold the waker
+    let mut yielded = false;
+    let result = pollster::block_on_checked(poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }));
+    assert_eq!(result, Ok(()));
+
+    // Pending without storing the waker is an error
+    assert!(pollster::block_on_checked(poll_fn(|_| Poll::<()>::Pending)).is_err());
+
+    // As is dropping the only clone without waking it
+    let result = pollster::block_on_checked(poll_fn(|cx| {
+        drop(cx.waker().clone());
+        Poll::<()>::Pending
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn budget() {
+    use std::{future::poll_fn, task::Poll};
+
+    // A future that wakes itself every time, so it never genuinely waits
+    let mut polls = 0;
+    let result = pollster::block_on_budget(
+        poll_fn(|cx| {
+            polls += 1;
+            if polls == 1000 {
+                Poll::Ready(polls)
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }),
+        8,
+    );
+    assert_eq!(result, 1000);
+
+    pollster::block_on_budget(futures_timer::Delay::new(Duration::from_millis(10)), 1);
+}
+
+#[test]
+fn poll_with_context() {
+    use std::{future::poll_fn, thread};
+
+    // The inner future is woken through the waker of the outer task, which runs on its own thread
+    let (tx, rx) = std::sync::mpsc::channel();
+    let outer = thread::spawn(move || {
+        let mut inner = core::pin::pin!(futures_timer::Delay::new(Duration::from_millis(10)));
+        pollster::block_on(poll_fn(|cx| {
+            let _ = tx.send(());
+            pollster::poll_with_context(inner.as_mut(), cx)
+        }));
+    });
+
+    outer.join().expect("join outer");
+    // Polled at least once before the delay elapsed, and again afterwards
+    assert!(rx.try_iter().count() >= 2);
+}
+
+#[test]
+fn builder() {
+    use pollster::BlockOnBuilder;
+    use std::{
+        future::{pending, poll_fn},
+        task::Poll,
+        thread,
+        time::Instant,
+    };
+
+    assert_eq!(BlockOnBuilder::new().run(async { 42 }), 42);
+
+    // A future that wakes itself is found woken while spinning, so each pending poll is followed by exactly one more
+    // poll, and the budget only decides when the thread yields in between
+    let mut pending_polls = Vec::new();
+    let mut polled = 0;
+    let result = BlockOnBuilder::new()
+        .on_poll(|n| pending_polls.push(n))
+        .spin_limit(16)
+        .budget(4)
+        .run(poll_fn(|cx| {
+            polled += 1;
+            if polled > 10 {
+                Poll::Ready(polled)
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }));
+    assert_eq!(result, 11);
+    assert_eq!(pending_polls, (1..=10).collect::<Vec<_>>());
+
+    // A wake from another thread that comes after the spin limit is used up parks the thread once
+    let (tx, rx) = pollster::oneshot::channel();
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        tx.send(42).unwrap();
+    });
+    let mut pending_polls = Vec::new();
+    let result = BlockOnBuilder::new()
+        .on_poll(|n| pending_polls.push(n))
+        .spin_limit(16)
+        .budget(4)
+        .run(rx);
+    sender.join().unwrap();
+    assert_eq!(result, Ok(42));
+    assert_eq!(pending_polls, [1]);
+
+    let start = Instant::now();
+    let result = BlockOnBuilder::new()
+        .poll_interval(Duration::from_millis(1))
+        .timeout(Duration::from_millis(20))
+        .run(pending::<()>());
+    assert!(result.is_err());
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    // A future that keeps waking itself never parks for long, but still times out
+    let start = Instant::now();
+    let result = BlockOnBuilder::new()
+        .timeout(Duration::from_millis(50))
+        .run(poll_fn(|cx| {
+            cx.waker().wake_by_ref();
+            Poll::<()>::Pending
+        }));
+    assert!(result.is_err());
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+}
+
+#[test]
+fn wake_handle() {
+    use std::{
+        future::poll_fn,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::Poll,
+        thread,
+    };
+
+    // A future that only becomes ready once the counter reaches 3, and never wakes itself
+    let counter = Arc::new(AtomicUsize::new(0));
+    let (handle, fut) = pollster::wrap_wakeable(poll_fn({
+        let counter = Arc::clone(&counter);
+        move |_| match counter.load(Ordering::Acquire) {
+            3 => Poll::Ready(3),
+            _ => Poll::Pending,
+        }
+    }));
+
+    // Waking before the first poll is harmless
+    handle.wake();
+
+    let waker_thread = thread::spawn({
+        let handle = handle.clone();
+        move || {
+            for _ in 0..3 {
+                thread::sleep(Duration::from_millis(10));
+                counter.fetch_add(1, Ordering::Release);
+                handle.wake();
+            }
+        }
+    });
+
+    assert_eq!(pollster::block_on(fut), 3);
+    waker_thread.join().expect("join waker thread");
+}
+
+#[test]
+fn driver() {
+    use std::{cell::Cell, future::poll_fn, sync::mpsc, task::Poll, thread};
+
+    // A stand-in for an OS poller: events arrive on a channel, and the driver blocks waiting for them
+    let (events_tx, events_rx) = mpsc::channel();
+    let producer = thread::spawn(move || {
+        for _ in 0..3 {
+            thread::sleep(Duration::from_millis(10));
+            events_tx.send(()).expect("send event");
+        }
+    });
+
+    // The future never registers its waker, so only the driver can make it progress
+    let events = Cell::new(0);
+    let result = pollster::block_on_with_driver(
+        poll_fn(|_| match events.get() {
+            3 => Poll::Ready(3),
+            _ => Poll::Pending,
+        }),
+        |waker| {
+            if events_rx.recv().is_ok() {
+                events.set(events.get() + 1);
+                waker.wake_by_ref();
+            }
+        },
+    );
+
+    assert_eq!(result, 3);
+    producer.join().expect("join producer");
+}
+
+#[test]
+fn yield_now() {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Pending exactly once, with exactly one wake
+    let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+    let waker = Waker::from(Arc::clone(&wakes));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(pollster::yield_now());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+
+    // Under `block_on`, each yield costs one extra poll
+    let mut polls = 0;
+    pollster::block_on_with_progress(
+        async {
+            for _ in 0..3 {
+                pollster::yield_now().await;
+            }
+        },
+        |n| polls = n,
+    );
+    assert_eq!(polls, 3);
+}
+
+#[test]
+fn block_on_dyn() {
+    use std::{future::Future, pin::Pin};
+
+    let mut futs: Vec<Pin<Box<dyn Future<Output = u32>>>> = vec![
+        Box::pin(async { 1 }),
+        Box::pin(async {
+            futures_timer::Delay::new(Duration::from_millis(10)).await;
+            2
+        }),
+    ];
+    let outputs = futs
+        .iter_mut()
+        .map(|fut| pollster::block_on_dyn(fut.as_mut()))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, [1, 2]);
+}
+
+#[test]
+fn signal_pair() {
+    use std::thread;
+
+    let (signal, waker) = pollster::signal_pair();
+    assert!(!signal.take_woken());
+
+    // A wake before waiting is remembered, and several wakes only count once
+    waker.wake_by_ref();
+    waker.wake_by_ref();
+    signal.wait();
+    assert!(!signal.take_woken());
+
+    let then = Instant::now();
+    let waker2 = waker.clone();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        waker2.wake();
+    });
+    signal.wait();
+    assert!(then.elapsed() >= Duration::from_millis(50));
+    handle.join().unwrap();
+
+    // The signal can be moved, but only waited on by the thread that created it
+    let result = thread::spawn(move || signal.wait()).join();
+    assert!(result.is_err());
+    drop(waker);
+}
+
+#[test]
+fn signal_for_thread() {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::Waker,
+        thread,
+    };
+
+    // A coordinator parks until it's woken by a waker built on another thread
+    let done = Arc::new(AtomicBool::new(false));
+    let coordinator = thread::spawn({
+        let done = Arc::clone(&done);
+        move || {
+            while !done.load(Ordering::Acquire) {
+                thread::park();
+            }
+        }
+    });
+
+    let signal = pollster::Signal::for_thread(coordinator.thread().clone());
+    assert_eq!(signal.thread().id(), coordinator.thread().id());
+    let waker = Waker::from(signal);
+    thread::sleep(Duration::from_millis(10));
+    done.store(true, Ordering::Release);
+    waker.wake();
+    coordinator.join().unwrap();
+}
+
+#[test]
+fn block_on_uncached() {
+    let output = std::thread::spawn(|| {
+        pollster::block_on_uncached(async {
+            futures_timer::Delay::new(Duration::from_millis(10)).await;
+            42
+        })
+    })
+    .join()
+    .unwrap();
+    assert_eq!(output, 42);
+}
+
+#[test]
+fn block_on_ready() {
+    use std::{sync::mpsc, task::Poll, thread};
+
+    assert_eq!(pollster::block_on_ready(async { 42 }), 42);
+
+    // The no-op waker sent on the first poll is never woken, but the future is polled again straight away with one
+    // that is
+    let (tx, rx) = mpsc::channel::<std::task::Waker>();
+    let waker_thread = thread::spawn(move || {
+        for waker in rx {
+            thread::sleep(Duration::from_millis(10));
+            waker.wake();
+        }
+    });
+    let mut polls = 0;
+    let output = pollster::block_on_ready(std::future::poll_fn(|cx| {
+        polls += 1;
+        if polls < 3 {
+            tx.send(cx.waker().clone()).unwrap();
+            Poll::Pending
+        } else {
+            Poll::Ready(polls)
+        }
+    }));
+    assert_eq!(output, 3);
+    drop(tx);
+    waker_thread.join().unwrap();
+}
+
+#[test]
+fn noop_waker() {
+    let waker = pollster::noop_waker();
+    let clone = waker.clone();
+    assert!(clone.will_wake(&waker));
+    clone.wake_by_ref();
+    clone.wake();
+    waker.wake();
+}
+
+#[test]
+fn block_on_abort_on_panic() {
+    use std::process::Command;
+
+    assert_eq!(pollster::block_on_abort_on_panic(async { 42 }), 42);
+    assert_eq!(
+        pollster::BlockOnBuilder::new()
+            .abort_on_panic()
+            .run(async { 42 }),
+        42
+    );
+
+    // Aborting can only be observed from outside, so run this test again in a child process that panics
+    if let Ok(via) = std::env::var("POLLSTER_ABORT_ON_PANIC") {
+        match via.as_str() {
+            "fn" => pollster::block_on_abort_on_panic(async { panic!("oh no") }),
+            _ => pollster::BlockOnBuilder::new()
+                .abort_on_panic()
+                .run(async { panic!("oh no") }),
+        }
+    }
+
+    for via in ["fn", "builder"] {
+        let output = Command::new(std::env::current_exe().expect("current exe"))
+            .args(["--exact", "block_on_abort_on_panic", "--test-threads=1"])
+            .env("POLLSTER_ABORT_ON_PANIC", via)
+            .output()
+            .expect("run child");
+        assert!(!output.status.success());
+        // A panic that unwound would be caught by the test harness and reported as a failure instead
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("test result"));
+        #[cfg(unix)]
+        assert_eq!(
+            std::os::unix::process::ExitStatusExt::signal(&output.status),
+            Some(6)
+        );
+    }
+}
+
+#[cfg(feature = "condvar")]
+#[test]
+fn condvar() {
+    use std::{
+        sync::{Arc, Mutex},
+        task::{Poll, Waker},
+        thread,
+    };
+
+    // Unparking the thread doesn't wake a future blocked on with a condition variable, so it's only polled again when
+    // it's actually woken
+    let waker = Arc::new(Mutex::new(None::<Waker>));
+    let blocked = thread::current();
+    let waker2 = Arc::clone(&waker);
+    let handle = thread::spawn(move || {
+        while waker2.lock().unwrap().is_none() {
+            thread::yield_now();
+        }
+        blocked.unpark();
+        thread::sleep(Duration::from_millis(50));
+        waker2.lock().unwrap().take().unwrap().wake();
+    });
+
+    let mut polls = 0;
+    pollster::block_on(std::future::poll_fn(|cx| {
+        polls += 1;
+        if polls == 1 {
+            *waker.lock().unwrap() = Some(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }));
+    assert_eq!(polls, 2);
+    handle.join().unwrap();
+}
+
+#[test]
+fn block_on_with_yield() {
+    use std::{
+        sync::{mpsc, Arc},
+        thread,
+    };
+
+    // Other work is done while the future waits, until it's woken from another thread
+    let (jobs_tx, jobs_rx) = mpsc::channel();
+    let (done_tx, done_rx) = futures::channel::oneshot::channel();
+    let producer = thread::spawn(move || {
+        for i in 0..3 {
+            jobs_tx.send(i).expect("send job");
+            thread::sleep(Duration::from_millis(10));
+        }
+        done_tx.send("done").expect("send done");
+    });
+
+    let mut jobs = Vec::new();
+    let result = pollster::block_on_with_yield(done_rx, || {
+        jobs.extend(jobs_rx.try_iter());
+        true
+    });
+    assert_eq!(result, Some(Ok("done")));
+    producer.join().expect("join producer");
+    jobs.extend(jobs_rx.try_iter());
+    assert_eq!(jobs, [0, 1, 2]);
+
+    // Giving up drops the future straight away
+    let alive = Arc::new(());
+    let held = Arc::clone(&alive);
+    let result = pollster::block_on_with_yield(
+        async move {
+            let _held = held;
+            std::future::pending::<()>().await
+        },
+        || false,
+    );
+    assert_eq!(result, None);
+    assert_eq!(Arc::strong_count(&alive), 1);
+}
+
+#[test]
+fn shared() {
+    use pollster::FutureExt as _;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering::SeqCst},
+        thread,
+    };
+
+    static POLLS: AtomicUsize = AtomicUsize::new(0);
+
+    // Only completes once the value has been sent, so every clone has to wait for it
+    let (tx, rx) = pollster::oneshot::channel();
+    let fut = rx
+        .inspect_poll(|_| {
+            POLLS.fetch_add(1, SeqCst);
+        })
+        .shared();
+
+    let waiters = (0..4)
+        .map(|_| {
+            let fut = fut.clone();
+            thread::spawn(move || fut.block_on())
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(50));
+    tx.send(String::from("hello")).unwrap();
+
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap().as_deref(), Ok("hello"));
+    }
+    // The output is kept for clones that haven't been polled yet
+    assert_eq!(fut.block_on().as_deref(), Ok("hello"));
+    // Polled at most once by each waiter before the value was sent, and then once more to complete
+    assert!(POLLS.load(SeqCst) <= 5);
+
+    // A panic is passed on to every clone
+    let fut = async { panic!("oh no") }.shared();
+    let other = fut.clone();
+    assert!(fut.block_on_catch_unwind().is_err());
+    assert!(std::panic::catch_unwind(move || other.block_on()).is_err());
+}
+
+#[test]
+fn and_then() {
+    use pollster::FutureExt as _;
+    use std::{
+        cell::Cell,
+        future::Future,
+        task::{Context, Poll},
+    };
+
+    let built = Cell::new(false);
+    let mut fut = Box::pin(
+        async {
+            pollster::yield_now().await;
+            20
+        }
+        .and_then(|n| {
+            built.set(true);
+            async move { n * 2 }
+        }),
+    );
+
+    // The second future isn't built until the first is ready, and is then polled in the same poll
+    let waker = pollster::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert!(!built.get());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(40));
+    assert!(built.get());
+
+    let then = Instant::now();
+    pollster::block_on_chain(futures_timer::Delay::new(Duration::from_millis(50)), |()| {
+        futures_timer::Delay::new(Duration::from_millis(50))
+    });
+    assert!(then.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn timed() {
+    let (output, parked) = pollster::block_on_timed(async { 42 });
+    assert_eq!(output, 42);
+    assert_eq!(parked, Duration::ZERO);
+
+    // Time spent waiting for the timer counts, but time spent busy in `poll` doesn't
+    let (output, parked) = pollster::block_on_timed(async {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(200) {}
+        futures_timer::Delay::new(Duration::from_millis(50)).await;
+        "done"
+    });
+    assert_eq!(output, "done");
+    assert!(parked >= Duration::from_millis(40), "{:?}", parked);
+    assert!(parked < Duration::from_millis(200), "{:?}", parked);
+}
+
+#[test]
+fn spin() {
+    assert_eq!(pollster::block_on_spin(async { 42 }, 0), Ok(42));
+    assert_eq!(
+        pollster::block_on_spin(std::future::pending::<()>(), 1000),
+        Err(pollster::StillPending)
+    );
+
+    // A future that wakes itself makes progress, however few spins are allowed
+    let result = pollster::block_on_spin(
+        async {
+            for _ in 0..10 {
+                pollster::yield_now().await;
+            }
+            "done"
+        },
+        0,
+    );
+    assert_eq!(result, Ok("done"));
+}
+
+#[test]
+fn try_poll_once() {
+    use std::task::Poll;
+
+    let mut fut = core::pin::pin!(async {
+        pollster::yield_now().await;
+        42
+    });
+    assert_eq!(pollster::try_poll_once(fut.as_mut()), Poll::Pending);
+    // The future woke itself, and is ready when polled again
+    assert_eq!(pollster::try_poll_once(fut.as_mut()), Poll::Ready(42));
+}
+
+#[test]
+fn or_panic() {
+    assert_eq!(pollster::block_on_or_panic(async { 42 }, "not ready"), 42);
+
+    // A future may be pending fewer times than allowed
+    let yields_twice = async {
+        pollster::yield_now().await;
+        pollster::yield_now().await;
+        42
+    };
+    assert_eq!(
+        pollster::block_on_or_panic_after(yields_twice, 3, "not ready"),
+        42
+    );
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "should be ready (future still pending after 1 polls)")
+)]
+fn or_panic_pending() {
+    pollster::block_on_or_panic(pollster::yield_now(), "should be ready");
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "still pending after 2 polls")
+)]
+fn or_panic_after_pending() {
+    let yields_twice = async {
+        pollster::yield_now().await;
+        pollster::yield_now().await;
+    };
+    pollster::block_on_or_panic_after(yields_twice, 2, "should be ready");
+}
+
+#[test]
+fn waker_identity() {
+    use std::{future::poll_fn, task::Poll, thread};
+
+    let capture = || pollster::block_on(poll_fn(|cx| Poll::Ready(cx.waker().clone())));
+    let waker = capture();
+    assert!(pollster::same_waker(&waker, &waker.clone()));
+    assert!(pollster::same_waker(&waker, &capture()));
+    assert!(pollster::is_pollster_waker(&waker));
+
+    // Another thread's waker wakes a different thread
+    let other = thread::spawn(capture).join().unwrap();
+    assert!(!pollster::same_waker(&waker, &other));
+    assert!(!pollster::is_pollster_waker(&other));
+
+    // A waker that does nothing isn't a live pollster waker
+    assert!(!pollster::is_pollster_waker(&pollster::noop_waker()));
+    assert!(!pollster::is_pollster_waker(&futures::task::noop_waker()));
+}
@@ -0,0 +1,126 @@
+#![cfg(all(feature = "futures-channel", feature = "tokio-channel"))]
+
+use std::{thread, time::Duration};
+
+use pollster::channel::{
+    BlockingReceiver, BlockingSender, RecvTimeoutError, SendError, TryRecvError,
+};
+
+#[test]
+fn throughput() {
+    const N: u64 = 10_000;
+
+    let (tx, rx) = futures::channel::mpsc::channel(4);
+    let (mut tx, rx) = (BlockingSender::new(tx), BlockingReceiver::new(rx));
+    let producer = thread::spawn(move || {
+        for i in 0..N {
+            tx.send(i).unwrap();
+        }
+    });
+    assert_eq!(rx.sum::<u64>(), N * (N - 1) / 2);
+    producer.join().unwrap();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let (mut tx, rx) = (BlockingSender::new(tx), BlockingReceiver::new(rx));
+    let producer = thread::spawn(move || {
+        for i in 0..N {
+            tx.send(i).unwrap();
+        }
+    });
+    assert_eq!(rx.sum::<u64>(), N * (N - 1) / 2);
+    producer.join().unwrap();
+}
+
+#[test]
+fn many_senders() {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let tx = BlockingSender::new(tx);
+    let producers = (0..4)
+        .map(|i| {
+            let mut tx = tx.clone();
+            thread::spawn(move || tx.send(i).unwrap())
+        })
+        .collect::<Vec<_>>();
+    drop(tx);
+
+    let mut received = BlockingReceiver::new(rx).collect::<Vec<_>>();
+    received.sort();
+    assert_eq!(received, [0, 1, 2, 3]);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+}
+
+#[test]
+fn sender_dropped_while_parked() {
+    let (tx, rx) = futures::channel::mpsc::channel::<i32>(1);
+    let tx = BlockingSender::new(tx);
+    let mut rx = BlockingReceiver::new(rx);
+    let dropper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(tx);
+    });
+    assert_eq!(rx.recv(), None);
+    dropper.join().unwrap();
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<i32>(1);
+    let tx = BlockingSender::new(tx);
+    let mut rx = BlockingReceiver::new(rx);
+    let dropper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(tx);
+    });
+    assert_eq!(rx.recv(), None);
+    dropper.join().unwrap();
+}
+
+#[test]
+fn receiver_dropped_while_parked() {
+    // With one sender and a buffer of zero, the second send waits for the first item to be received
+    let (tx, rx) = futures::channel::mpsc::channel(0);
+    let mut tx = BlockingSender::new(tx);
+    let rx = BlockingReceiver::new(rx);
+    tx.send(1).unwrap();
+    let dropper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(rx);
+    });
+    assert_eq!(tx.send(2).map_err(SendError::into_inner), Err(2));
+    dropper.join().unwrap();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let mut tx = BlockingSender::new(tx);
+    let rx = BlockingReceiver::new(rx);
+    tx.send(1).unwrap();
+    let dropper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(rx);
+    });
+    assert_eq!(tx.send(2).map_err(SendError::into_inner), Err(2));
+    dropper.join().unwrap();
+}
+
+#[test]
+fn try_recv_and_timeout() {
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    let mut tx = BlockingSender::new(tx);
+    let mut rx = BlockingReceiver::new(rx);
+
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+
+    tx.send(1).unwrap();
+    assert_eq!(rx.try_recv(), Ok(1));
+    tx.send(2).unwrap();
+    assert_eq!(rx.recv_timeout(Duration::ZERO), Ok(2));
+
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_secs(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
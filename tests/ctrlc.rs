@@ -0,0 +1,49 @@
+#![cfg(all(any(target_os = "linux", target_os = "android"), feature = "ctrlc"))]
+
+use std::{cell::Cell, mem, ptr, thread, time::Duration};
+
+use pollster::Interrupted;
+
+// Records when it's dropped
+struct Guard<'a>(&'a Cell<bool>);
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+fn sigint_disposition() -> libc::sighandler_t {
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        assert_eq!(libc::sigaction(libc::SIGINT, ptr::null(), &mut action), 0);
+        action.sa_sigaction
+    }
+}
+
+// Everything is in one test, because only one thread can be waiting for `SIGINT` at a time
+#[test]
+fn interruptible() {
+    assert_eq!(sigint_disposition(), libc::SIG_DFL);
+
+    // A future that finishes is unaffected
+    assert_eq!(pollster::block_on_interruptible(async { 42 }), Ok(42));
+    assert_eq!(sigint_disposition(), libc::SIG_DFL);
+
+    let dropped = Cell::new(false);
+    let sender = thread::spawn(|| {
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(unsafe { libc::kill(libc::getpid(), libc::SIGINT) }, 0);
+    });
+    let result = pollster::block_on_interruptible(async {
+        let _guard = Guard(&dropped);
+        std::future::pending::<()>().await
+    });
+    sender.join().unwrap();
+    assert_eq!(result, Err(Interrupted));
+    assert!(dropped.get());
+
+    // The default disposition is back, and the next call starts afresh
+    assert_eq!(sigint_disposition(), libc::SIG_DFL);
+    assert_eq!(pollster::block_on_interruptible(async { 42 }), Ok(42));
+}
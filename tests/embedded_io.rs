@@ -0,0 +1,127 @@
+#![cfg(feature = "embedded-io")]
+
+use std::{future::poll_fn, task::Poll, thread, time::Duration};
+
+use embedded_io::{BufRead, ErrorKind, ErrorType, Read, Write};
+use pollster::io::embedded::{BlockingReader, BlockingWriter};
+
+#[derive(Debug, PartialEq)]
+enum DriverError {
+    Full,
+}
+
+impl embedded_io::Error for DriverError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::OutOfMemory
+    }
+}
+
+// Waits for a wake from another thread, as a driver waiting on an interrupt would
+async fn interrupt() {
+    let mut fired = false;
+    poll_fn(|cx| {
+        if std::mem::replace(&mut fired, true) {
+            return Poll::Ready(());
+        }
+        let waker = cx.waker().clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(5));
+            waker.wake();
+        });
+        Poll::Pending
+    })
+    .await
+}
+
+// A reader that hands out at most 2 bytes per interrupt
+struct Uart {
+    data: &'static [u8],
+}
+
+impl ErrorType for Uart {
+    type Error = DriverError;
+}
+
+impl embedded_io_async::Read for Uart {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        interrupt().await;
+        let n = buf.len().min(self.data.len()).min(2);
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+impl embedded_io_async::BufRead for Uart {
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        interrupt().await;
+        Ok(&self.data[..self.data.len().min(2)])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.data = &self.data[amt..];
+    }
+}
+
+// A writer with room for a fixed number of bytes
+struct Flash {
+    written: Vec<u8>,
+    capacity: usize,
+    flushes: usize,
+}
+
+impl ErrorType for Flash {
+    type Error = DriverError;
+}
+
+impl embedded_io_async::Write for Flash {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        interrupt().await;
+        let n = buf.len().min(self.capacity - self.written.len());
+        if n == 0 {
+            return Err(DriverError::Full);
+        }
+        self.written.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        interrupt().await;
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn read() {
+    let mut reader = BlockingReader::new(Uart { data: b"hello" });
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 2);
+    assert_eq!(reader.read(&mut []).expect("read"), 0);
+
+    let mut rest = [0; 3];
+    reader.read_exact(&mut rest).expect("read exact");
+    assert_eq!(&rest, b"llo");
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+
+    let mut reader = BlockingReader::new(Uart { data: b"hello" });
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"he");
+    reader.consume(1);
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"el");
+}
+
+#[test]
+fn write() {
+    let mut writer = BlockingWriter::new(Flash {
+        written: Vec::new(),
+        capacity: 8,
+        flushes: 0,
+    });
+    writer.write_all(b"hello").expect("write all");
+    writer.flush().expect("flush");
+    assert_eq!(writer.get_ref().flushes, 1);
+
+    // The driver's own error type comes through unchanged
+    assert_eq!(writer.write_all(b", world"), Err(DriverError::Full));
+    assert_eq!(writer.into_inner().written, b"hello, w");
+}
@@ -0,0 +1,147 @@
+use std::{cell::Cell, future::poll_fn, task::Poll, time::Duration};
+
+use pollster::LocalExecutor;
+
+#[test]
+fn local_executor() {
+    let log = std::cell::RefCell::new(Vec::new());
+
+    let mut executor = LocalExecutor::new();
+    for i in 0..3u64 {
+        let log = &log;
+        executor.spawn(async move {
+            futures_timer::Delay::new(Duration::from_millis(30 - 10 * i)).await;
+            log.borrow_mut().push(i);
+        });
+    }
+    executor.run();
+
+    // Each future finished when its delay did, rather than in the order they were spawned
+    assert_eq!(*log.borrow(), [2, 1, 0]);
+
+    // Nothing to run
+    LocalExecutor::new().run();
+}
+
+#[test]
+fn local_executor_budget() {
+    // A future that keeps waking itself until told to stop, counting its polls
+    let stop = Cell::new(false);
+    let hog_polls = Cell::new(0);
+    let hog = poll_fn(|cx| {
+        if stop.get() {
+            return Poll::Ready(());
+        }
+        hog_polls.set(hog_polls.get() + 1);
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    });
+
+    // A future that needs a few polls to finish, and then stops the hog
+    let mut polls = 0;
+    let other = poll_fn(|cx| {
+        polls += 1;
+        if polls == 5 {
+            stop.set(true);
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    });
+
+    let mut executor = LocalExecutor::new().budget(4);
+    executor.spawn(hog);
+    executor.spawn(other);
+    executor.run();
+
+    // In the first sweep, both futures used up their budget of 4 polls. The other future went first in the second
+    // sweep and finished, so the hog only got one budget's worth of polls.
+    assert_eq!(hog_polls.get(), 4);
+}
+
+#[test]
+fn local_executor_fair() {
+    // Two futures that immediately wake themselves again, logging each poll
+    let log = std::cell::RefCell::new(Vec::new());
+    let mut executor = LocalExecutor::new().budget(1);
+    for i in 0..2 {
+        let log = &log;
+        executor.spawn(poll_fn(move |cx| {
+            log.borrow_mut().push(i);
+            if log.borrow().len() >= 8 {
+                return Poll::Ready(());
+            }
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }));
+    }
+    executor.run();
+
+    // Neither gets to go first every time
+    assert_eq!(log.borrow()[..8], [0, 1, 1, 0, 0, 1, 1, 0]);
+
+    // `join` alternates in the same way
+    log.borrow_mut().clear();
+    let logged = |i| {
+        let log = &log;
+        poll_fn(move |cx| {
+            log.borrow_mut().push(i);
+            if log.borrow().len() >= 6 {
+                return Poll::Ready(());
+            }
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+    };
+    pollster::block_on(pollster::join(logged(0), logged(1)));
+    assert_eq!(log.borrow()[..6], [0, 1, 1, 0, 0, 1]);
+}
+
+#[test]
+fn join() {
+    let (a, b) = pollster::block_on(pollster::join(
+        async {
+            futures_timer::Delay::new(Duration::from_millis(20)).await;
+            1
+        },
+        async {
+            futures_timer::Delay::new(Duration::from_millis(10)).await;
+            "two"
+        },
+    ));
+    assert_eq!((a, b), (1, "two"));
+}
+
+#[test]
+fn block_on_any() {
+    use pollster::Either;
+
+    let delay = |ms| futures_timer::Delay::new(Duration::from_millis(ms));
+
+    let (output, remaining) = pollster::block_on_any(
+        async {
+            delay(50).await;
+            "slow"
+        },
+        async {
+            delay(10).await;
+            "fast"
+        },
+    );
+    assert_eq!(output, Either::Right("fast"));
+    assert!(matches!(remaining, pollster::Remaining::Left(_)));
+
+    // The remaining future can be raced again, keeping whatever hasn't finished
+    let (output, remaining) = pollster::block_on_any(remaining, delay(500));
+    assert_eq!(output, Either::Left(Either::Left("slow")));
+    assert!(matches!(remaining, pollster::Remaining::Right(_)));
+
+    // Draining a stream of work while holding on to a timer, which is only borrowed so it doesn't need replacing
+    let mut ticks = 0;
+    let mut timer = delay(100);
+    while let (Either::Right(()), _) = pollster::block_on_any(&mut timer, delay(10)) {
+        ticks += 1;
+    }
+    assert!(ticks >= 3, "only {} ticks", ticks);
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "futures")]
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll},
+    thread,
+};
+
+#[test]
+fn futures_waker() {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    let waker = pollster::futures_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut rx = pin!(rx);
+
+    assert!(rx.as_mut().poll(&mut cx).is_pending());
+
+    let handle = thread::spawn(move || tx.send(42).unwrap());
+
+    let result = loop {
+        match rx.as_mut().poll(&mut cx) {
+            Poll::Ready(x) => break x,
+            Poll::Pending => thread::park(),
+        }
+    };
+    assert_eq!(result, Ok(42));
+    handle.join().unwrap();
+}
@@ -0,0 +1,66 @@
+#![cfg(all(windows, feature = "hi-res-timer"))]
+
+use std::{
+    future::poll_fn,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+const TARGET: Duration = Duration::from_millis(5);
+
+// Assert that `wait`, which should take `TARGET`, overshoots by less than 2ms. The median of several runs is used, so
+// that one unlucky reschedule on a busy machine doesn't fail the test.
+fn assert_accurate(mut wait: impl FnMut()) {
+    let mut errors = (0..11)
+        .map(|_| {
+            let start = Instant::now();
+            wait();
+            start.elapsed().saturating_sub(TARGET)
+        })
+        .collect::<Vec<_>>();
+    errors.sort();
+    let median = errors[errors.len() / 2];
+    assert!(median < Duration::from_millis(2), "{:?}", errors);
+}
+
+#[test]
+fn short_sleep_accuracy() {
+    assert_accurate(|| pollster::block_on(pollster::sleep(TARGET)));
+}
+
+#[test]
+fn short_timeout_accuracy() {
+    assert_accurate(|| {
+        let result = pollster::block_on_timeout(std::future::pending::<()>(), TARGET);
+        assert!(result.is_err());
+    });
+}
+
+#[test]
+fn short_builder_timeout_accuracy() {
+    assert_accurate(|| {
+        let result = pollster::BlockOnBuilder::new()
+            .timeout(TARGET)
+            .run(std::future::pending::<()>());
+        assert!(result.is_err());
+    });
+}
+
+#[test]
+fn short_resilient_interval_accuracy() {
+    assert_accurate(|| {
+        // Never woken, so it's only polled again once the interval has passed
+        let mut polls = 0;
+        pollster::block_on_resilient(
+            poll_fn(|_| {
+                polls += 1;
+                if polls > 1 {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            }),
+            TARGET,
+        );
+    });
+}
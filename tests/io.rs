@@ -0,0 +1,835 @@
+#![cfg(feature = "futures-io")]
+
+use std::{
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use futures::{
+    channel::mpsc,
+    io::{AsyncBufRead, AsyncRead, AsyncWrite},
+    StreamExt as _, TryStreamExt as _,
+};
+use pollster::io::{BlockingBufReader, BlockingReader, BlockingWriter};
+
+// An in-memory pipe, where each write shows up as a separate chunk on the reading side
+fn pipe() -> (mpsc::UnboundedSender<Vec<u8>>, impl AsyncBufRead + Unpin) {
+    let (tx, rx) = mpsc::unbounded();
+    (tx, rx.map(Ok).into_async_read())
+}
+
+#[test]
+fn partial_reads() {
+    let (tx, rx) = pipe();
+    let mut reader = BlockingReader::new(rx);
+
+    let writer = thread::spawn(move || {
+        for chunk in [&b"hello"[..], b", ", b"world"] {
+            thread::sleep(Duration::from_millis(10));
+            tx.unbounded_send(chunk.to_vec()).expect("send chunk");
+        }
+    });
+
+    // Each read parks until the next chunk arrives, and returns no more than one chunk
+    let mut buf = [0; 3];
+    assert_eq!(reader.read(&mut buf).expect("read"), 3);
+    assert_eq!(&buf, b"hel");
+    assert_eq!(reader.read(&mut buf).expect("read"), 2);
+    assert_eq!(&buf[..2], b"lo");
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).expect("read to end");
+    assert_eq!(rest, ", world");
+
+    writer.join().expect("join writer");
+}
+
+#[test]
+fn empty_buffer() {
+    let (_tx, rx) = pipe();
+    let mut reader = BlockingReader::new(rx);
+
+    // Nothing will ever be written, but an empty read shouldn't wait for it
+    assert_eq!(reader.read(&mut []).expect("read"), 0);
+}
+
+#[test]
+fn eof() {
+    let (tx, rx) = pipe();
+    let mut reader = BlockingReader::new(rx);
+
+    tx.unbounded_send(b"bye".to_vec()).expect("send chunk");
+    drop(tx);
+
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 3);
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+
+    // The reader can be recovered afterwards
+    let _rx = reader.into_inner();
+}
+
+#[test]
+fn lines() {
+    let (tx, rx) = pipe();
+    let reader = BlockingBufReader::new(rx);
+
+    // Lines are split across chunks, and chunks contain several lines
+    let writer = thread::spawn(move || {
+        for chunk in [&b"fir"[..], b"st\r", b"\nsecond\nth", b"ird\r\n", b"\nlast"] {
+            thread::sleep(Duration::from_millis(10));
+            tx.unbounded_send(chunk.to_vec()).expect("send chunk");
+        }
+    });
+
+    let lines = reader
+        .lines()
+        .collect::<io::Result<Vec<_>>>()
+        .expect("read lines");
+    assert_eq!(lines, ["first", "second", "third", "", "last"]);
+
+    writer.join().expect("join writer");
+}
+
+#[test]
+fn fill_buf() {
+    let (tx, rx) = pipe();
+    let mut reader = BlockingBufReader::new(rx);
+
+    tx.unbounded_send(b"hello".to_vec()).expect("send chunk");
+    drop(tx);
+
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"hello");
+    reader.consume(2);
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"llo");
+
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 3);
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"");
+}
+
+// A writer that accepts at most 3 bytes at a time, and is only ready every other poll
+#[derive(Default)]
+struct Throttled {
+    written: Vec<u8>,
+    ready: bool,
+    polls: usize,
+    closed: bool,
+}
+
+impl Throttled {
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.polls += 1;
+        if std::mem::take(&mut self.ready) {
+            Poll::Ready(())
+        } else {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+impl AsyncWrite for Throttled {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        self.poll_ready(cx).map(|()| {
+            let n = buf.len().min(3);
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        })
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_ready(cx).map(Ok)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_ready(cx).map(|()| {
+            self.closed = true;
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn short_writes() {
+    let mut writer = BlockingWriter::new(Throttled::default());
+
+    // Short writes are returned as-is
+    assert_eq!(writer.write(b"hello").expect("write"), 3);
+    assert_eq!(writer.write(&[]).expect("write"), 0);
+    assert_eq!(writer.get_ref().written, b"hel");
+
+    // ...so `write_all` loops over them
+    writer.write_all(b"lo, world").expect("write all");
+    writer.flush().expect("flush");
+    assert_eq!(writer.get_ref().written, b"hello, world");
+    assert!(writer.get_ref().polls >= 10);
+
+    writer.close().expect("close");
+    assert_eq!(
+        writer.write(b"!").expect_err("closed").kind(),
+        io::ErrorKind::BrokenPipe
+    );
+    assert_eq!(writer.into_inner().written, b"hello, world");
+}
+
+#[test]
+fn seek() {
+    let mut reader = BlockingReader::new(futures::io::Cursor::new(b"hello, world"));
+
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).expect("read");
+    assert_eq!(reader.stream_position().expect("position"), 5);
+
+    // Seek backwards and read the same bytes again
+    assert_eq!(reader.seek(SeekFrom::Current(-3)).expect("seek"), 2);
+    reader.read_exact(&mut buf).expect("read");
+    assert_eq!(&buf, b"llo, ");
+    assert_eq!(reader.seek(SeekFrom::End(-5)).expect("seek"), 7);
+    reader.read_exact(&mut buf).expect("read");
+    assert_eq!(&buf, b"world");
+
+    let mut writer = BlockingWriter::new(futures::io::Cursor::new(Vec::new()));
+    writer.write_all(b"hello, world").expect("write");
+    writer.seek(SeekFrom::Start(7)).expect("seek");
+    writer.write_all(b"there").expect("write");
+    assert_eq!(writer.into_inner().into_inner(), b"hello, there");
+}
+
+#[test]
+fn seek_buffered() {
+    let inner = futures::io::BufReader::with_capacity(4, futures::io::Cursor::new(b"one\ntwo\n"));
+    let mut reader = BlockingBufReader::new(inner);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).expect("read line");
+    assert_eq!(line, "one\n");
+    assert_eq!(reader.fill_buf().expect("fill buf"), b"two\n");
+
+    // The buffered data is thrown away, so the next read starts from the new position
+    reader.seek(SeekFrom::Start(0)).expect("seek");
+    assert_eq!(reader.stream_position().expect("position"), 0);
+    let lines = reader
+        .lines()
+        .collect::<io::Result<Vec<_>>>()
+        .expect("read lines");
+    assert_eq!(lines, ["one", "two"]);
+}
+
+// Limits each read or write of the inner type to at most `max` bytes, and makes it pending every other poll
+struct Chunked<T> {
+    inner: T,
+    max: usize,
+    ready: bool,
+}
+
+impl<T> Chunked<T> {
+    fn new(inner: T, max: usize) -> Self {
+        Self {
+            inner,
+            max,
+            ready: false,
+        }
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if std::mem::take(&mut self.ready) {
+            Poll::Ready(())
+        } else {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Chunked<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        futures::ready!(self.poll_ready(cx));
+        let n = buf.len().min(self.max);
+        Pin::new(&mut self.inner).poll_read(cx, &mut buf[..n])
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Chunked<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        futures::ready!(self.poll_ready(cx));
+        let n = buf.len().min(self.max);
+        Pin::new(&mut self.inner).poll_write(cx, &buf[..n])
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[test]
+fn copy() {
+    let data = (0..4 * 1024 * 1024)
+        .map(|i| (i * 7) as u8)
+        .collect::<Vec<_>>();
+
+    // Async to sync
+    let reader = Chunked::new(futures::io::Cursor::new(data.clone()), 1000);
+    let mut writer = Vec::new();
+    let copied = pollster::io::copy(reader, &mut writer).expect("copy");
+    assert_eq!(copied, data.len() as u64);
+    assert_eq!(writer, data);
+
+    // Async to async, with short writes as well as short reads
+    let reader = Chunked::new(futures::io::Cursor::new(data.clone()), 1000);
+    let mut writer = Chunked::new(futures::io::Cursor::new(Vec::new()), 777);
+    let copied = pollster::io::CopyBuilder::new()
+        .buffer_size(4096)
+        .copy_async(reader, &mut writer)
+        .expect("copy");
+    assert_eq!(copied, data.len() as u64);
+    assert_eq!(writer.inner.into_inner(), data);
+
+    // An empty source
+    let mut writer = Vec::new();
+    let copied = pollster::io::copy(futures::io::empty(), &mut writer).expect("copy");
+    assert_eq!(copied, 0);
+    assert!(writer.is_empty());
+}
+
+#[test]
+fn copy_error() {
+    // A source that fails after 10 bytes
+    let reader = futures::io::AsyncReadExt::chain(futures::io::Cursor::new([0; 10]), ResetReader);
+    let mut writer = Vec::new();
+    let err = pollster::io::copy(reader, &mut writer).expect_err("copy fails");
+    assert_eq!(err.copied(), 10);
+    assert_eq!(err.error().kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(writer.len(), 10);
+
+    // A destination with no room
+    let mut buf = [0; 5];
+    let err = pollster::io::copy(futures::io::Cursor::new([1; 8]), &mut &mut buf[..])
+        .expect_err("copy fails");
+    assert_eq!(err.copied(), 5);
+    assert_eq!(io::Error::from(err).kind(), io::ErrorKind::WriteZero);
+}
+
+struct ResetReader;
+
+impl AsyncRead for ResetReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()))
+    }
+}
+
+#[test]
+fn read_to_end() {
+    use pollster::io::AsyncReadExt as _;
+
+    let data = (0..100_000).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+
+    // One byte per wake
+    let mut reader = Chunked::new(futures::io::Cursor::new(data.clone()), 1);
+    let mut buf = b"prefix".to_vec();
+    assert_eq!(
+        reader.blocking_read_to_end(&mut buf).expect("read"),
+        data.len()
+    );
+    assert_eq!(&buf[..6], b"prefix");
+    assert_eq!(&buf[6..], data);
+
+    // Reserved capacity is used before growing the buffer
+    let mut reader = futures::io::Cursor::new(data.clone());
+    let mut buf = Vec::with_capacity(data.len());
+    reader.blocking_read_to_end(&mut buf).expect("read");
+    assert_eq!(buf, data);
+    assert_eq!(buf.capacity(), data.len());
+
+    // An error halfway keeps what was read
+    let mut reader = futures::io::AsyncReadExt::chain(
+        Chunked::new(futures::io::Cursor::new([1; 10]), 3),
+        ResetReader,
+    );
+    let mut buf = Vec::new();
+    let err = reader
+        .blocking_read_to_end(&mut buf)
+        .expect_err("read fails");
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(buf, [1; 10]);
+}
+
+#[test]
+fn read_to_string() {
+    use pollster::io::AsyncReadExt as _;
+
+    let mut reader = Chunked::new(futures::io::Cursor::new("héllo wörld"), 1);
+    let mut buf = String::new();
+    assert_eq!(reader.blocking_read_to_string(&mut buf).expect("read"), 13);
+    assert_eq!(buf, "héllo wörld");
+
+    // Invalid UTF-8 leaves the string unchanged, but the bytes can be recovered from the error
+    let mut reader = futures::io::Cursor::new(b"ok \xff");
+    let mut buf = "unchanged".to_string();
+    let err = reader
+        .blocking_read_to_string(&mut buf)
+        .expect_err("read fails");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(buf, "unchanged");
+    let bytes = err
+        .into_inner()
+        .and_then(|err| err.downcast::<std::string::FromUtf8Error>().ok())
+        .expect("utf-8 error")
+        .into_bytes();
+    assert_eq!(bytes, b"ok \xff");
+
+    // An error halfway keeps what was read, as long as it's valid
+    let mut reader = futures::io::AsyncReadExt::chain(futures::io::Cursor::new("hi"), ResetReader);
+    let mut buf = String::new();
+    let err = reader
+        .blocking_read_to_string(&mut buf)
+        .expect_err("read fails");
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(buf, "hi");
+}
+
+#[test]
+fn read_exact() {
+    use pollster::io::AsyncReadExt as _;
+
+    let mut reader = Chunked::new(futures::io::Cursor::new(b"hello, world"), 1);
+    let mut buf = [0; 5];
+    reader.blocking_read_exact(&mut buf).expect("read");
+    assert_eq!(&buf, b"hello");
+    reader.blocking_read_exact(&mut buf).expect("read");
+    assert_eq!(&buf, b", wor");
+
+    let err = reader
+        .blocking_read_exact(&mut buf)
+        .expect_err("read fails");
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    let mut reader =
+        futures::io::AsyncReadExt::chain(futures::io::Cursor::new([1; 2]), ResetReader);
+    let err = reader
+        .blocking_read_exact(&mut buf)
+        .expect_err("read fails");
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+}
+
+#[test]
+fn read_timeout() {
+    use std::time::Instant;
+
+    // A reader that never wakes
+    let (tx, rx) = pipe();
+    let mut reader = BlockingReader::new(rx).with_timeout(Duration::from_millis(50));
+    let then = Instant::now();
+    let err = reader.read(&mut [0; 8]).expect_err("read times out");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(then.elapsed() >= Duration::from_millis(50));
+
+    // A reader that wakes just after the deadline still times out, but the next read picks up the data
+    let sender = thread::spawn({
+        let tx = tx.clone();
+        move || {
+            thread::sleep(Duration::from_millis(100));
+            tx.unbounded_send(b"late".to_vec()).expect("send chunk");
+        }
+    });
+    let err = reader.read(&mut [0; 8]).expect_err("read times out");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    sender.join().expect("join sender");
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 4);
+    assert_eq!(&buf[..4], b"late");
+
+    // The timeout can be cleared
+    reader.set_timeout(None);
+    assert_eq!(reader.timeout(), None);
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        tx.unbounded_send(b"slow".to_vec()).expect("send chunk");
+    });
+    assert_eq!(reader.read(&mut buf).expect("read"), 4);
+    sender.join().expect("join sender");
+}
+
+#[test]
+fn fill_buf_timeout() {
+    let (tx, rx) = pipe();
+    let mut reader = BlockingBufReader::new(rx).with_timeout(Duration::from_millis(50));
+
+    let err = reader.fill_buf().expect_err("fill times out");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    tx.unbounded_send(b"hello".to_vec()).expect("send chunk");
+    assert_eq!(reader.fill_buf().expect("fill"), b"hello");
+}
+
+// Records the lengths of the buffers passed to each read or write
+#[derive(Default)]
+struct Vectored {
+    written: Vec<u8>,
+    layouts: Vec<Vec<usize>>,
+}
+
+impl AsyncRead for Vectored {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.layouts.push(vec![buf.len()]);
+        buf.fill(1);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.layouts
+            .push(bufs.iter().map(|buf| buf.len()).collect());
+        bufs.iter_mut().for_each(|buf| buf.fill(1));
+        Poll::Ready(Ok(bufs.iter().map(|buf| buf.len()).sum()))
+    }
+}
+
+impl AsyncWrite for Vectored {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.layouts.push(vec![buf.len()]);
+        self.written.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.layouts
+            .push(bufs.iter().map(|buf| buf.len()).collect());
+        bufs.iter()
+            .for_each(|buf| self.written.extend_from_slice(buf));
+        Poll::Ready(Ok(bufs.iter().map(|buf| buf.len()).sum()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn vectored() {
+    use std::io::{IoSlice, IoSliceMut};
+
+    // The inner writer receives the buffers as they were passed in
+    let mut writer = BlockingWriter::new(Vectored::default());
+    let bufs = [IoSlice::new(b"ab"), IoSlice::new(b""), IoSlice::new(b"cde")];
+    assert_eq!(writer.write_vectored(&bufs).expect("write"), 5);
+    let writer = writer.into_inner();
+    assert_eq!(writer.layouts, [vec![2, 0, 3]]);
+    assert_eq!(writer.written, b"abcde");
+
+    let mut reader = BlockingReader::new(Vectored::default());
+    let (mut a, mut b) = ([0; 2], [0; 3]);
+    let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+    assert_eq!(reader.read_vectored(&mut bufs).expect("read"), 5);
+    assert_eq!(reader.get_ref().layouts, [vec![2, 3]]);
+    assert_eq!((a, b), ([1; 2], [1; 3]));
+
+    // Inner types without vectored support only see the first non-empty buffer, as with the async traits
+    let mut writer = BlockingWriter::new(Chunked::new(futures::io::Cursor::new(Vec::new()), 100));
+    let bufs = [IoSlice::new(b""), IoSlice::new(b"ab"), IoSlice::new(b"cde")];
+    assert_eq!(writer.write_vectored(&bufs).expect("write"), 2);
+    assert_eq!(writer.into_inner().inner.into_inner(), b"ab");
+}
+
+// A stream that reads back whatever is written to it, and reaches EOF once closed
+#[derive(Debug, Default)]
+struct Loopback {
+    buf: std::collections::VecDeque<u8>,
+    closed: bool,
+    reader: Option<std::task::Waker>,
+}
+
+impl AsyncRead for Loopback {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.buf.is_empty() && !self.closed {
+            self.reader = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(self.buf.len());
+        for (dst, src) in buf.iter_mut().zip(self.buf.drain(..n)) {
+            *dst = src;
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Loopback {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.buf.extend(buf);
+        if let Some(waker) = self.reader.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.closed = true;
+        if let Some(waker) = self.reader.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn split() {
+    let (mut reader, mut writer) = pollster::io::split(Loopback::default());
+
+    // The reader blocks waiting for data, which the writer can still send while it does
+    let reader = thread::spawn(move || {
+        let mut echoed = Vec::new();
+        reader.read_to_end(&mut echoed).expect("read");
+        (reader, echoed)
+    });
+    let writer = thread::spawn(move || {
+        for chunk in [&b"hello"[..], b", ", b"world"] {
+            thread::sleep(Duration::from_millis(10));
+            writer.write_all(chunk).expect("write");
+        }
+        writer.close().expect("close");
+        writer
+    });
+
+    let (reader, echoed) = reader.join().expect("join reader");
+    let writer = writer.join().expect("join writer");
+    assert_eq!(echoed, b"hello, world");
+
+    let stream = reader.reunite(writer).expect("reunite");
+    assert!(stream.closed);
+
+    // Halves of different streams can't be reunited
+    let (reader, _) = pollster::io::split(Loopback::default());
+    let (_, writer) = pollster::io::split(Loopback::default());
+    let (reader, writer) = reader
+        .reunite(writer)
+        .expect_err("reunite fails")
+        .into_inner();
+    drop((reader, writer));
+}
+
+#[test]
+fn nonblocking() {
+    let (tx_a, rx_a) = pipe();
+    let (tx_b, rx_b) = pipe();
+    let mut readers = [BlockingReader::new(rx_a), BlockingReader::new(rx_b)];
+    readers
+        .iter_mut()
+        .for_each(|reader| reader.set_nonblocking(true));
+    assert!(readers
+        .iter()
+        .all(|reader| reader.is_nonblocking() && reader.ready()));
+
+    let sender = thread::spawn(move || {
+        for (tx, chunk) in [(&tx_a, &b"a1"[..]), (&tx_b, b"b1"), (&tx_a, b"a2")] {
+            thread::sleep(Duration::from_millis(10));
+            tx.unbounded_send(chunk.to_vec()).expect("send chunk");
+        }
+    });
+
+    // A select-style loop: try every reader that might be ready, and park until one is woken when none are
+    let mut received = [Vec::new(), Vec::new()];
+    let (mut would_block, mut eof) = (0, [false; 2]);
+    while !eof.iter().all(|&eof| eof) {
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if eof[i] || !reader.ready() {
+                continue;
+            }
+            let mut buf = [0; 8];
+            match reader.read(&mut buf) {
+                Ok(0) => eof[i] = true,
+                Ok(n) => received[i].extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => would_block += 1,
+                Err(err) => panic!("read failed: {}", err),
+            }
+        }
+        let idle = readers
+            .iter()
+            .zip(&eof)
+            .all(|(reader, &eof)| eof || !reader.ready());
+        if idle && !eof.iter().all(|&eof| eof) {
+            thread::park();
+        }
+    }
+    assert_eq!(received, [b"a1a2".to_vec(), b"b1".to_vec()]);
+    assert!(would_block > 0);
+    sender.join().expect("join sender");
+
+    // A reader that is never woken times out waiting
+    let (_tx, rx) = pipe();
+    let mut reader = BlockingReader::new(rx);
+    reader.set_nonblocking(true);
+    let err = reader.read(&mut [0; 8]).expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    let err = reader
+        .read_vectored(&mut [io::IoSliceMut::new(&mut [0; 8])])
+        .expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(!reader.wait_readable(Some(Duration::from_millis(20))));
+
+    // Writers work the same way
+    let mut writer = BlockingWriter::new(Throttled::default());
+    writer.set_nonblocking(true);
+    let err = writer.write(b"hi").expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(writer.wait_writable(None));
+    assert_eq!(writer.write(b"hi").expect("write"), 2);
+}
+
+// The receiving end of a connection, recording what has been sent to it
+#[derive(Clone, Default)]
+struct Peer(std::sync::Arc<std::sync::Mutex<(Vec<u8>, usize)>>);
+
+impl Peer {
+    fn received(&self) -> Vec<u8> {
+        self.0.lock().unwrap().0.clone()
+    }
+
+    fn closes(&self) -> usize {
+        self.0.lock().unwrap().1
+    }
+}
+
+impl AsyncWrite for Peer {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.0.lock().unwrap().0.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.lock().unwrap().1 += 1;
+        Poll::Ready(Ok(()))
+    }
+}
+
+// A writer that never finishes flushing, or that panics when closed
+struct Stalled {
+    panic: bool,
+}
+
+impl AsyncWrite for Stalled {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.panic {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        panic!("close failed")
+    }
+}
+
+#[test]
+fn drop_closes() {
+    // Buffered data is flushed through to the peer, which is then closed
+    let peer = Peer::default();
+    let mut writer = BlockingWriter::new(futures::io::BufWriter::new(peer.clone()));
+    writer.write_all(b"hello").expect("write all");
+    assert_eq!(peer.received(), b"");
+    drop(writer);
+    assert_eq!((peer.received(), peer.closes()), (b"hello".to_vec(), 1));
+
+    // An explicit close isn't repeated on drop
+    let peer = Peer::default();
+    let mut writer = BlockingWriter::new(peer.clone());
+    writer.close().expect("close");
+    drop(writer);
+    assert_eq!(peer.closes(), 1);
+
+    // ...and neither `forget_close` nor `into_inner` close the writer at all
+    let peer = Peer::default();
+    let mut writer = BlockingWriter::new(futures::io::BufWriter::new(peer.clone()));
+    writer.write_all(b"hello").expect("write all");
+    writer.forget_close();
+    drop(writer);
+    drop(BlockingWriter::new(peer.clone()).into_inner());
+    assert_eq!((peer.received(), peer.closes()), (Vec::new(), 0));
+
+    // A writer that never finishes is given up on after the timeout
+    let start = std::time::Instant::now();
+    drop(
+        BlockingWriter::new(Stalled { panic: false }).with_drop_timeout(Duration::from_millis(20)),
+    );
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(20) && elapsed < Duration::from_secs(1));
+
+    // A panic while closing doesn't escape `drop`
+    drop(BlockingWriter::new(Stalled { panic: true }));
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+
+// A recorder that keeps what it's given
+#[derive(Default)]
+struct Recorded {
+    calls: AtomicU64,
+    parks: AtomicU64,
+    durations: Mutex<Vec<f64>>,
+}
+
+struct Handle(Arc<Recorded>, &'static str);
+
+impl CounterFn for Handle {
+    fn increment(&self, value: u64) {
+        match self.1 {
+            "calls" => self.0.calls.fetch_add(value, Ordering::Relaxed),
+            _ => self.0.parks.fetch_add(value, Ordering::Relaxed),
+        };
+    }
+
+    fn absolute(&self, _: u64) {
+        unimplemented!()
+    }
+}
+
+impl HistogramFn for Handle {
+    fn record(&self, value: f64) {
+        self.0.durations.lock().unwrap().push(value);
+    }
+}
+
+struct Record(Arc<Recorded>);
+
+impl Recorder for Record {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let name = match key.name() {
+            "pollster.block_on.calls" => "calls",
+            "pollster.block_on.parks" => "parks",
+            name => panic!("unexpected counter {}", name),
+        };
+        Counter::from_arc(Arc::new(Handle(Arc::clone(&self.0), name)))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        panic!("unexpected gauge {}", key.name())
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        assert_eq!(key.name(), "pollster.block_on.duration");
+        Histogram::from_arc(Arc::new(Handle(Arc::clone(&self.0), "duration")))
+    }
+}
+
+#[test]
+fn block_on_metrics() {
+    let recorded = Arc::new(Recorded::default());
+    let recorder = Record(Arc::clone(&recorded));
+
+    metrics::with_local_recorder(&recorder, || {
+        pollster::block_on(async {});
+
+        let (tx, rx) = pollster::oneshot::channel();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            tx.send(()).unwrap();
+        });
+        pollster::block_on(rx).unwrap();
+        sender.join().unwrap();
+    });
+
+    assert_eq!(recorded.calls.load(Ordering::Relaxed), 2);
+    // The ready future never parks, but the other one parks at least once
+    assert!(recorded.parks.load(Ordering::Relaxed) >= 1);
+    let durations = recorded.durations.lock().unwrap();
+    assert_eq!(durations.len(), 2);
+    assert!(durations[1] >= 0.05);
+}
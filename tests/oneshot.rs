@@ -0,0 +1,63 @@
+use std::{thread, time::Duration};
+
+use pollster::oneshot::{self, RecvError, RecvTimeoutError};
+
+#[test]
+fn send_from_thread() {
+    let (tx, rx) = oneshot::channel();
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        tx.send("hello").unwrap();
+    });
+    assert_eq!(rx.recv(), Ok("hello"));
+    sender.join().unwrap();
+}
+
+#[test]
+fn sender_dropped_while_parked() {
+    let (tx, rx) = oneshot::channel::<i32>();
+    let dropper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(tx);
+    });
+    assert_eq!(rx.recv(), Err(RecvError));
+    dropper.join().unwrap();
+}
+
+#[test]
+fn receiver_dropped() {
+    let (tx, rx) = oneshot::channel();
+    assert!(!tx.is_closed());
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1), Err(1));
+}
+
+#[test]
+fn recv_timeout() {
+    let (tx, mut rx) = oneshot::channel();
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+
+    // The receiver can still be used after timing out
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv_timeout(Duration::ZERO), Ok(1));
+
+    let (tx, mut rx) = oneshot::channel::<i32>();
+    drop(tx);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_secs(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn await_receiver() {
+    let (tx, rx) = oneshot::channel();
+    let sender = thread::spawn(move || tx.send(42).unwrap());
+    let value = pollster::block_on(async { rx.await.unwrap() + 1 });
+    assert_eq!(value, 43);
+    sender.join().unwrap();
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "testing")]
+
+// Each test runs on its own thread, so pausing time in one doesn't affect the others.
+
+use std::{
+    future::pending,
+    thread,
+    time::{Duration, Instant},
+};
+
+use pollster::time;
+
+// How long a test that waits in virtual time may take in real time
+const REAL_LIMIT: Duration = Duration::from_millis(500);
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+#[test]
+fn ten_minute_timeout() {
+    time::pause();
+    let start = Instant::now();
+    let result = pollster::block_on(pollster::timeout(MINUTE * 10, pending::<()>()));
+    assert_eq!(result.map_err(|err| err.elapsed()), Err(MINUTE * 10));
+    assert!(start.elapsed() < REAL_LIMIT, "{:?}", start.elapsed());
+}
+
+#[test]
+fn deadlines_in_order() {
+    time::pause();
+    let start = Instant::now();
+    // Each deadline is reached in turn, so the timeout around the shorter sleep succeeds
+    let (timed, ()) = pollster::block_on(pollster::join(
+        pollster::timeout(MINUTE * 10, pollster::sleep(MINUTE * 5)),
+        pollster::sleep(MINUTE * 20),
+    ));
+    assert_eq!(timed, Ok(()));
+
+    let mut interval = pollster::interval(MINUTE * 60);
+    let first = interval.blocking_tick();
+    for i in 1..=3 {
+        assert_eq!(interval.blocking_tick(), first + MINUTE * 60 * i);
+    }
+    assert!(start.elapsed() < REAL_LIMIT, "{:?}", start.elapsed());
+}
+
+#[test]
+fn advance() {
+    time::pause();
+    let mut sleep = core::pin::pin!(pollster::sleep(MINUTE));
+    assert!(pollster::try_poll_once(sleep.as_mut()).is_pending());
+    time::advance(MINUTE / 2);
+    assert!(pollster::try_poll_once(sleep.as_mut()).is_pending());
+    time::advance(MINUTE / 2);
+    assert!(pollster::try_poll_once(sleep.as_mut()).is_ready());
+}
+
+#[test]
+fn external_wakes() {
+    time::pause();
+    let start = Instant::now();
+    let (tx, rx) = pollster::oneshot::channel();
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        tx.send(42).unwrap();
+    });
+    // Once the sleep is done, the thread parks until the real wake arrives
+    let (received, ()) = pollster::block_on(pollster::join(rx, pollster::sleep(MINUTE * 10)));
+    sender.join().unwrap();
+    assert_eq!(received, Ok(42));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert!(start.elapsed() < REAL_LIMIT, "{:?}", start.elapsed());
+}
+
+#[test]
+fn resume() {
+    time::pause();
+    time::advance(MINUTE * 60);
+    time::resume();
+
+    // Time passes at the usual rate again
+    let start = Instant::now();
+    pollster::block_on(pollster::sleep(Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+#[should_panic(expected = "time isn't paused")]
+fn advance_without_pause() {
+    time::advance(MINUTE);
+}
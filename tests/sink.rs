@@ -0,0 +1,145 @@
+#![cfg(feature = "sink")]
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use futures::{channel::mpsc, StreamExt as _};
+use pollster::SinkExt as _;
+
+#[test]
+fn backpressure() {
+    // A channel with room for just one buffered item
+    let (mut tx, mut rx) = mpsc::channel(1);
+
+    tx.blocking_send(0).expect("send");
+
+    let consumer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        let mut items = Vec::new();
+        while let Some(item) = pollster::block_on(rx.next()) {
+            items.push(item);
+        }
+        items
+    });
+
+    // The channel is full, so this parks until the consumer drains it
+    let then = Instant::now();
+    tx.blocking_send(1).expect("send");
+    assert!(then.elapsed() >= Duration::from_millis(50));
+
+    for i in 2..10 {
+        tx.blocking_send(i).expect("send");
+    }
+    tx.blocking_flush().expect("flush");
+    tx.blocking_close().expect("close");
+
+    assert_eq!(consumer.join().expect("join consumer"), (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn receiver_dropped_mid_send() {
+    let (mut tx, rx) = mpsc::channel(1);
+
+    tx.blocking_send(0).expect("send");
+
+    let consumer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(rx);
+    });
+
+    // This parks waiting for the consumer to make room, and the receiver goes away in the meantime. The item was
+    // already handed to the channel, so the error surfaces on the next send.
+    let then = Instant::now();
+    let _ = tx.blocking_send(1);
+    assert!(then.elapsed() >= Duration::from_millis(25));
+    assert!(tx.blocking_send(2).expect_err("receiver dropped").is_disconnected());
+
+    consumer.join().expect("join consumer");
+}
+
+#[test]
+fn send_all() {
+    const ITEMS: usize = 1_000_000;
+
+    let (tx, rx) = mpsc::channel(16);
+
+    let consumer =
+        thread::spawn(move || pollster::block_on(rx.fold(0, |n, _| async move { n + 1 })));
+
+    let then = Instant::now();
+    pollster::sink::blocking_send_all(tx, 0..ITEMS).expect("send all");
+    let received = consumer.join().expect("join consumer");
+    println!("Sent {} items in {:?}", ITEMS, then.elapsed());
+
+    assert_eq!(received, ITEMS);
+}
+
+#[test]
+fn send_all_receiver_dropped() {
+    let (tx, rx) = mpsc::channel(1);
+
+    let consumer = thread::spawn(move || {
+        let mut rx = rx.take(5);
+        while pollster::block_on(rx.next()).is_some() {}
+    });
+
+    let error = pollster::sink::blocking_send_all(tx, 0..).expect_err("receiver dropped");
+    consumer.join().expect("join consumer");
+
+    assert!(error.error().is_disconnected());
+    // Everything the consumer took was accepted, plus at most the channel's buffer
+    assert!(error.sent() >= 5);
+}
+
+#[test]
+fn sink_writer_frames() {
+    use pollster::io::SinkWriter;
+    use std::io::Write;
+
+    // Without coalescing, every write is a frame
+    let (tx, rx) = mpsc::unbounded();
+    let mut writer = SinkWriter::new(tx, <[u8]>::to_vec);
+    writer.write_all(b"hello").expect("write");
+    assert_eq!(writer.write(&[]).expect("write"), 0);
+    writer.write_all(b", world").expect("write");
+    writer.flush().expect("flush");
+    drop(writer);
+    let frames = pollster::block_on(rx.collect::<Vec<_>>());
+    assert_eq!(frames, [&b"hello"[..], b", world"]);
+
+    // With coalescing, small writes are gathered until the buffer would overflow, and large writes go out alone
+    let (tx, rx) = mpsc::unbounded();
+    let mut writer = SinkWriter::new(tx, <[u8]>::to_vec).coalesce(8);
+    for chunk in [&b"ab"[..], b"cd", b"efg", b"hi", b"0123456789", b"j"] {
+        writer.write_all(chunk).expect("write");
+    }
+    assert_eq!(writer.buffer(), b"j");
+    writer.flush().expect("flush");
+    assert_eq!(writer.buffer(), b"");
+    drop(writer);
+    let frames = pollster::block_on(rx.collect::<Vec<_>>());
+    assert_eq!(frames, [&b"abcdefg"[..], b"hi", b"0123456789", b"j"]);
+}
+
+#[test]
+fn sink_writer_closed() {
+    use pollster::io::SinkWriter;
+    use std::io::{ErrorKind, Write};
+
+    let (tx, rx) = mpsc::channel::<Vec<u8>>(1);
+    let mut writer = SinkWriter::new(tx, <[u8]>::to_vec).coalesce(8);
+    writer.write_all(b"hi").expect("write");
+    drop(rx);
+
+    // The buffered bytes are kept when the sink can no longer accept them
+    let err = writer.flush().expect_err("receiver dropped");
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert!(err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<mpsc::SendError>())
+        .map_or(false, mpsc::SendError::is_disconnected));
+    assert_eq!(writer.buffer(), b"hi");
+    assert!(writer.write(b"0123456789").is_err());
+}
@@ -0,0 +1,235 @@
+use std::{
+    future::{pending, poll_fn, Future},
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+// Generous, so that a loaded machine doesn't fail the tests
+const TOLERANCE: Duration = Duration::from_millis(150);
+
+fn assert_took(start: Instant, expected: Duration) {
+    assert_near(start.elapsed(), expected);
+}
+
+fn assert_near(elapsed: Duration, expected: Duration) {
+    assert!(elapsed >= expected, "{:?} < {:?}", elapsed, expected);
+    assert!(
+        elapsed < expected + TOLERANCE,
+        "{:?} >= {:?}",
+        elapsed,
+        expected + TOLERANCE
+    );
+}
+
+#[test]
+fn sleep() {
+    let start = Instant::now();
+    pollster::block_on(pollster::sleep(Duration::from_millis(50)));
+    assert_took(start, Duration::from_millis(50));
+
+    // Consecutive sleeps inside an `async` block
+    let start = Instant::now();
+    pollster::block_on(async {
+        pollster::sleep(Duration::from_millis(30)).await;
+        pollster::sleep(Duration::from_millis(30)).await;
+    });
+    assert_took(start, Duration::from_millis(60));
+}
+
+#[test]
+fn sleep_until() {
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(50);
+    let sleep = pollster::sleep_until(deadline);
+    assert_eq!(sleep.deadline(), Some(deadline));
+    pollster::block_on(sleep);
+    assert_took(start, Duration::from_millis(50));
+}
+
+#[test]
+fn earliest_deadline() {
+    // Two sleeps polled together wake the thread for the earlier of the two
+    let start = Instant::now();
+    let mut long = Box::pin(pollster::sleep(Duration::from_secs(10)));
+    let mut short = Box::pin(pollster::sleep(Duration::from_millis(50)));
+    pollster::block_on(poll_fn(|cx| {
+        let _ = long.as_mut().poll(cx);
+        short.as_mut().poll(cx)
+    }));
+    assert_took(start, Duration::from_millis(50));
+}
+
+#[test]
+fn zero_duration() {
+    // Ready on the first poll, so the thread never parks
+    let ((), parked) = pollster::block_on_timed(pollster::sleep(Duration::ZERO));
+    assert_eq!(parked, Duration::ZERO);
+
+    let ((), parked) = pollster::block_on_timed(pollster::sleep_until(
+        Instant::now() - Duration::from_secs(1),
+    ));
+    assert_eq!(parked, Duration::ZERO);
+}
+
+#[test]
+fn foreign_executor() {
+    // Without `block_on` to wait out the deadline, the timer thread wakes the task instead
+    let start = Instant::now();
+    futures::executor::block_on(pollster::sleep(Duration::from_millis(50)));
+    assert_took(start, Duration::from_millis(50));
+
+    let start = Instant::now();
+    let mut executor = pollster::LocalExecutor::new();
+    for i in 1..=4 {
+        executor.spawn(pollster::sleep(Duration::from_millis(i * 20)));
+    }
+    executor.run();
+    assert_took(start, Duration::from_millis(80));
+}
+
+#[test]
+fn nested_block_on() {
+    // A `block_on` inside another's poll doesn't lose the outer one's deadline
+    let start = Instant::now();
+    let mut outer = Box::pin(pollster::sleep(Duration::from_millis(50)));
+    pollster::block_on(poll_fn(|cx| {
+        let poll = outer.as_mut().poll(cx);
+        pollster::block_on(pollster::sleep(Duration::from_millis(10)));
+        poll
+    }));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert!(start.elapsed() < Duration::from_millis(50) + TOLERANCE);
+}
+
+#[test]
+fn deadline_kept_until_passed() {
+    // Once a sleep has been polled, its deadline wakes the thread even if it isn't polled again
+    let start = Instant::now();
+    let mut sleep = Box::pin(pollster::sleep(Duration::from_millis(50)));
+    let mut polled = false;
+    pollster::block_on(poll_fn(|cx| {
+        if !polled {
+            polled = true;
+            let _ = sleep.as_mut().poll(cx);
+        }
+        if Instant::now() >= sleep.deadline().unwrap() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }));
+    assert_took(start, Duration::from_millis(50));
+}
+
+// Resolves to the output of `fut`, and how long after `start` it was ready
+async fn finished_at<F: Future>(start: Instant, fut: F) -> (F::Output, Duration) {
+    let output = fut.await;
+    (output, start.elapsed())
+}
+
+#[test]
+fn timeout() {
+    let ms = Duration::from_millis;
+    let start = Instant::now();
+    let (timed_out, ()) = pollster::block_on(pollster::join(
+        finished_at(start, pollster::timeout(ms(30), pending::<()>())),
+        pollster::sleep(ms(60)),
+    ));
+    assert!(timed_out.0.is_err());
+    assert_near(timed_out.1, ms(30));
+    assert_took(start, ms(60));
+
+    // A future that's ready in time isn't affected
+    let result = pollster::block_on(pollster::timeout(ms(50), pollster::sleep(ms(10))));
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn overlapping_timeouts() {
+    // Each timeout fires at its own deadline, whatever order they were created in
+    let ms = Duration::from_millis;
+    let start = Instant::now();
+    let (late, (early, nested)) = pollster::block_on(pollster::join(
+        finished_at(start, pollster::timeout(ms(60), pending::<()>())),
+        pollster::join(
+            finished_at(start, pollster::timeout(ms(20), pending::<()>())),
+            finished_at(
+                start,
+                pollster::timeout(ms(80), pollster::timeout(ms(40), pending::<()>())),
+            ),
+        ),
+    ));
+    assert!(late.0.is_err());
+    assert_near(late.1, ms(60));
+    assert!(early.0.is_err());
+    assert_near(early.1, ms(20));
+    // The inner timeout fired first, so the outer one succeeded
+    assert!(matches!(nested.0, Ok(Err(_))));
+    assert_near(nested.1, ms(40));
+}
+
+#[test]
+fn interval() {
+    let start = Instant::now();
+    let mut interval = pollster::interval(Duration::from_millis(20));
+    // The first tick is immediate, and the rest follow the schedule
+    let first = interval.blocking_tick();
+    assert!(first - start < TOLERANCE);
+    for i in 1..=3 {
+        assert_eq!(
+            interval.blocking_tick(),
+            first + Duration::from_millis(20) * i
+        );
+    }
+    assert_took(start, Duration::from_millis(60));
+}
+
+#[test]
+fn interval_delayed() {
+    let start = Instant::now();
+    let mut interval = pollster::IntervalBuilder::new(Duration::from_millis(30))
+        .immediate(false)
+        .build();
+    interval.blocking_tick();
+    assert_took(start, Duration::from_millis(30));
+}
+
+#[test]
+fn interval_missed_ticks() {
+    let period = Duration::from_millis(20);
+
+    // Every missed tick fires straight away
+    let mut interval = pollster::interval(period);
+    let first = interval.blocking_tick();
+    std::thread::sleep(period * 3 + period / 2);
+    let caught_up = Instant::now();
+    for i in 1..=3 {
+        assert_eq!(interval.blocking_tick(), first + period * i);
+    }
+    assert!(caught_up.elapsed() < period / 2);
+
+    // Only one fires, and the schedule carries on from the next tick still to come
+    let mut interval = pollster::IntervalBuilder::new(period)
+        .missed_ticks(pollster::MissedTicks::Skip)
+        .build();
+    let first = interval.blocking_tick();
+    std::thread::sleep(period * 3 + period / 2);
+    assert_eq!(interval.blocking_tick(), first + period);
+    assert_eq!(interval.blocking_tick(), first + period * 4);
+}
+
+#[test]
+fn interval_alongside_future() {
+    // Polling on every tick while also waiting for something else
+    let mut interval = pollster::interval(Duration::from_millis(10));
+    let mut checks = 0;
+    let done = pollster::sleep(Duration::from_millis(55));
+    let mut done = core::pin::pin!(done);
+    pollster::block_on(poll_fn(|cx| {
+        while interval.poll_tick(cx).is_ready() {
+            checks += 1;
+        }
+        done.as_mut().poll(cx)
+    }));
+    assert!((5..=7).contains(&checks), "{}", checks);
+}
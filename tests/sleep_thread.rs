@@ -0,0 +1,25 @@
+#![cfg(target_os = "linux")]
+
+// In its own test binary, so that no other test has started the timer thread.
+
+use std::{fs, time::Duration};
+
+#[test]
+fn no_timer_thread() {
+    pollster::block_on(async {
+        pollster::sleep(Duration::from_millis(20)).await;
+        pollster::sleep(Duration::from_millis(20)).await;
+        let _ = pollster::timeout(Duration::from_millis(20), std::future::pending::<()>()).await;
+    });
+
+    // `block_on` waited out the sleeps and the timeout itself, without spawning the timer thread
+    let names = fs::read_dir("/proc/self/task")
+        .unwrap()
+        .map(|task| fs::read_to_string(task.unwrap().path().join("comm")).unwrap())
+        .collect::<Vec<_>>();
+    assert!(
+        names.iter().all(|name| name.trim() != "pollster-timer"),
+        "{:?}",
+        names
+    );
+}
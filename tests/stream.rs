@@ -0,0 +1,458 @@
+#![cfg(feature = "stream")]
+
+use std::{thread, time::Duration};
+
+use futures::{channel::mpsc, stream, SinkExt as _};
+
+#[test]
+fn channel() {
+    let (mut tx, rx) = mpsc::channel(4);
+
+    let sender = thread::spawn(move || {
+        pollster::block_on(async {
+            for i in 0..100 {
+                tx.send(i).await.expect("send");
+            }
+        });
+    });
+
+    let items = pollster::block_on_stream(rx).collect::<Vec<_>>();
+    assert_eq!(items, (0..100).collect::<Vec<_>>());
+
+    sender.join().expect("join sender");
+}
+
+#[test]
+fn ends_mid_iteration() {
+    let (tx, rx) = mpsc::unbounded();
+
+    let sender = thread::spawn(move || {
+        for i in 0..3 {
+            thread::sleep(Duration::from_millis(10));
+            tx.unbounded_send(i).expect("send");
+        }
+        // Dropping the sender ends the stream while the receiver is parked waiting for more.
+        thread::sleep(Duration::from_millis(10));
+        drop(tx);
+    });
+
+    let mut iter = pollster::block_on_stream(rx);
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(iter.next(), None);
+
+    sender.join().expect("join sender");
+}
+
+#[test]
+fn not_unpin() {
+    let stream = stream::unfold(0, |n| async move {
+        if n < 3 {
+            std::future::ready(()).await;
+            Some((n, n + 1))
+        } else {
+            None
+        }
+    });
+
+    let items = pollster::block_on_stream(stream).collect::<Vec<_>>();
+    assert_eq!(items, [0, 1, 2]);
+}
+
+#[test]
+fn size_hint() {
+    let iter = pollster::block_on_stream(stream::iter([1, 2, 3]));
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn blocking_next() {
+    use futures::StreamExt as _;
+    use pollster::StreamExt as _;
+
+    let mut stream = stream::iter(0..5);
+
+    // Interleave blocking and async access to the same stream
+    assert_eq!(stream.blocking_next(), Some(0));
+    assert_eq!(pollster::block_on(stream.next()), Some(1));
+    assert_eq!(stream.blocking_next(), Some(2));
+    assert_eq!(pollster::block_on(async { stream.next().await }), Some(3));
+    assert_eq!(stream.blocking_next(), Some(4));
+    assert_eq!(stream.blocking_next(), None);
+}
+
+#[test]
+fn blocking_next_pinned() {
+    use pollster::StreamExt as _;
+
+    let stream = stream::unfold(0, |n| async move { Some((n, n + 1)) });
+    let mut stream = core::pin::pin!(stream);
+
+    assert_eq!(stream.blocking_next(), Some(0));
+    assert_eq!(stream.blocking_next(), Some(1));
+}
+
+#[test]
+fn blocking_try_next() {
+    use pollster::StreamExt as _;
+
+    let mut stream = stream::iter([Ok(1), Err("bad"), Ok(2)]);
+    assert_eq!(stream.blocking_try_next(), Ok(Some(1)));
+    assert_eq!(stream.blocking_try_next(), Err("bad"));
+    // The stream is still usable after an error
+    assert_eq!(stream.blocking_try_next(), Ok(Some(2)));
+    assert_eq!(stream.blocking_try_next(), Ok(None));
+
+    fn sum(items: &[Result<i32, &'static str>]) -> Result<i32, &'static str> {
+        let mut stream = stream::iter(items.iter().copied());
+        let mut total = 0;
+        while let Some(n) = stream.blocking_try_next()? {
+            total += n;
+        }
+        Ok(total)
+    }
+
+    assert_eq!(sum(&[Ok(1), Ok(2), Ok(3)]), Ok(6));
+    assert_eq!(sum(&[Err("immediate"), Ok(1)]), Err("immediate"));
+}
+
+#[test]
+fn blocking_collect() {
+    use pollster::StreamExt as _;
+    use std::collections::HashMap;
+
+    let (mut tx, rx) = mpsc::channel(4);
+    let sender = thread::spawn(move || {
+        pollster::block_on(async {
+            for i in 0..10 {
+                tx.send(i).await.expect("send");
+            }
+        });
+    });
+    assert_eq!(rx.blocking_collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    sender.join().expect("join sender");
+
+    let map = stream::iter([("a", 1), ("b", 2)]).blocking_collect::<HashMap<_, _>>();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map["b"], 2);
+
+    // Streams that aren't `Unpin` can be consumed too
+    let stream = stream::unfold(0, |n| async move { if n < 3 { Some((n, n + 1)) } else { None } });
+    assert_eq!(stream.blocking_collect::<Vec<_>>(), [0, 1, 2]);
+}
+
+#[test]
+fn blocking_for_each_and_fold() {
+    use pollster::StreamExt as _;
+
+    let mut seen = Vec::new();
+    stream::iter(0..5).blocking_for_each(|i| seen.push(i));
+    assert_eq!(seen, [0, 1, 2, 3, 4]);
+
+    assert_eq!(stream::iter(0..5).blocking_fold(0, |acc, i| acc + i), 10);
+    assert_eq!(stream::iter(0..0).blocking_fold(7, |acc, i| acc + i), 7);
+}
+
+#[test]
+fn blocking_try_for_each_early_exit() {
+    use pollster::StreamExt as _;
+    use std::ops::ControlFlow;
+
+    let mut stream = stream::iter(0..6);
+
+    let mut seen = Vec::new();
+    let result = stream.blocking_try_for_each(|i| {
+        seen.push(i);
+        if i == 2 {
+            ControlFlow::Break("stopped")
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(result, ControlFlow::Break("stopped"));
+    assert_eq!(seen, [0, 1, 2]);
+
+    // The remaining items are still deliverable
+    assert_eq!(stream.blocking_collect::<Vec<_>>(), [3, 4, 5]);
+
+    let result: ControlFlow<()> = stream::iter(0..3).blocking_try_for_each(|_| ControlFlow::Continue(()));
+    assert_eq!(result, ControlFlow::Continue(()));
+}
+
+#[test]
+fn blocking_ready_chunks() {
+    use pollster::StreamExt as _;
+
+    // Everything is available up front, so batches are always full until the last
+    let (tx, rx) = mpsc::unbounded();
+    for i in 0..10 {
+        tx.unbounded_send(i).expect("send");
+    }
+    drop(tx);
+    let batches = rx.blocking_ready_chunks(4).collect::<Vec<_>>();
+    assert_eq!(batches, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+
+    // Items trickle in, so we park for each batch but never return an empty one
+    let (tx, rx) = mpsc::unbounded();
+    let sender = thread::spawn(move || {
+        for i in 0..5 {
+            thread::sleep(Duration::from_millis(10));
+            tx.unbounded_send(i).expect("send");
+        }
+    });
+    let batches = rx.blocking_ready_chunks(4).collect::<Vec<_>>();
+    assert!(batches.iter().all(|batch| !batch.is_empty() && batch.len() <= 4));
+    assert_eq!(batches.concat(), [0, 1, 2, 3, 4]);
+    sender.join().expect("join sender");
+
+    // Rounding the upper bound up to a whole batch must not overflow
+    let batches = futures::StreamExt::take(stream::repeat(0u8), usize::MAX).blocking_ready_chunks(2);
+    assert_eq!(batches.size_hint(), (1, Some(usize::MAX / 2 + 1)));
+}
+
+#[test]
+fn blocking_chunks_timeout() {
+    use pollster::StreamExt as _;
+
+    let (tx, rx) = mpsc::unbounded();
+    let sender = thread::spawn(move || {
+        for i in 0..3 {
+            tx.unbounded_send(i).expect("send");
+        }
+        // Longer than the window, so the first batch is cut short
+        thread::sleep(Duration::from_millis(300));
+        for i in 3..5 {
+            tx.unbounded_send(i).expect("send");
+        }
+    });
+
+    let batches = rx
+        .blocking_chunks_timeout(10, Duration::from_millis(50))
+        .collect::<Vec<_>>();
+    assert_eq!(batches, [vec![0, 1, 2], vec![3, 4]]);
+    sender.join().expect("join sender");
+
+    // Full batches are returned without waiting for the window to elapse
+    let batches = stream::iter(0..4)
+        .blocking_chunks_timeout(2, Duration::from_secs(60))
+        .collect::<Vec<_>>();
+    assert_eq!(batches, [vec![0, 1], vec![2, 3]]);
+}
+
+#[test]
+fn blocking_buffered() {
+    use pollster::StreamExt as _;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+    let make_stream = || {
+        let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+        // Later futures finish sooner, so completion order is the reverse of stream order
+        stream::iter((0..6u64).map(move |i| {
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                futures_timer::Delay::new(Duration::from_millis(60 - i * 10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        }))
+    };
+
+    // Ordered mode preserves stream order, and drains the futures still in flight when the stream ends
+    let outputs = make_stream().blocking_buffered(3).collect::<Vec<_>>();
+    assert_eq!(outputs, [0, 1, 2, 3, 4, 5]);
+    assert_eq!(max_in_flight.swap(0, Ordering::SeqCst), 3);
+    assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+
+    // Unordered mode yields as futures finish
+    let outputs = make_stream().blocking_buffer_unordered(3).collect::<Vec<_>>();
+    assert_eq!(outputs.len(), 6);
+    assert_eq!(outputs[0], 2);
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn merge() {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    // Panics if polled again after ending, to check that ended streams are dropped from the merge
+    struct Strict<S>(S, bool);
+
+    impl<S: futures::Stream + Unpin> futures::Stream for Strict<S> {
+        type Item = S::Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+            assert!(!self.1, "stream polled after it ended");
+            let poll = Pin::new(&mut self.0).poll_next(cx);
+            if let Poll::Ready(None) = poll {
+                self.1 = true;
+            }
+            poll
+        }
+    }
+
+    let senders = (0..3)
+        .map(|i| {
+            let (tx, rx) = mpsc::unbounded();
+            let sender = thread::spawn(move || {
+                // Each stream produces a different number of items at a different rate, so they end at different times
+                for n in 0..(i + 1) * 5 {
+                    thread::sleep(Duration::from_millis(i as u64 * 5 + 1));
+                    tx.unbounded_send((i, n)).expect("send");
+                }
+            });
+            (sender, Strict(rx, false))
+        })
+        .collect::<Vec<_>>();
+    let (senders, streams): (Vec<_>, Vec<_>) = senders.into_iter().unzip();
+
+    let items = pollster::stream::merge(streams).collect::<Vec<_>>();
+    for i in 0..3 {
+        let from_stream = items.iter().filter(|(s, _)| *s == i).map(|(_, n)| *n).collect::<Vec<_>>();
+        assert_eq!(from_stream, (0..(i + 1) * 5).collect::<Vec<_>>());
+    }
+
+    for sender in senders {
+        sender.join().expect("join sender");
+    }
+
+    // Merging no streams ends immediately
+    let mut empty = pollster::stream::merge(Vec::<stream::Iter<std::vec::IntoIter<()>>>::new());
+    assert_eq!(empty.next(), None);
+}
+
+#[test]
+fn next_timeout() {
+    let (tx, rx) = mpsc::unbounded();
+    let mut iter = pollster::block_on_stream(rx);
+
+    // Nothing has arrived yet
+    assert!(iter.next_timeout(Duration::from_millis(20)).is_err());
+
+    let sender = thread::spawn(move || {
+        tx.unbounded_send(1).expect("send");
+        thread::sleep(Duration::from_millis(100));
+        tx.unbounded_send(2).expect("send");
+    });
+
+    assert_eq!(iter.next_timeout(Duration::from_secs(10)), Ok(Some(1)));
+    // The stream remains usable after timing out, and nothing is lost
+    assert!(iter.next_timeout(Duration::from_millis(10)).is_err());
+    assert_eq!(iter.next_timeout(Duration::from_secs(10)), Ok(Some(2)));
+    assert_eq!(iter.next_timeout(Duration::from_secs(10)), Ok(None));
+
+    sender.join().expect("join sender");
+}
+
+#[test]
+fn block_on_stream_for_each() {
+    use std::{cell::Cell, rc::Rc};
+
+    let mut seen = Vec::new();
+    pollster::block_on_stream_for_each(stream::iter(0..5), |i| seen.push(i));
+    assert_eq!(seen, [0, 1, 2, 3, 4]);
+
+    // Stops at the first error, dropping the stream without polling it again
+    let polls = Rc::new(Cell::new(0));
+    let counted = futures::StreamExt::inspect(stream::iter(0..5), {
+        let polls = Rc::clone(&polls);
+        move |_| polls.set(polls.get() + 1)
+    });
+    let result =
+        pollster::try_block_on_stream_for_each(counted, |i| if i < 2 { Ok(()) } else { Err(i) });
+    assert_eq!(result, Err(2));
+    assert_eq!(polls.get(), 3);
+    assert_eq!(Rc::strong_count(&polls), 1);
+
+    assert_eq!(
+        pollster::try_block_on_stream_for_each(stream::iter(0..5), |_| Ok::<_, ()>(())),
+        Ok(())
+    );
+}
+
+#[test]
+fn stream_reader() {
+    use pollster::io::StreamReader;
+    use std::io::{self, BufRead, Read};
+
+    let chunks = || {
+        let chunks = [&b"hel"[..], b"", b"lo, wor", b"ld"];
+        stream::iter(IntoIterator::into_iter(chunks).map(Ok::<_, io::Error>))
+    };
+
+    // Reads smaller than a chunk are served from it, without splitting the chunks any further
+    let mut reader = StreamReader::new(chunks(), |err| err);
+    let mut buf = [0; 2];
+    let mut reads = Vec::new();
+    loop {
+        match reader.read(&mut buf).expect("read") {
+            0 => break,
+            n => reads.push(buf[..n].to_vec()),
+        }
+    }
+    assert_eq!(reads, [&b"he"[..], b"l", b"lo", b", ", b"wo", b"r", b"ld"]);
+    // EOF is sticky, rather than polling the finished stream again
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+
+    // Reads larger than a chunk return one chunk at a time
+    let mut reader = StreamReader::new(chunks(), |err| err);
+    let mut buf = [0; 64];
+    assert_eq!(reader.read(&mut buf).expect("read"), 3);
+    assert_eq!(reader.read(&mut buf).expect("read"), 7);
+    assert_eq!(&buf[..7], b"lo, wor");
+
+    // `BufRead` works across chunk boundaries too
+    let reader = StreamReader::new(chunks(), |err| err);
+    let words = reader
+        .split(b' ')
+        .collect::<Result<Vec<_>, _>>()
+        .expect("split");
+    assert_eq!(words, [&b"hello,"[..], b"world"]);
+}
+
+#[test]
+fn stream_reader_error() {
+    use pollster::io::StreamReader;
+    use std::io::{self, Read};
+
+    #[derive(Debug)]
+    struct Reset;
+
+    let chunks = stream::iter([Ok(&b"hello"[..]), Err(Reset), Ok(b"world")]);
+    let mut reader = StreamReader::new(chunks, |Reset| {
+        io::Error::new(io::ErrorKind::ConnectionReset, "reset")
+    });
+
+    let mut buf = [0; 16];
+    assert_eq!(reader.read(&mut buf).expect("read"), 5);
+    let err = reader.read(&mut buf).expect_err("reset");
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+
+    // The stream carries on after an error
+    assert_eq!(reader.read(&mut buf).expect("read"), 5);
+    assert_eq!(&buf[..5], b"world");
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+}
+
+#[test]
+fn interval_stream() {
+    let period = Duration::from_millis(10);
+    let ticks = pollster::block_on_stream(pollster::interval(period))
+        .take(3)
+        .collect::<Vec<_>>();
+    assert_eq!(ticks[1] - ticks[0], period);
+    assert_eq!(ticks[2] - ticks[1], period);
+}
@@ -0,0 +1,219 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use pollster::sync::{Event, WaitGroup};
+
+#[test]
+fn many_waiters() {
+    let event = Arc::new(Event::new());
+    let waiters = (0..16)
+        .map(|_| {
+            let event = Arc::clone(&event);
+            thread::spawn(move || event.wait())
+        })
+        .collect::<Vec<_>>();
+
+    // A single set wakes every one of them
+    thread::sleep(Duration::from_millis(50));
+    event.set();
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+}
+
+#[test]
+fn set_before_wait() {
+    let event = Event::new();
+    event.set();
+    event.set();
+    assert!(event.is_set());
+
+    // Stays set until it's reset
+    event.wait();
+    assert!(event.wait_timeout(Duration::ZERO));
+    pollster::block_on(event.wait_async());
+
+    event.reset();
+    assert!(!event.is_set());
+    assert!(!event.wait_timeout(Duration::from_millis(10)));
+}
+
+#[test]
+fn wait_timeout() {
+    let event = Arc::new(Event::new());
+    assert!(!event.wait_timeout(Duration::from_millis(10)));
+
+    let setter = {
+        let event = Arc::clone(&event);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            event.set();
+        })
+    };
+    assert!(event.wait_timeout(Duration::from_secs(10)));
+    setter.join().unwrap();
+}
+
+#[test]
+fn set_then_reset() {
+    let event = Event::new();
+    let waker = pollster::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut wait = Box::pin(event.wait_async());
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+
+    // A waiter that was waiting when the event was set still sees it, even once reset
+    event.set();
+    event.reset();
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(()));
+
+    // But a new one doesn't
+    let mut wait = Box::pin(event.wait_async());
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn async_waiters() {
+    let event = Event::new();
+    let mut executor = pollster::LocalExecutor::new();
+    for _ in 0..4 {
+        executor.spawn(event.wait_async());
+    }
+    executor.spawn(async {
+        pollster::yield_now().await;
+        event.set();
+    });
+    executor.run();
+}
+
+#[test]
+fn wait_group_threads() {
+    let group = WaitGroup::new();
+    let workers = (0..32)
+        .map(|i| {
+            let worker = group.worker();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(i % 4 * 10));
+                drop(worker);
+            })
+        })
+        .collect::<Vec<_>>();
+
+    group.wait();
+    assert_eq!(group.count(), 0);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn wait_group_finished_early() {
+    let group = WaitGroup::new();
+    // Nothing to wait for yet
+    group.wait();
+
+    // Workers that finish before the wait starts still count
+    let workers = (0..32)
+        .map(|_| {
+            let worker = group.worker();
+            thread::spawn(move || drop(worker))
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert!(group.wait_timeout(Duration::ZERO));
+}
+
+#[test]
+fn wait_group_manual() {
+    let group = WaitGroup::new();
+    group.add(3);
+    let worker = group.worker();
+    let clone = worker.clone();
+    assert_eq!(group.count(), 5);
+
+    group.done();
+    group.done();
+    drop(worker);
+    assert!(!group.wait_timeout(Duration::from_millis(10)));
+
+    group.done();
+    drop(clone);
+    assert!(group.wait_timeout(Duration::ZERO));
+}
+
+#[test]
+#[should_panic(expected = "more times than `add`")]
+fn wait_group_done_underflow() {
+    WaitGroup::new().done();
+}
+
+#[test]
+fn wait_group_reached_zero_then_added() {
+    let group = WaitGroup::new();
+    let waker = pollster::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let worker = group.worker();
+    let mut wait = Box::pin(group.wait_async());
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+
+    // A waiter that was waiting when the count reached zero still returns, even if more work is added before it runs
+    drop(worker);
+    let worker = group.worker();
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(()));
+
+    // But a new one waits for the new work
+    let mut wait = Box::pin(group.wait_async());
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+    drop(worker);
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn wait_group_tasks() {
+    let group = WaitGroup::new();
+    let mut executor = pollster::LocalExecutor::new();
+    for i in 0..48 {
+        let worker = group.worker();
+        executor.spawn(async move {
+            for _ in 0..i % 5 {
+                pollster::yield_now().await;
+            }
+            drop(worker);
+        });
+    }
+    for _ in 0..4 {
+        executor.spawn(group.wait_async());
+    }
+    executor.run();
+    assert_eq!(group.count(), 0);
+}
+
+#[test]
+fn wait_group_racing_add() {
+    // Work added while other work is finishing is waited for as well, as long as it's added before the count reaches
+    // zero
+    let group = WaitGroup::new();
+    let worker = group.worker();
+    let spawner = thread::spawn(move || {
+        (0..32)
+            .map(|_| {
+                let worker = worker.clone();
+                thread::spawn(move || drop(worker))
+            })
+            .collect::<Vec<_>>()
+    });
+    group.wait();
+    assert_eq!(group.count(), 0);
+    for worker in spawner.join().unwrap() {
+        worker.join().unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "testing")]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use pollster::testing::{block_on_n_times, block_on_n_times_parallel};
+
+#[test]
+fn n_times() {
+    let counter = AtomicUsize::new(0);
+    let outputs = block_on_n_times(
+        || async {
+            futures_timer::Delay::new(Duration::from_micros(10)).await;
+            counter.fetch_add(1, Ordering::Relaxed)
+        },
+        100,
+    );
+
+    // Run one after the other, in order
+    assert_eq!(outputs, (0..100).collect::<Vec<_>>());
+    assert!(block_on_n_times(|| async {}, 0).is_empty());
+}
+
+#[test]
+fn n_times_parallel() {
+    let workers = Mutex::new(Vec::new());
+    let outputs = block_on_n_times_parallel(
+        || async {
+            futures_timer::Delay::new(Duration::from_micros(10)).await;
+            let mut workers = workers.lock().unwrap();
+            if !workers.contains(&thread::current().id()) {
+                workers.push(thread::current().id());
+            }
+            2 + 2
+        },
+        1000,
+        4,
+    );
+
+    assert_eq!(outputs.len(), 1000);
+    assert!(outputs.iter().all(|&x| x == 4));
+    assert!(workers.into_inner().unwrap().len() <= 4);
+    assert!(block_on_n_times_parallel(|| async {}, 0, 4).is_empty());
+
+    let payload = std::panic::catch_unwind(|| {
+        block_on_n_times_parallel(|| async { panic!("oh no") }, 8, 4)
+    })
+    .expect_err("panicked");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oh no"));
+}
@@ -0,0 +1,195 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn threaded_block_on() {
+    let executor = pollster::ThreadedBlockOn::new();
+
+    let caller = thread::current().id();
+    let background = executor.block_on(async { thread::current().id() });
+    assert_ne!(caller, background);
+
+    // Every future runs on the same background thread
+    assert_eq!(executor.block_on(async { thread::current().id() }), background);
+
+    let delay = futures_timer::Delay::new(Duration::from_millis(10));
+    executor.block_on(async move {
+        delay.await;
+    });
+}
+
+#[test]
+fn threaded_block_on_panic() {
+    let executor = pollster::ThreadedBlockOn::new();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| executor.block_on(async { panic!("oh no") })));
+    assert!(result.is_err());
+
+    // The background thread survives the panic
+    assert_eq!(executor.block_on(async { 42 }), 42);
+}
+
+#[test]
+fn block_on_thread() {
+    let handle = pollster::block_on_thread("pollster-worker", async {
+        futures_timer::Delay::new(Duration::from_millis(10)).await;
+        thread::current().name().map(String::from)
+    });
+
+    assert_eq!(handle.thread().name(), Some("pollster-worker"));
+    assert_eq!(
+        handle.join().expect("join worker").as_deref(),
+        Some("pollster-worker")
+    );
+
+    let handle =
+        pollster::block_on_thread(String::from("panicking-worker"), async { panic!("oh no") });
+    assert!(handle.join().is_err());
+}
+
+#[test]
+fn spawn_block_on() {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    let handle = pollster::spawn_block_on(async { rx.await.expect("receive") * 2 });
+    assert_ne!(handle.thread().id(), thread::current().id());
+
+    // The caller isn't blocked while the future waits
+    assert_eq!(handle.try_join(), None);
+    tx.send(21).expect("send");
+    assert_eq!(handle.join(), 42);
+
+    // The output can be polled for instead of joined
+    let handle = pollster::spawn_block_on(async { 42 });
+    let output = loop {
+        if let Some(output) = handle.try_join() {
+            break output;
+        }
+        thread::yield_now();
+    };
+    assert_eq!(output, 42);
+    assert_eq!(handle.try_join(), None);
+
+    let handle = pollster::spawn_block_on(async { panic!("oh no") });
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| handle.join())).expect_err("panicked");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oh no"));
+}
+
+#[test]
+fn block_on_par() {
+    use std::{collections::HashSet, sync::Mutex};
+
+    let workers = Mutex::new(HashSet::new());
+    let outputs = pollster::block_on_par(
+        (0..32u64).map(|i| {
+            let workers = &workers;
+            async move {
+                // Finish out of order, so the outputs would be shuffled if they weren't put back in order
+                futures_timer::Delay::new(Duration::from_millis(32 - i)).await;
+                workers.lock().unwrap().insert(thread::current().id());
+                i * i
+            }
+        }),
+        4,
+    );
+
+    assert_eq!(outputs, (0..32).map(|i| i * i).collect::<Vec<_>>());
+    let workers = workers.into_inner().unwrap();
+    assert!(workers.len() <= 4);
+    assert!(!workers.contains(&thread::current().id()));
+
+    // More threads than futures, and no futures at all
+    assert_eq!(pollster::block_on_par([async { 1 }], 8), [1]);
+    assert!(pollster::block_on_par(Vec::<std::future::Ready<()>>::new(), 8).is_empty());
+}
+
+#[test]
+fn block_on_par_panic() {
+    let payload = panic::catch_unwind(|| {
+        pollster::block_on_par(
+            (0..4).map(|i| async move {
+                if i == 2 {
+                    panic!("oh no");
+                }
+            }),
+            2,
+        )
+    })
+    .expect_err("panicked");
+    // The future's own payload is resumed, rather than a generic one from the worker thread
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oh no"));
+}
+
+#[test]
+fn block_on_annotated() {
+    use std::sync::{Arc, Mutex};
+
+    // The hook that was installed first still runs, with the thread the panic happened on
+    let panicked = Arc::new(Mutex::new(Vec::new()));
+    panic::set_hook(Box::new({
+        let panicked = Arc::clone(&panicked);
+        move |_| {
+            let name = thread::current().name().map(str::to_owned);
+            panicked.lock().unwrap().push(name);
+        }
+    }));
+
+    let worker = thread::Builder::new()
+        .name("worker-3".into())
+        .spawn(|| {
+            assert_eq!(pollster::block_on_annotated(async { 42 }), 42);
+            pollster::block_on_annotated(async { panic!("oh no") })
+        })
+        .expect("spawn worker");
+    let payload = worker.join().expect_err("worker panicked");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oh no"));
+    assert!(panicked
+        .lock()
+        .unwrap()
+        .contains(&Some("worker-3".to_owned())));
+}
+
+#[test]
+fn block_on_servicing() {
+    use pollster::FutureExt as _;
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+    static POLLS: AtomicUsize = AtomicUsize::new(0);
+
+    let (tx, mut rx) = pollster::waker_channel();
+    let (done_tx, done_rx) = pollster::oneshot::channel();
+
+    let (sent_tx, sent_rx) = std::sync::mpsc::channel();
+    let helper = thread::spawn(move || {
+        // Sent before the other thread blocks, so it has to be woken by the first drain
+        let (early, waker) = pollster::signal_pair();
+        tx.send(waker).unwrap();
+        sent_tx.send(()).unwrap();
+        early.wait();
+
+        // By now the other thread is parked, so this has to unpark it to be woken
+        thread::sleep(Duration::from_millis(50));
+        for _ in 0..3 {
+            let (signal, waker) = pollster::signal_pair();
+            tx.send(waker).unwrap();
+            signal.wait();
+        }
+        done_tx.send(()).unwrap();
+    });
+
+    sent_rx.recv().unwrap();
+    let fut = done_rx.inspect_poll(|_| {
+        POLLS.fetch_add(1, SeqCst);
+    });
+    assert_eq!(pollster::block_on_servicing(fut, &mut rx), Ok(()));
+    // Sending wakers didn't cause the future to be polled
+    assert_eq!(POLLS.load(SeqCst), 2);
+    helper.join().unwrap();
+
+    // Sending fails once the receiver has been dropped
+    let (tx, rx) = pollster::waker_channel();
+    drop(rx);
+    assert!(tx.send(pollster::noop_waker()).is_err());
+}
@@ -0,0 +1,150 @@
+use std::{
+    cell::Cell,
+    convert::TryInto,
+    future::{pending, ready},
+    sync::Arc,
+    task::{Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use pollster::{Clock, ParkStrategy};
+
+#[test]
+fn timeout() {
+    assert_eq!(pollster::block_on_timeout(ready(42), Duration::ZERO), Ok(42));
+
+    let then = Instant::now();
+    let err = pollster::block_on_timeout(pending::<()>(), Duration::from_millis(100)).expect_err("timed out");
+    assert!(err.elapsed() >= Duration::from_millis(100));
+    assert!(then.elapsed() >= err.elapsed());
+
+    let delay = futures_timer::Delay::new(Duration::from_millis(10));
+    assert_eq!(pollster::block_on_timeout(delay, Duration::from_secs(10)), Ok(()));
+
+    // Timeouts too large to represent never elapse
+    assert_eq!(pollster::block_on_timeout(ready(42), Duration::MAX), Ok(42));
+}
+
+#[test]
+fn deadline() {
+    let then = Instant::now();
+    assert!(pollster::block_on_deadline(pending::<()>(), then + Duration::from_millis(100)).is_err());
+    assert!(then.elapsed() >= Duration::from_millis(100));
+
+    // The future is still polled once if the deadline has already passed
+    assert_eq!(pollster::block_on_deadline(ready(42), then), Ok(42));
+}
+
+// A clock backed by a tick counter, as might be found on an embedded target, which advances one tick every time the
+// strategy parks.
+#[derive(Default)]
+struct Ticks(Cell<u64>);
+
+impl Clock for Ticks {
+    type Instant = u64;
+
+    fn now(&self) -> u64 {
+        self.0.get()
+    }
+
+    fn saturating_duration_since(&self, later: u64, earlier: u64) -> Duration {
+        Duration::from_millis(later.saturating_sub(earlier))
+    }
+
+    fn checked_add(&self, instant: u64, duration: Duration) -> Option<u64> {
+        instant.checked_add(duration.as_millis().try_into().ok()?)
+    }
+}
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+impl ParkStrategy for Ticks {
+    fn waker(&self) -> Waker {
+        Waker::from(Arc::new(Noop))
+    }
+
+    fn park(&self) {
+        self.0.set(self.0.get() + 1);
+    }
+
+    fn park_timeout(&self, _: Duration) -> bool {
+        self.park();
+        false
+    }
+}
+
+#[test]
+fn custom_clock() {
+    let ticks = Ticks::default();
+
+    let result = pollster::block_on_timeout_with(pending::<()>(), Duration::from_millis(5), &ticks, &ticks);
+    assert_eq!(result.map_err(|err| err.elapsed()), Err(Duration::from_millis(5)));
+    assert_eq!(ticks.now(), 5);
+
+    let result = pollster::block_on_deadline_with(ready(42), 0, &ticks, &ticks);
+    assert_eq!(result, Ok(42));
+}
+
+#[test]
+fn timeout_combinator() {
+    use pollster::FutureExt as _;
+
+    assert_eq!(ready(42).timeout(Duration::ZERO).block_on(), Ok(42));
+
+    let start = Instant::now();
+    assert!(pending::<()>().timeout(Duration::from_millis(20)).block_on().is_err());
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    // Unlike `pollster::timeout`, the deadline is measured from the first poll rather than from creation
+    let fut = pending::<()>().timeout(Duration::from_millis(50));
+    assert_eq!(fut.deadline(), None);
+    std::thread::sleep(Duration::from_millis(50));
+    let then = Instant::now();
+    let err = fut.block_on().expect_err("timed out");
+    assert!(then.elapsed() >= Duration::from_millis(50));
+    assert!(err.elapsed() >= Duration::from_millis(50));
+    assert!(err.elapsed() <= then.elapsed());
+
+    let fut = pollster::timeout(Duration::from_millis(50), pending::<()>());
+    assert!(fut.deadline().is_some());
+    std::thread::sleep(Duration::from_millis(50));
+    let then = Instant::now();
+    assert!(pollster::block_on(fut).is_err());
+    assert!(then.elapsed() < Duration::from_millis(50));
+
+    let delay = futures_timer::Delay::new(Duration::from_millis(10));
+    assert_eq!(delay.timeout(Duration::from_secs(10)).block_on(), Ok(()));
+    assert_eq!(ready(42).timeout(Duration::MAX).block_on(), Ok(42));
+
+    // Composes with other futures: the inner timeout fires first, and the outer one never does
+    let nested = pending::<()>()
+        .timeout(Duration::from_millis(10))
+        .timeout(Duration::from_secs(10))
+        .block_on();
+    assert!(matches!(nested, Ok(Err(_))));
+}
+
+#[test]
+fn timeout_combinator_many() {
+    use pollster::FutureExt as _;
+
+    // Many timeouts at once, sharing the same timer thread
+    let handles = (0..8u64)
+        .map(|i| {
+            std::thread::spawn(move || {
+                pending::<()>()
+                    .timeout(Duration::from_millis(5 * i))
+                    .block_on()
+                    .expect_err("timed out")
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert!(handle.join().expect("join").elapsed() >= Duration::from_millis(5 * i as u64));
+    }
+}
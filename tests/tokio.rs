@@ -0,0 +1,29 @@
+#![cfg(feature = "tokio-detect")]
+
+use std::time::Duration;
+
+#[test]
+fn inside_tokio() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("build runtime");
+
+    // Blocking inside the runtime is discouraged (and warned about), but still works for futures that don't rely on
+    // the runtime itself
+    let result = rt.block_on(async {
+        pollster::block_on(async {
+            futures_timer::Delay::new(Duration::from_millis(10)).await;
+            42
+        })
+    });
+    assert_eq!(result, 42);
+
+    // Blocking inside `spawn_blocking` is fine
+    let result = rt.block_on(async {
+        tokio::task::spawn_blocking(|| {
+            pollster::block_on(futures_timer::Delay::new(Duration::from_millis(10)))
+        })
+        .await
+    });
+    assert!(result.is_ok());
+}
@@ -0,0 +1,289 @@
+#![cfg(feature = "tokio-io")]
+
+use std::{
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use pollster::io::tokio::{BlockingBufReader, BlockingReader, BlockingWriter};
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _, BufReader, DuplexStream};
+
+// Drive the other end of a pipe from a thread inside a Tokio runtime
+fn peer<T: Send + 'static>(
+    f: impl FnOnce(&tokio::runtime::Runtime) -> T + Send + 'static,
+) -> thread::JoinHandle<T> {
+    thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("build runtime");
+        f(&rt)
+    })
+}
+
+fn write_chunks(mut tx: DuplexStream, chunks: &'static [&'static [u8]]) -> thread::JoinHandle<()> {
+    peer(move |rt| {
+        for chunk in chunks {
+            thread::sleep(Duration::from_millis(10));
+            rt.block_on(tx.write_all(chunk)).expect("write chunk");
+        }
+    })
+}
+
+#[test]
+fn partial_reads() {
+    let (tx, rx) = tokio::io::duplex(64);
+    let mut reader = BlockingReader::new(rx);
+    let writer = write_chunks(tx, &[b"hello", b", ", b"world"]);
+
+    // Each read parks until the next chunk arrives, and returns no more than one chunk
+    let mut buf = [0; 3];
+    assert_eq!(reader.read(&mut buf).expect("read"), 3);
+    assert_eq!(&buf, b"hel");
+    assert_eq!(reader.read(&mut buf).expect("read"), 2);
+    assert_eq!(&buf[..2], b"lo");
+    assert_eq!(reader.read(&mut []).expect("read"), 0);
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).expect("read to end");
+    assert_eq!(rest, ", world");
+
+    // The peer is gone, so subsequent reads hit EOF
+    assert_eq!(reader.read(&mut buf).expect("read"), 0);
+    writer.join().expect("join writer");
+}
+
+#[test]
+fn lines() {
+    let (tx, rx) = tokio::io::duplex(64);
+    let reader = BlockingBufReader::new(BufReader::new(rx));
+    let writer = write_chunks(
+        tx,
+        &[b"fir", b"st\r", b"\nsecond\nth", b"ird\r\n", b"\nlast"],
+    );
+
+    let lines = reader
+        .lines()
+        .collect::<io::Result<Vec<_>>>()
+        .expect("read lines");
+    assert_eq!(lines, ["first", "second", "third", "", "last"]);
+
+    writer.join().expect("join writer");
+}
+
+#[test]
+fn backpressure() {
+    // Only 4 bytes fit in the pipe at once, so writes park until the peer catches up
+    let (tx, mut rx) = tokio::io::duplex(4);
+    let reader = peer(move |rt| {
+        let mut received = Vec::new();
+        rt.block_on(async {
+            let mut buf = [0; 3];
+            loop {
+                thread::sleep(Duration::from_millis(1));
+                match rx.read(&mut buf).await.expect("read") {
+                    0 => break,
+                    n => received.extend_from_slice(&buf[..n]),
+                }
+            }
+        });
+        received
+    });
+
+    let mut writer = BlockingWriter::new(tx);
+    let data = (0..200u8).collect::<Vec<_>>();
+    assert!(writer.write(&data).expect("write") <= 4);
+    assert_eq!(writer.write(&[]).expect("write"), 0);
+    writer.write_all(&data).expect("write all");
+    writer.flush().expect("flush");
+
+    // Shutting down the writer ends the stream for the peer
+    writer.close().expect("close");
+    let received = reader.join().expect("join reader");
+    assert!(received.ends_with(&data));
+}
+
+#[test]
+fn seek() {
+    let mut reader = BlockingReader::new(io::Cursor::new(b"hello, world"));
+
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).expect("read");
+    assert_eq!(reader.seek(SeekFrom::Current(-3)).expect("seek"), 2);
+    reader.read_exact(&mut buf).expect("read");
+    assert_eq!(&buf, b"llo, ");
+    assert_eq!(reader.stream_position().expect("position"), 7);
+}
+
+#[test]
+fn read_timeout() {
+    let (tx, rx) = tokio::io::duplex(64);
+    let mut reader = BlockingReader::new(rx).with_timeout(Duration::from_millis(50));
+
+    // Nothing has been written yet
+    let err = reader.read(&mut [0; 8]).expect_err("read times out");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    // The adapter is still usable after a timeout
+    let writer = write_chunks(tx, &[b"hello"]);
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 5);
+    assert_eq!(&buf[..5], b"hello");
+    writer.join().expect("join writer");
+}
+
+#[test]
+fn write_vectored() {
+    // Records the lengths of the buffers passed to each write
+    #[derive(Default)]
+    struct Vectored {
+        layouts: Vec<Vec<usize>>,
+    }
+
+    impl tokio::io::AsyncWrite for Vectored {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.layouts.push(vec![buf.len()]);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            bufs: &[io::IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            self.layouts
+                .push(bufs.iter().map(|buf| buf.len()).collect());
+            Poll::Ready(Ok(bufs.iter().map(|buf| buf.len()).sum()))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    let bufs = [
+        io::IoSlice::new(b"ab"),
+        io::IoSlice::new(b""),
+        io::IoSlice::new(b"cde"),
+    ];
+    let mut writer = BlockingWriter::new(Vectored::default());
+    assert_eq!(writer.write_vectored(&bufs).expect("write"), 5);
+    assert_eq!(writer.into_inner().layouts, [vec![2, 0, 3]]);
+}
+
+#[test]
+fn drop_shuts_down() {
+    let (tx, mut rx) = tokio::io::duplex(64);
+    let mut writer = BlockingWriter::new(tokio::io::BufWriter::new(tx));
+    writer.write_all(b"hello").expect("write all");
+    drop(writer);
+
+    // The buffered data arrives, followed by EOF
+    let reader = peer(move |rt| {
+        let mut received = Vec::new();
+        rt.block_on(rx.read_to_end(&mut received))
+            .expect("read to end");
+        received
+    });
+    assert_eq!(reader.join().expect("join reader"), b"hello");
+}
+
+// Needs a wake to finish each seek
+#[derive(Default)]
+struct SlowSeek {
+    pos: u64,
+    target: Option<u64>,
+    woken: bool,
+    seeks: usize,
+}
+
+impl tokio::io::AsyncSeek for SlowSeek {
+    fn start_seek(mut self: Pin<&mut Self>, pos: SeekFrom) -> io::Result<()> {
+        match pos {
+            SeekFrom::Start(pos) => self.target = Some(pos),
+            _ => unimplemented!(),
+        }
+        self.seeks += 1;
+        Ok(())
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = &mut *self;
+        match this.target {
+            Some(target) if std::mem::take(&mut this.woken) => {
+                this.pos = target;
+                this.target = None;
+                Poll::Ready(Ok(target))
+            }
+            Some(_) => {
+                this.woken = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            None => Poll::Ready(Ok(this.pos)),
+        }
+    }
+}
+
+#[test]
+fn nonblocking() {
+    let (tx, rx) = tokio::io::duplex(4);
+    let mut reader = BlockingReader::new(rx);
+    reader.set_nonblocking(true);
+    assert!(reader.is_nonblocking() && reader.ready());
+
+    // Nothing has been written yet
+    let err = reader.read(&mut [0; 8]).expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(!reader.ready());
+    assert!(!reader.wait_readable(Some(Duration::from_millis(20))));
+
+    // Writing to the pipe wakes the reader
+    let writer = write_chunks(tx, &[b"hi"]);
+    assert!(reader.wait_readable(None));
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).expect("read"), 2);
+    assert_eq!(&buf[..2], b"hi");
+    writer.join().expect("join writer");
+
+    // Writers work the same way, once the pipe is full
+    let (tx, mut rx) = tokio::io::duplex(4);
+    let mut writer = BlockingWriter::new(tx);
+    writer.set_nonblocking(true);
+    assert_eq!(writer.write(b"hello").expect("write"), 4);
+    let err = writer.write(b"o").expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    let reader = peer(move |rt| {
+        thread::sleep(Duration::from_millis(10));
+        let mut buf = [0; 4];
+        rt.block_on(rx.read_exact(&mut buf)).expect("read");
+        (buf, rx)
+    });
+    assert!(writer.wait_writable(None));
+    assert_eq!(writer.write(b"o").expect("write"), 1);
+    let (received, _rx) = reader.join().expect("join reader");
+    assert_eq!(&received, b"hell");
+
+    // A seek that would block is finished by retrying it, rather than started again
+    let mut seeker = BlockingReader::new(SlowSeek::default());
+    seeker.set_nonblocking(true);
+    let err = seeker.seek(SeekFrom::Start(3)).expect_err("would block");
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(seeker.ready());
+    assert_eq!(seeker.seek(SeekFrom::Start(3)).expect("seek"), 3);
+    assert_eq!(seeker.get_ref().seeks, 1);
+}
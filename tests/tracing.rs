@@ -0,0 +1,69 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tracing::{
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+// A subscriber that counts what it sees
+#[derive(Default)]
+struct Counts {
+    spans: AtomicUsize,
+    enters: AtomicUsize,
+    exits: AtomicUsize,
+    wakeups: AtomicUsize,
+}
+
+struct Counter(Arc<Counts>);
+
+impl Subscriber for Counter {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        assert_eq!(attrs.metadata().name(), "block_on");
+        Id::from_u64(self.0.spans.fetch_add(1, Ordering::Relaxed) as u64 + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {
+        self.0.wakeups.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn enter(&self, _: &Id) {
+        self.0.enters.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn exit(&self, _: &Id) {
+        self.0.exits.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn spans_and_wakeups() {
+    let counts = Arc::new(Counts::default());
+    let dispatch = tracing::Dispatch::new(Counter(Arc::clone(&counts)));
+
+    // The wake happens on the timer's thread, so the subscriber needs to be global to see it
+    tracing::dispatcher::set_global_default(dispatch).expect("set subscriber");
+    pollster::block_on(futures_timer::Delay::new(Duration::from_millis(10)));
+
+    assert_eq!(counts.spans.load(Ordering::Relaxed), 1);
+    // Polled at least once before the delay elapsed, and again afterwards
+    let enters = counts.enters.load(Ordering::Relaxed);
+    assert!(enters >= 2);
+    assert_eq!(counts.exits.load(Ordering::Relaxed), enters);
+    assert!(counts.wakeups.load(Ordering::Relaxed) >= 1);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "spawn")]
+
+use std::{
+    panic, thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn unblock() {
+    let caller = thread::current().id();
+
+    let then = Instant::now();
+    let worker = pollster::block_on(pollster::unblock(|| {
+        thread::sleep(Duration::from_millis(50));
+        thread::current().id()
+    }));
+    assert!(then.elapsed() >= Duration::from_millis(50));
+    assert_ne!(worker, caller);
+
+    // Several at once run in parallel, rather than one after the other
+    let then = Instant::now();
+    let futs = (0..4)
+        .map(|i| {
+            pollster::unblock(move || {
+                thread::sleep(Duration::from_millis(100));
+                i
+            })
+        })
+        .collect::<Vec<_>>();
+    let outputs = futs.into_iter().map(pollster::block_on).collect::<Vec<_>>();
+    assert_eq!(outputs, [0, 1, 2, 3]);
+    assert!(then.elapsed() < Duration::from_millis(400));
+}
+
+#[test]
+fn unblock_panic() {
+    let fut = pollster::unblock(|| panic!("oh no"));
+    let result = panic::catch_unwind(|| pollster::block_on(fut));
+    assert!(result.is_err());
+}